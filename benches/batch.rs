@@ -0,0 +1,87 @@
+// Compares generating events one at a time with generating them in a batch.
+//
+// The benchmarks run against a headless X server so Xvfb must be installed.
+// They are skipped otherwise.
+
+#[cfg(target_os = "linux")]
+#[allow(dead_code)]
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+#[cfg(target_os = "linux")]
+use common::HeadlessSession;
+#[cfg(target_os = "linux")]
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+#[cfg(target_os = "linux")]
+use tfc::{traits::*, Context, Key};
+
+#[cfg(target_os = "linux")]
+const EVENT_COUNTS: [i32; 2] = [10, 100];
+
+#[cfg(target_os = "linux")]
+fn mouse_moves(ctx: &mut Context, count: i32) -> Result<(), tfc::Error> {
+    for i in 0..count {
+        ctx.mouse_move_abs(i, i)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn key_clicks(ctx: &mut Context, count: i32) -> Result<(), tfc::Error> {
+    for _ in 0..count {
+        ctx.key_click(Key::Shift)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn bench_batch(c: &mut Criterion) {
+    let session = match HeadlessSession::start() {
+        Some(session) => session,
+        None => {
+            eprintln!("Xvfb is not installed, skipping benchmarks");
+            return;
+        }
+    };
+    let mut ctx = session.context().unwrap();
+
+    let mut group = c.benchmark_group("mouse_move_abs");
+    for count in EVENT_COUNTS {
+        group.bench_with_input(BenchmarkId::new("single", count), &count, |b, count| {
+            b.iter(|| mouse_moves(&mut ctx, *count).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &count, |b, count| {
+            b.iter(|| ctx.batch(|ctx| mouse_moves(ctx, *count)).unwrap())
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("key_click");
+    for count in EVENT_COUNTS {
+        group.bench_with_input(BenchmarkId::new("single", count), &count, |b, count| {
+            b.iter(|| key_clicks(&mut ctx, *count).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &count, |b, count| {
+            b.iter(|| ctx.batch(|ctx| key_clicks(ctx, *count)).unwrap())
+        });
+    }
+    group.finish();
+
+    let text = "The quick brown fox jumps over the lazy dog";
+    let mut group = c.benchmark_group("unicode_string");
+    group.bench_function("single", |b| b.iter(|| ctx.unicode_string(text).unwrap()));
+    group.bench_function("batch", |b| {
+        b.iter(|| ctx.batch(|ctx| ctx.unicode_string(text)).unwrap())
+    });
+    group.finish();
+}
+
+#[cfg(target_os = "linux")]
+criterion_group!(benches, bench_batch);
+#[cfg(target_os = "linux")]
+criterion_main!(benches);
+
+// The benchmarks need an X server. The harness is disabled so the target
+// needs a main function on the other platforms too.
+#[cfg(not(target_os = "linux"))]
+fn main() {}
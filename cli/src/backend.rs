@@ -0,0 +1,141 @@
+use std::{thread, time::Duration};
+use tfc::{
+    traits::*, Command, Completion, ContextBuilder, DynContext, DynError, DynExtendedContext,
+    Executor,
+};
+
+/// Where the commands are sent.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Backend {
+    /// The context of the platform (X11 on Linux).
+    Native,
+    /// The context that uses `/dev/uinput` (Linux).
+    #[cfg(all(target_os = "linux", feature = "uinput"))]
+    Uinput,
+    /// Print the commands instead of executing them.
+    Print,
+}
+
+impl Backend {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "native" => Some(Backend::Native),
+            #[cfg(all(target_os = "linux", feature = "uinput"))]
+            "uinput" => Some(Backend::Uinput),
+            "print" => Some(Backend::Print),
+            _ => None,
+        }
+    }
+}
+
+// The context of a backend. The backend is chosen at runtime so the context is
+// a trait object. Only some contexts support the extended commands.
+enum BackendContext {
+    Extended(Box<dyn DynExtendedContext>),
+    // Only the uinput backend is basic on Linux.
+    #[cfg_attr(all(target_os = "linux", not(feature = "uinput")), allow(dead_code))]
+    Basic(Box<dyn DynContext>),
+}
+
+impl BackendContext {
+    fn execute(
+        &mut self,
+        executor: &mut Executor,
+        commands: &[Command],
+    ) -> Result<Completion, DynError> {
+        match self {
+            BackendContext::Extended(ctx) => executor.execute_extended(&mut **ctx, commands),
+            BackendContext::Basic(ctx) => executor.execute_dyn(&mut **ctx, commands),
+        }
+    }
+
+    fn screen(&mut self) -> &mut dyn DynContext {
+        match self {
+            BackendContext::Extended(ctx) => ctx.as_dyn_context(),
+            BackendContext::Basic(ctx) => &mut **ctx,
+        }
+    }
+}
+
+pub struct Target {
+    pub backend: Backend,
+    pub builder: ContextBuilder,
+}
+
+impl Target {
+    fn context(&self) -> Result<BackendContext, String> {
+        let ctx = match self.backend {
+            #[cfg(target_os = "linux")]
+            Backend::Native => {
+                BackendContext::Extended(Box::new(self.builder.build().map_err(|e| e.to_string())?))
+            }
+            #[cfg(not(target_os = "linux"))]
+            Backend::Native => {
+                BackendContext::Basic(Box::new(self.builder.build().map_err(|e| e.to_string())?))
+            }
+            #[cfg(all(target_os = "linux", feature = "uinput"))]
+            Backend::Uinput => BackendContext::Basic(Box::new(
+                self.builder.build_uinput().map_err(|e| e.to_string())?,
+            )),
+            Backend::Print => return Err("The print backend doesn't have a context".to_owned()),
+        };
+        // For OS-specific reasons, it's necessary to wait a moment after
+        // creating the context before generating events.
+        thread::sleep(Duration::from_millis(10));
+        Ok(ctx)
+    }
+
+    /// Execute a sequence of commands.
+    ///
+    /// If `abortable` is set, pressing Escape three times stops the commands
+    /// (Linux only).
+    pub fn execute(&self, commands: &[Command], abortable: bool) -> Result<(), String> {
+        if self.backend == Backend::Print {
+            for command in commands.iter() {
+                println!("{:?}", command);
+            }
+            return Ok(());
+        }
+
+        let mut ctx = self.context()?;
+        let mut executor = Executor::new();
+
+        #[cfg(target_os = "linux")]
+        let _kill_switch = if abortable {
+            tfc::KillSwitch::x11(&self.builder, executor.abort_handle()).ok()
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "linux"))]
+        let _ = abortable;
+
+        match ctx
+            .execute(&mut executor, commands)
+            .map_err(|e| e.to_string())?
+        {
+            Completion::Finished => Ok(()),
+            Completion::Aborted => Err("Aborted".to_owned()),
+        }
+    }
+
+    fn screen_context(&self) -> Result<BackendContext, String> {
+        match self.backend {
+            Backend::Print => Err("The print backend can't query the screen".to_owned()),
+            _ => self.context(),
+        }
+    }
+
+    pub fn cursor_location(&self) -> Result<(i32, i32), String> {
+        self.screen_context()?
+            .screen()
+            .cursor_location()
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn screen_size(&self) -> Result<(i32, i32), String> {
+        self.screen_context()?
+            .screen()
+            .screen_size()
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,322 @@
+use std::str::FromStr;
+use tfc::{Command, Enum, Hotkey, MouseButton};
+
+// Each click is a command so the count is limited.
+const MAX_REPEAT: u32 = 1000;
+
+fn parse_number<T: FromStr>(name: &str, arg: Option<&String>) -> Result<T, String> {
+    match arg {
+        Some(arg) => arg
+            .parse()
+            .map_err(|_| format!("Expected {}, found \"{}\"", name, arg)),
+        None => Err(format!("Expected {}", name)),
+    }
+}
+
+fn parse_button(name: &str) -> Result<MouseButton, String> {
+    match name {
+        "1" => return Ok(MouseButton::Left),
+        "2" => return Ok(MouseButton::Middle),
+        "3" => return Ok(MouseButton::Right),
+        _ => {}
+    }
+    MouseButton::iter()
+        .find(|button| button.identifier_name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Expected mouse button, found \"{}\"", name))
+}
+
+fn no_more_args(args: &mut std::slice::Iter<String>) -> Result<(), String> {
+    match args.next() {
+        Some(arg) => Err(format!("Unexpected argument \"{}\"", arg)),
+        None => Ok(()),
+    }
+}
+
+/// `type [--delay <ms>] [--] <text>...`
+///
+/// The arguments are joined with spaces. The arguments after `--` are text
+/// even if they look like options. The delay is returned separately because
+/// it's a property of the context.
+pub fn type_text(args: &[String]) -> Result<(Vec<Command>, Option<u32>), String> {
+    let mut delay = None;
+    let mut words = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--delay" => delay = Some(parse_number("delay", args.next())?),
+            "--" => words.extend(args.by_ref().map(String::as_str)),
+            _ => words.push(arg.as_str()),
+        }
+    }
+
+    if words.is_empty() {
+        return Err("Expected text".to_owned());
+    }
+    Ok((vec![Command::UnicodeString(words.join(" "))], delay))
+}
+
+/// `key <hotkey>...`
+///
+/// Each hotkey presses its modifiers, clicks the key and then releases the
+/// modifiers in reverse.
+pub fn key(args: &[String]) -> Result<Vec<Command>, String> {
+    if args.is_empty() {
+        return Err("Expected key".to_owned());
+    }
+
+    let mut commands = Vec::new();
+    for arg in args.iter() {
+        let hotkey = arg.parse::<Hotkey>().map_err(|e| e.to_string())?;
+        let modifiers = hotkey.modifiers.keys();
+        commands.extend(modifiers.iter().map(|key| Command::KeyDown(*key)));
+        commands.push(Command::KeyClick(hotkey.key));
+        commands.extend(modifiers.iter().rev().map(|key| Command::KeyUp(*key)));
+    }
+    Ok(commands)
+}
+
+/// `mousemove [--relative] <x> <y>`
+pub fn mouse_move(args: &[String]) -> Result<Vec<Command>, String> {
+    let relative = args.iter().any(|arg| arg == "--relative");
+    let positional = args
+        .iter()
+        .filter(|arg| *arg != "--relative")
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut args = positional.iter();
+
+    let x = parse_number("x", args.next())?;
+    let y = parse_number("y", args.next())?;
+    no_more_args(&mut args)?;
+
+    Ok(vec![if relative {
+        Command::MouseMoveRel(x, y)
+    } else {
+        Command::MouseMoveAbs(x, y)
+    }])
+}
+
+/// `click [--repeat <n>] [--delay <ms>] [button]`
+pub fn click(args: &[String]) -> Result<Vec<Command>, String> {
+    let mut button = None;
+    let mut repeat: u32 = 1;
+    let mut delay = 0;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--repeat" => repeat = parse_number("repeat count", args.next())?,
+            "--delay" => delay = parse_number("delay", args.next())?,
+            _ if button.is_none() => button = Some(parse_button(arg)?),
+            _ => return Err(format!("Unexpected argument \"{}\"", arg)),
+        }
+    }
+
+    if repeat == 0 || repeat > MAX_REPEAT {
+        return Err(format!(
+            "Expected a repeat count between 1 and {}, found {}",
+            MAX_REPEAT, repeat
+        ));
+    }
+
+    let button = button.unwrap_or(MouseButton::Left);
+    let mut commands = Vec::new();
+    for i in 0..repeat {
+        if i != 0 && delay != 0 {
+            commands.push(Command::Delay(delay));
+        }
+        commands.push(Command::MouseClick(button));
+    }
+    Ok(commands)
+}
+
+/// `scroll <dx> <dy>`
+pub fn scroll(args: &[String]) -> Result<Vec<Command>, String> {
+    let mut args = args.iter();
+    let dx = parse_number("dx", args.next())?;
+    let dy = parse_number("dy", args.next())?;
+    no_more_args(&mut args)?;
+    Ok(vec![Command::MouseScroll(dx, dy)])
+}
+
+/// `run <file>`
+///
+/// The file is a TFC script. `-` reads the script from stdin.
+pub fn script(args: &[String]) -> Result<String, String> {
+    let mut args = args.iter();
+    let path = match args.next() {
+        Some(path) => path,
+        None => return Err("Expected script path".to_owned()),
+    };
+    no_more_args(&mut args)?;
+    Ok(path.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfc::Key;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn type_text_joins_words() {
+        assert_eq!(
+            type_text(&args(&["Hello", "world!"])),
+            Ok((
+                vec![Command::UnicodeString("Hello world!".to_owned())],
+                None
+            ))
+        );
+        assert_eq!(
+            type_text(&args(&["--delay", "10", "slow"])),
+            Ok((vec![Command::UnicodeString("slow".to_owned())], Some(10)))
+        );
+        assert_eq!(
+            type_text(&args(&["--", "--delay", "10"])),
+            Ok((vec![Command::UnicodeString("--delay 10".to_owned())], None))
+        );
+    }
+
+    #[test]
+    fn type_text_errors() {
+        assert_eq!(type_text(&[]), Err("Expected text".to_owned()));
+        assert_eq!(
+            type_text(&args(&["--delay"])),
+            Err("Expected delay".to_owned())
+        );
+        assert_eq!(
+            type_text(&args(&["--delay", "soon", "text"])),
+            Err("Expected delay, found \"soon\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn key_presses_modifiers() {
+        assert_eq!(
+            key(&args(&["ctrl+shift+s", "returnorenter"])),
+            Ok(vec![
+                Command::KeyDown(Key::Control),
+                Command::KeyDown(Key::Shift),
+                Command::KeyClick(Key::S),
+                Command::KeyUp(Key::Shift),
+                Command::KeyUp(Key::Control),
+                Command::KeyClick(Key::ReturnOrEnter),
+            ])
+        );
+    }
+
+    #[test]
+    fn key_errors() {
+        assert_eq!(key(&[]), Err("Expected key".to_owned()));
+        assert!(key(&args(&["ctrl+nokey"])).is_err());
+        assert!(key(&args(&["hyper+a"])).is_err());
+    }
+
+    #[test]
+    fn mouse_move_absolute_and_relative() {
+        assert_eq!(
+            mouse_move(&args(&["100", "200"])),
+            Ok(vec![Command::MouseMoveAbs(100, 200)])
+        );
+        assert_eq!(
+            mouse_move(&args(&["--relative", "-10", "0"])),
+            Ok(vec![Command::MouseMoveRel(-10, 0)])
+        );
+        assert_eq!(
+            mouse_move(&args(&["-10", "0", "--relative"])),
+            Ok(vec![Command::MouseMoveRel(-10, 0)])
+        );
+    }
+
+    #[test]
+    fn mouse_move_errors() {
+        assert_eq!(mouse_move(&[]), Err("Expected x".to_owned()));
+        assert_eq!(mouse_move(&args(&["1"])), Err("Expected y".to_owned()));
+        assert_eq!(
+            mouse_move(&args(&["1", "two"])),
+            Err("Expected y, found \"two\"".to_owned())
+        );
+        assert_eq!(
+            mouse_move(&args(&["1", "2", "3"])),
+            Err("Unexpected argument \"3\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn click_buttons_and_repeat() {
+        assert_eq!(click(&[]), Ok(vec![Command::MouseClick(MouseButton::Left)]));
+        assert_eq!(
+            click(&args(&["3"])),
+            Ok(vec![Command::MouseClick(MouseButton::Right)])
+        );
+        assert_eq!(
+            click(&args(&["middle", "--repeat", "2", "--delay", "50"])),
+            Ok(vec![
+                Command::MouseClick(MouseButton::Middle),
+                Command::Delay(50),
+                Command::MouseClick(MouseButton::Middle),
+            ])
+        );
+        assert_eq!(
+            click(&args(&["--repeat", "2", "left"])),
+            Ok(vec![
+                Command::MouseClick(MouseButton::Left),
+                Command::MouseClick(MouseButton::Left),
+            ])
+        );
+    }
+
+    #[test]
+    fn click_errors() {
+        assert_eq!(
+            click(&args(&["4"])),
+            Err("Expected mouse button, found \"4\"".to_owned())
+        );
+        assert_eq!(
+            click(&args(&["left", "right"])),
+            Err("Unexpected argument \"right\"".to_owned())
+        );
+        assert_eq!(
+            click(&args(&["--repeat"])),
+            Err("Expected repeat count".to_owned())
+        );
+        assert_eq!(
+            click(&args(&["--repeat", "-1"])),
+            Err("Expected repeat count, found \"-1\"".to_owned())
+        );
+        assert_eq!(
+            click(&args(&["--repeat", "0"])),
+            Err("Expected a repeat count between 1 and 1000, found 0".to_owned())
+        );
+        assert_eq!(
+            click(&args(&["--repeat", "4000000000"])),
+            Err("Expected a repeat count between 1 and 1000, found 4000000000".to_owned())
+        );
+        assert_eq!(
+            click(&args(&["--delay", "-1"])),
+            Err("Expected delay, found \"-1\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn scroll_by_offset() {
+        assert_eq!(
+            scroll(&args(&["0", "-3"])),
+            Ok(vec![Command::MouseScroll(0, -3)])
+        );
+    }
+
+    #[test]
+    fn scroll_errors() {
+        assert_eq!(scroll(&[]), Err("Expected dx".to_owned()));
+        assert_eq!(scroll(&args(&["1"])), Err("Expected dy".to_owned()));
+        assert_eq!(
+            scroll(&args(&["1", "2", "3"])),
+            Err("Unexpected argument \"3\"".to_owned())
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use serde_json::json;
+use tfc::{ContextBuilder, Diagnostic, DiagnosticStatus};
+
+const USAGE: &str = "\
+Usage: tfc doctor [options]
+
+Checks the environment for problems that stop TFC from working and suggests
+how to fix them. The exit code is 1 if events can't be generated.
+
+Options:
+  --json  Print the results as JSON (useful for bug reports)
+
+The global --display option sets the X display to check.";
+
+struct Options {
+    json: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options { json: false };
+
+    for arg in args.iter() {
+        match arg.as_str() {
+            "--json" => options.json = true,
+            _ => return Err(format!("Unexpected argument \"{}\"", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn print_text(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics.iter() {
+        let status = format!("[{}]", diagnostic.status);
+        println!(
+            "{:<10} {}: {}",
+            status, diagnostic.check, diagnostic.message
+        );
+        if let Some(fix) = &diagnostic.fix {
+            println!("{:<10} Fix: {}", "", fix);
+        }
+    }
+}
+
+fn print_json(diagnostics: &[Diagnostic]) {
+    let diagnostics = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            json!({
+                "check": diagnostic.check,
+                "status": diagnostic.status.as_str(),
+                "message": diagnostic.message,
+                "fix": diagnostic.fix,
+            })
+        })
+        .collect::<Vec<_>>();
+    let report = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "diagnostics": diagnostics,
+    });
+    println!("{:#}", report);
+}
+
+pub fn run(args: &[String], builder: &ContextBuilder) -> i32 {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return 0;
+    }
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let diagnostics = tfc::diagnose(builder);
+
+    if options.json {
+        print_json(&diagnostics);
+    } else {
+        print_text(&diagnostics);
+    }
+
+    let failed = diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.status == DiagnosticStatus::Error);
+    if failed {
+        1
+    } else {
+        0
+    }
+}
@@ -0,0 +1,263 @@
+mod backend;
+mod commands;
+mod doctor;
+
+use backend::{Backend, Target};
+use std::{io::Read, process, time::Duration};
+use tfc::{Command, ContextBuilder};
+
+const USAGE: &str = "\
+Usage: tfc [options] <command> [arguments]
+
+Commands:
+  type [--delay <ms>] [--] <text>... Type some text
+  key <hotkey>...                    Press key combinations such as ctrl+s
+  mousemove [--relative] <x> <y>     Move the mouse to a position or by an offset
+  click [--repeat <n>] [--delay <ms>] [button]
+                                     Click a mouse button (left by default)
+  scroll <dx> <dy>                   Scroll the mouse wheel
+  getmouselocation                   Print the position of the mouse
+  screensize                         Print the size of the screen
+  run <file>                         Run a TFC script (- reads from stdin)
+  xdotool <arguments>...             Run the arguments of an xdotool invocation
+  doctor                             Check the environment for problems that
+                                     stop TFC from working
+
+Options:
+  --backend <name>  native sends events to the platform (the default),
+                    uinput sends them to /dev/uinput (Linux, built with the
+                    uinput feature) and print prints the commands instead
+  --display <name>  The X display to connect to
+
+The exit code is 0 on success, 1 if the command failed and 2 if the arguments
+are invalid. Run tfc doctor --help for the options of doctor.";
+
+// Remove the global options from the arguments. The options must come before
+// the command so that the arguments of the command are left untouched.
+fn extract_options(args: &mut Vec<String>) -> Result<Target, String> {
+    let mut target = Target {
+        backend: Backend::Native,
+        builder: ContextBuilder::new(),
+    };
+    let mut args_iter = std::mem::take(args).into_iter().peekable();
+
+    while let Some(arg) = args_iter.next_if(|arg| arg == "--backend" || arg == "--display") {
+        match arg.as_str() {
+            "--backend" => match args_iter.next() {
+                Some(name) => match Backend::from_name(&name) {
+                    Some(backend) => target.backend = backend,
+                    None => return Err(format!("Unknown backend \"{}\"", name)),
+                },
+                None => return Err("Expected backend name after --backend".to_owned()),
+            },
+            _ => match args_iter.next() {
+                Some(display) => target.builder = target.builder.display(&display),
+                None => return Err("Expected display name after --display".to_owned()),
+            },
+        }
+    }
+
+    *args = args_iter.collect();
+    Ok(target)
+}
+
+fn read_script(path: &str) -> Result<String, String> {
+    let mut script = String::new();
+    let result = if path == "-" {
+        std::io::stdin().read_to_string(&mut script).map(|_| ())
+    } else {
+        std::fs::File::open(path).and_then(|mut file| file.read_to_string(&mut script).map(|_| ()))
+    };
+    result.map_err(|e| format!("Error reading \"{}\": {}", path, e))?;
+    Ok(script)
+}
+
+fn parse_script(script: &str) -> Result<Vec<Command>, String> {
+    tfc_interpreter::parse_tokens(script.split_ascii_whitespace())
+        .map_err(|e| format!("Error parsing script: {}", e))
+}
+
+// Each command returns the exit code. 0 is success, 1 is failure and 2 is
+// invalid usage.
+fn run(command: &str, args: &[String], mut target: Target) -> i32 {
+    // Only the first argument asks for help. The others, including those
+    // after --, are left to the command.
+    let help = matches!(args.first(), Some(arg) if arg == "-h" || arg == "--help");
+    if command != "doctor" && help {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    let parsed = match command {
+        "type" => match commands::type_text(args) {
+            Ok((commands, Some(delay))) => {
+                target.builder = target
+                    .builder
+                    .key_delay(Duration::from_millis(delay.into()));
+                Ok((commands, false))
+            }
+            Ok((commands, None)) => Ok((commands, false)),
+            Err(e) => Err(e),
+        },
+        "key" => commands::key(args).map(|commands| (commands, false)),
+        "mousemove" => commands::mouse_move(args).map(|commands| (commands, false)),
+        "click" => commands::click(args).map(|commands| (commands, false)),
+        "scroll" => commands::scroll(args).map(|commands| (commands, false)),
+        "run" => match commands::script(args) {
+            Ok(path) => match read_script(&path).and_then(|script| parse_script(&script)) {
+                Ok(commands) => Ok((commands, true)),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return 1;
+                }
+            },
+            Err(e) => Err(e),
+        },
+        "xdotool" => match tfc_interpreter::parse_xdotool(args.iter().map(String::as_str)) {
+            Ok(commands) => Ok((commands, false)),
+            Err(e) => Err(e.to_string()),
+        },
+        "getmouselocation" | "screensize" => {
+            if let Some(arg) = args.first() {
+                eprintln!("Unexpected argument \"{}\"\n\n{}", arg, USAGE);
+                return 2;
+            }
+            let result = if command == "getmouselocation" {
+                target
+                    .cursor_location()
+                    .map(|(x, y)| format!("x:{} y:{}", x, y))
+            } else {
+                target.screen_size().map(|(w, h)| format!("{} {}", w, h))
+            };
+            return match result {
+                Ok(output) => {
+                    println!("{}", output);
+                    0
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    1
+                }
+            };
+        }
+        "doctor" => return doctor::run(args, &target.builder),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        _ => {
+            eprintln!("Unknown command \"{}\"\n\n{}", command, USAGE);
+            return 2;
+        }
+    };
+
+    let (commands, abortable) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    match target.execute(&commands, abortable) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let target = match extract_options(&mut args) {
+        Ok(target) => target,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    if args.is_empty() {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    }
+
+    let command = args.remove(0);
+    process::exit(run(&command, &args, target));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn print_target() -> Target {
+        Target {
+            backend: Backend::Print,
+            builder: ContextBuilder::new(),
+        }
+    }
+
+    #[test]
+    fn options_before_the_command() {
+        let mut arguments = args(&["--backend", "print", "--display", ":1", "type", "hi"]);
+        let target = extract_options(&mut arguments).unwrap();
+        assert_eq!(target.backend, Backend::Print);
+        assert!(format!("{:?}", target.builder).contains("Some(\":1\")"));
+        assert_eq!(arguments, args(&["type", "hi"]));
+    }
+
+    #[test]
+    fn options_after_the_command_are_arguments() {
+        let mut arguments = args(&["type", "--backend", "print", "--", "text"]);
+        let target = extract_options(&mut arguments).unwrap();
+        assert_eq!(target.backend, Backend::Native);
+        assert_eq!(
+            arguments,
+            args(&["type", "--backend", "print", "--", "text"])
+        );
+    }
+
+    #[test]
+    fn invalid_options() {
+        assert_eq!(
+            extract_options(&mut args(&["--backend", "nope", "type"])).err(),
+            Some("Unknown backend \"nope\"".to_owned())
+        );
+        assert_eq!(
+            extract_options(&mut args(&["--display"])).err(),
+            Some("Expected display name after --display".to_owned())
+        );
+    }
+
+    #[test]
+    fn help_is_only_the_first_argument() {
+        assert_eq!(run("key", &args(&["--help"]), print_target()), 0);
+        // Otherwise, -h is passed to the command which doesn't accept it.
+        assert_eq!(run("key", &args(&["a", "-h"]), print_target()), 2);
+        assert_eq!(run("key", &args(&["--", "-h"]), print_target()), 2);
+        assert_eq!(run("type", &args(&["--", "-h"]), print_target()), 0);
+    }
+
+    #[test]
+    fn invalid_arguments_exit_with_2() {
+        assert_eq!(run("type", &[], print_target()), 2);
+        assert_eq!(run("key", &args(&["nokey"]), print_target()), 2);
+        assert_eq!(run("mousemove", &args(&["1"]), print_target()), 2);
+        assert_eq!(run("click", &args(&["9"]), print_target()), 2);
+        assert_eq!(run("scroll", &args(&["a", "b"]), print_target()), 2);
+        assert_eq!(run("screensize", &args(&["x"]), print_target()), 2);
+        assert_eq!(run("unknown", &[], print_target()), 2);
+    }
+
+    #[test]
+    fn valid_arguments_exit_with_0() {
+        assert_eq!(run("type", &args(&["hi"]), print_target()), 0);
+        assert_eq!(run("key", &args(&["ctrl+a"]), print_target()), 0);
+        assert_eq!(run("mousemove", &args(&["1", "2"]), print_target()), 0);
+        assert_eq!(run("click", &[], print_target()), 0);
+        assert_eq!(run("scroll", &args(&["0", "1"]), print_target()), 0);
+    }
+}
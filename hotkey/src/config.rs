@@ -0,0 +1,79 @@
+use serde::Deserialize;
+use std::{fs, path::Path};
+use tfc::{Command, Hotkey};
+
+#[derive(Deserialize)]
+struct RawConfig {
+    #[serde(default)]
+    evdev: bool,
+    display: Option<String>,
+    #[serde(default)]
+    hotkey: Vec<RawHotkey>,
+}
+
+#[derive(Deserialize)]
+struct RawHotkey {
+    keys: String,
+    text: Option<String>,
+    script: Option<String>,
+    file: Option<String>,
+}
+
+pub enum Action {
+    Text(String),
+    Commands(Vec<Command>),
+}
+
+pub struct Config {
+    pub evdev: bool,
+    pub display: Option<String>,
+    pub hotkeys: Vec<Hotkey>,
+    pub actions: Vec<Action>,
+}
+
+fn parse_script(script: &str) -> Result<Vec<Command>, String> {
+    tfc_interpreter::parse_tokens(script.split_ascii_whitespace()).map_err(|e| e.to_string())
+}
+
+fn parse_action(raw: RawHotkey, dir: &Path) -> Result<Action, String> {
+    match (raw.text, raw.script, raw.file) {
+        (Some(text), None, None) => Ok(Action::Text(text)),
+        (None, Some(script), None) => parse_script(&script).map(Action::Commands),
+        (None, None, Some(file)) => {
+            let path = dir.join(file);
+            let script = fs::read_to_string(&path)
+                .map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+            parse_script(&script).map(Action::Commands)
+        }
+        _ => Err("Expected exactly one of text, script or file".to_owned()),
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let raw: RawConfig = toml::from_str(&source).map_err(|e| e.to_string())?;
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+        let mut hotkeys = Vec::new();
+        let mut actions = Vec::new();
+        for raw_hotkey in raw.hotkey {
+            let keys = raw_hotkey.keys.clone();
+            let hotkey = keys.parse::<Hotkey>().map_err(|e| e.to_string())?;
+            if hotkeys.contains(&hotkey) {
+                return Err(format!("Hotkey \"{}\" is used more than once", keys));
+            }
+            let action = parse_action(raw_hotkey, dir)
+                .map_err(|e| format!("Hotkey \"{}\": {}", keys, e))?;
+            hotkeys.push(hotkey);
+            actions.push(action);
+        }
+
+        Ok(Self {
+            evdev: raw.evdev,
+            display: raw.display,
+            hotkeys,
+            actions,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+// The hotkey listener is only available on Linux.
+#![cfg_attr(not(target_os = "linux"), allow(dead_code, unused_imports))]
+
+mod config;
+
+use config::{Action, Config};
+use std::{env, path::PathBuf, process};
+#[cfg(target_os = "linux")]
+use tfc::HotkeyListener;
+use tfc::{traits::*, Context, ContextBuilder, Hotkey, Key};
+
+const USAGE: &str = "\
+Usage: tfc-hotkey [config]
+
+Performs the actions in the config file when their hotkeys are pressed. The
+default config file is $XDG_CONFIG_HOME/tfc/hotkeys.toml.";
+
+fn default_config_path() -> Option<PathBuf> {
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join("tfc").join("hotkeys.toml"))
+}
+
+// The modifiers of the hotkey are probably still held down when the action
+// starts. They would change the meaning of the keys that the action presses
+// so they're released first.
+fn release_modifiers(ctx: &mut Context, hotkey: &Hotkey) -> Result<(), tfc::Error> {
+    let right = |key| match key {
+        Key::Control => Key::RightControl,
+        Key::Shift => Key::RightShift,
+        Key::Alt => Key::RightAlt,
+        _ => Key::RightMeta,
+    };
+    for key in hotkey.modifiers.keys() {
+        ctx.key_up(key)?;
+        ctx.key_up(right(key))?;
+    }
+    Ok(())
+}
+
+fn perform(ctx: &mut Context, hotkey: &Hotkey, action: &Action) -> Result<(), tfc::Error> {
+    release_modifiers(ctx, hotkey)?;
+    match action {
+        Action::Text(text) => ctx.unicode_string(text),
+        Action::Commands(commands) => {
+            for command in commands {
+                command.execute_extended(ctx)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("tfc-hotkey is only supported on Linux");
+    process::exit(1);
+}
+
+#[cfg(target_os = "linux")]
+fn main() {
+    let mut args = env::args().skip(1);
+    let path = match args.next() {
+        Some(arg) if arg == "-h" || arg == "--help" => {
+            println!("{}", USAGE);
+            return;
+        }
+        Some(arg) => PathBuf::from(arg),
+        None => match default_config_path() {
+            Some(path) => path,
+            None => {
+                eprintln!("Couldn't find the config directory\n\n{}", USAGE);
+                process::exit(2);
+            }
+        },
+    };
+
+    let config = match Config::load(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    let mut builder = ContextBuilder::new();
+    if let Some(display) = &config.display {
+        builder = builder.display(display);
+    }
+
+    let mut ctx = match builder.build() {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            eprintln!("Error creating context: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let listener = if config.evdev {
+        HotkeyListener::evdev(&builder, &config.hotkeys).map_err(|e| e.to_string())
+    } else {
+        HotkeyListener::x11(&builder, &config.hotkeys).map_err(|e| e.to_string())
+    };
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error starting listener: {}", e);
+            process::exit(1);
+        }
+    };
+
+    while let Some(index) = listener.recv() {
+        let hotkey = &config.hotkeys[index];
+        let action = &config.actions[index];
+        if let Err(e) = perform(&mut ctx, hotkey, action) {
+            eprintln!("Error performing action for {}: {}", hotkey, e);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// The input listener is only available on Linux.
+#![cfg_attr(not(target_os = "linux"), allow(dead_code, unused_imports))]
+
+use serde::Deserialize;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process, thread,
+    time::Duration,
+};
+#[cfg(target_os = "linux")]
+use tfc::InputListener;
+use tfc::{ContextBuilder, Hotstring, HotstringEngine, InputEvent};
+
+const USAGE: &str = "\
+Usage: tfc-hotstring [config]
+
+Replaces the triggers in the config file with their expansions as they're
+typed. The default config file is $XDG_CONFIG_HOME/tfc/hotstrings.toml.";
+
+// How long to wait after typing an expansion for its events to reach the
+// listener.
+const SETTLE: Duration = Duration::from_millis(50);
+
+#[derive(Deserialize)]
+struct Config {
+    #[serde(default)]
+    evdev: bool,
+    display: Option<String>,
+    #[serde(default)]
+    hotstring: Vec<RawHotstring>,
+}
+
+#[derive(Deserialize)]
+struct RawHotstring {
+    trigger: String,
+    expansion: String,
+    #[serde(default)]
+    immediate: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    inside_word: bool,
+}
+
+impl From<RawHotstring> for Hotstring {
+    fn from(raw: RawHotstring) -> Self {
+        Self {
+            trigger: raw.trigger,
+            expansion: raw.expansion,
+            immediate: raw.immediate,
+            case_sensitive: raw.case_sensitive,
+            inside_word: raw.inside_word,
+        }
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join("tfc").join("hotstrings.toml"))
+}
+
+fn load_config(path: &Path) -> Result<Config, String> {
+    let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let config: Config = toml::from_str(&source).map_err(|e| e.to_string())?;
+    if let Some(raw) = config.hotstring.iter().find(|h| h.trigger.is_empty()) {
+        return Err(format!("Hotstring \"{}\" has an empty trigger", raw.expansion));
+    }
+    Ok(config)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("tfc-hotstring is only supported on Linux");
+    process::exit(1);
+}
+
+#[cfg(target_os = "linux")]
+fn main() {
+    let mut args = env::args().skip(1);
+    let path = match args.next() {
+        Some(arg) if arg == "-h" || arg == "--help" => {
+            println!("{}", USAGE);
+            return;
+        }
+        Some(arg) => PathBuf::from(arg),
+        None => match default_config_path() {
+            Some(path) => path,
+            None => {
+                eprintln!("Couldn't find the config directory\n\n{}", USAGE);
+                process::exit(2);
+            }
+        },
+    };
+
+    let config = match load_config(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    let mut builder = ContextBuilder::new();
+    if let Some(display) = &config.display {
+        builder = builder.display(display);
+    }
+
+    let mut ctx = match builder.build() {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            eprintln!("Error creating context: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let listener = if config.evdev {
+        InputListener::evdev(&builder).map_err(|e| e.to_string())
+    } else {
+        InputListener::x11(&builder).map_err(|e| e.to_string())
+    };
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error starting listener: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let hotstrings = config.hotstring.into_iter().map(Hotstring::from).collect();
+    let mut engine = HotstringEngine::new(hotstrings);
+
+    while let Some(event) = listener.recv() {
+        let expansion = match engine.feed(&event) {
+            Some(expansion) => expansion,
+            None => continue,
+        };
+        if let Err(e) = expansion.perform(&mut ctx) {
+            let trigger = &engine.hotstrings()[expansion.index].trigger;
+            eprintln!("Error expanding {}: {}", trigger, e);
+        }
+
+        // The X server reports the events that we just generated. Only the
+        // releases are passed on so that the engine knows which modifiers
+        // are still held.
+        thread::sleep(SETTLE);
+        while let Ok(event) = listener.try_recv() {
+            if let InputEvent::KeyUp(_) = event.event {
+                engine.feed(&event);
+            }
+        }
+        engine.reset();
+    }
+}
@@ -0,0 +1,31 @@
+//! Parsing and formatting of TFC scripts.
+//!
+//! A script is a whitespace-separated sequence of commands. Each command is
+//! the name of a [`CommandCode`](tfc::CommandCode) followed by its arguments.
+//! Names are not case-sensitive. Images are given as paths to PNG files and
+//! windows are given as regular expressions that match their titles. Neither
+//! can contain whitespace, nor can the text given to `setclipboard`.
+//!
+//! ```text
+//! keydown shift
+//! keyclick a
+//! keyup shift
+//! delay 100
+//! mousemoveabs 200 300
+//! mousemovenormalized 32768 32768
+//! waitforimage button.png 5000
+//! clickimage button.png left
+//! focuswindow Firefox$
+//! setclipboard hello
+//! ```
+//!
+//! The arguments of an `xdotool` invocation can also be translated into
+//! commands with [`parse_xdotool`] to help with migrating from xdotool.
+
+mod parser;
+mod writer;
+mod xdotool;
+
+pub use parser::*;
+pub use writer::*;
+pub use xdotool::*;
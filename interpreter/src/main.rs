@@ -1,15 +1,28 @@
-mod parser;
-
-use tfc::Command;
+use tfc::{Command, Completion, Executor};
+#[cfg(target_os = "linux")]
+use tfc::{ContextBuilder, KillSwitch};
 use std::io::Read;
-use parser::parse_tokens;
+use tfc_interpreter::parse_tokens;
 
-fn execute(commands: Vec<Command>) -> Result<(), tfc::Error> {
+fn execute(commands: Vec<Command>) -> Result<Completion, tfc::Error> {
     let mut ctx = tfc::Context::new()?;
-    for command in commands.iter() {
-        command.execute(&mut ctx)?;
-    }
-    Ok(())
+    let mut executor = Executor::new();
+
+    // Pressing Escape three times stops the script.
+    #[cfg(target_os = "linux")]
+    let _kill_switch = match KillSwitch::x11(&ContextBuilder::new(), executor.abort_handle()) {
+        Ok(kill_switch) => Some(kill_switch),
+        Err(e) => {
+            println!("Kill switch is unavailable: {}", e);
+            None
+        }
+    };
+
+    #[cfg(target_os = "linux")]
+    let completion = executor.execute_extended(&mut ctx, &commands)?;
+    #[cfg(not(target_os = "linux"))]
+    let completion = executor.execute(&mut ctx, &commands)?;
+    Ok(completion)
 }
 
 fn main() {
@@ -19,7 +32,6 @@ fn main() {
         return;
     }
 
-    let command_str = command_str.to_ascii_lowercase();
     let commands = match parse_tokens(command_str.split_ascii_whitespace()) {
         Ok(commands) => commands,
         Err(e) => {
@@ -28,7 +40,9 @@ fn main() {
         }
     };
 
-    if let Err(e) = execute(commands) {
-        println!("Error executing commands: {}", e);
+    match execute(commands) {
+        Ok(Completion::Finished) => {}
+        Ok(Completion::Aborted) => println!("Aborted"),
+        Err(e) => println!("Error executing commands: {}", e),
     }
 }
@@ -1,6 +1,6 @@
 use lazy_static::lazy_static;
-use tfc::{Command, CommandCode, Key, MouseButton, Enum};
-use std::{fmt::{self, Display, Formatter}, iter::Iterator};
+use tfc::{Command, CommandCode, Image, Key, MouseButton, Enum};
+use std::{fmt::{self, Display, Formatter}, io, iter::Iterator};
 
 #[derive(Debug)]
 pub enum ParseError<'a> {
@@ -8,9 +8,12 @@ pub enum ParseError<'a> {
     InvalidKey(&'a str),
     InvalidMouseButton(&'a str),
     InvalidInteger(&'a str),
+    InvalidImage(&'a str, io::Error),
     MissingKey,
     MissingMouseButton,
     MissingInteger,
+    MissingImage,
+    MissingString,
 }
 
 use ParseError::*;
@@ -22,9 +25,12 @@ impl<'a> Display for ParseError<'a> {
             InvalidKey(s) => write!(f, "Expected key, found \"{}\"", s),
             InvalidMouseButton(s) => write!(f, "Expected mouse button, found \"{}\"", s),
             InvalidInteger(s) => write!(f, "Expected integer, found \"{}\"", s),
+            InvalidImage(s, e) => write!(f, "Failed to load image \"{}\": {}", s, e),
             MissingKey => write!(f, "Expected key, reached end-of-input"),
             MissingMouseButton => write!(f, "Expected mouse button, reached end-of-input"),
             MissingInteger => write!(f, "Expected integer, reached end-of-input"),
+            MissingImage => write!(f, "Expected image path, reached end-of-input"),
+            MissingString => write!(f, "Expected string, reached end-of-input"),
         }
     }
 }
@@ -52,7 +58,7 @@ impl<'a> Parsable<'a> for CommandCode {
     // this MISSING constant is unused
     const MISSING: ParseError<'a> = MissingKey;
     fn parse(s: &'a str) -> Result<Self, ParseError<'a>> {
-        match COMMAND_STRINGS.iter().position(|st| s == st) {
+        match COMMAND_STRINGS.iter().position(|st| s.eq_ignore_ascii_case(st)) {
             Some(idx) => Ok(Self::from_u8(idx as u8).unwrap()),
             None => Err(InvalidCommand(s))
         }
@@ -62,7 +68,7 @@ impl<'a> Parsable<'a> for CommandCode {
 impl<'a> Parsable<'a> for Key {
     const MISSING: ParseError<'a> = MissingKey;
     fn parse(s: &'a str) -> Result<Self, ParseError<'a>> {
-        match KEY_STRINGS.iter().position(|st| s == st) {
+        match KEY_STRINGS.iter().position(|st| s.eq_ignore_ascii_case(st)) {
             Some(idx) => Ok(Self::from_u8(idx as u8).unwrap()),
             None => Err(InvalidKey(s))
         }
@@ -72,7 +78,7 @@ impl<'a> Parsable<'a> for Key {
 impl<'a> Parsable<'a> for MouseButton {
     const MISSING: ParseError<'a> = MissingMouseButton;
     fn parse(s: &'a str) -> Result<Self, ParseError<'a>> {
-        match KEY_STRINGS.iter().position(|st| s == st) {
+        match MOUSE_BUTTON_STRINGS.iter().position(|st| s.eq_ignore_ascii_case(st)) {
             Some(idx) => Ok(Self::from_u8(idx as u8).unwrap()),
             None => Err(InvalidMouseButton(s))
         }
@@ -86,6 +92,13 @@ impl<'a> Parsable<'a> for i32 {
     }
 }
 
+impl<'a> Parsable<'a> for u16 {
+    const MISSING: ParseError<'a> = MissingInteger;
+    fn parse(s: &'a str) -> Result<Self, ParseError<'a>> {
+        s.parse::<Self>().map_err(|_| InvalidInteger(s))
+    }
+}
+
 impl<'a> Parsable<'a> for u32 {
     const MISSING: ParseError<'a> = MissingInteger;
     fn parse(s: &'a str) -> Result<Self, ParseError<'a>> {
@@ -93,6 +106,27 @@ impl<'a> Parsable<'a> for u32 {
     }
 }
 
+impl<'a> Parsable<'a> for Image {
+    const MISSING: ParseError<'a> = MissingImage;
+    fn parse(s: &'a str) -> Result<Self, ParseError<'a>> {
+        if s.is_empty() {
+            return Err(MissingImage);
+        }
+        Image::load_png(s).map_err(|e| InvalidImage(s, e))
+    }
+}
+
+impl<'a> Parsable<'a> for String {
+    const MISSING: ParseError<'a> = MissingString;
+    fn parse(s: &'a str) -> Result<Self, ParseError<'a>> {
+        if s.is_empty() {
+            Err(MissingString)
+        } else {
+            Ok(s.to_owned())
+        }
+    }
+}
+
 fn parse<'a, T, I>(tokens: &mut I) -> Result<T, ParseError<'a>>
     where T: Parsable<'a>, I: Iterator<Item = &'a str>
 {
@@ -126,6 +160,11 @@ pub fn parse_tokens<'a, I>(mut tokens: I) -> Result<Vec<Command>, ParseError<'a>
             MouseDown => Command::MouseDown(parse(&mut tokens)?),
             MouseUp => Command::MouseUp(parse(&mut tokens)?),
             MouseClick => Command::MouseClick(parse(&mut tokens)?),
+            WaitForImage => Command::WaitForImage(parse(&mut tokens)?, parse(&mut tokens)?),
+            ClickImage => Command::ClickImage(parse(&mut tokens)?, parse(&mut tokens)?),
+            FocusWindow => Command::FocusWindow(parse(&mut tokens)?),
+            SetClipboard => Command::SetClipboard(parse(&mut tokens)?),
+            MouseMoveNormalized => Command::MouseMoveNormalized(parse(&mut tokens)?, parse(&mut tokens)?),
             // TODO: Extend this to handle the ASCII and Unicode commands
             _ => return Err(InvalidCommand(command_token)),
         });
@@ -0,0 +1,62 @@
+use std::fmt::Write;
+use tfc::{Command, Enum};
+
+fn name<E: Enum>(value: E) -> String {
+    value.identifier_name().to_ascii_lowercase()
+}
+
+/// Format a command in the syntax accepted by [`parse_tokens`](crate::parse_tokens).
+///
+/// `None` is returned if the command can't be represented in a script.
+pub fn format_command(command: &Command) -> Option<String> {
+    use Command::*;
+    Some(match command {
+        Delay(millis) => format!("delay {}", millis),
+        KeyDown(key) => format!("keydown {}", name(*key)),
+        KeyUp(key) => format!("keyup {}", name(*key)),
+        KeyClick(key) => format!("keyclick {}", name(*key)),
+        MouseMoveRel(dx, dy) => format!("mousemoverel {} {}", dx, dy),
+        MouseMoveAbs(x, y) => format!("mousemoveabs {} {}", x, y),
+        MouseMoveNormalized(x, y) => format!("mousemovenormalized {} {}", x, y),
+        MouseScroll(dx, dy) => format!("mousescroll {} {}", dx, dy),
+        MouseDown(button) => format!("mousedown {}", name(*button)),
+        MouseUp(button) => format!("mouseup {}", name(*button)),
+        MouseClick(button) => format!("mouseclick {}", name(*button)),
+        FocusWindow(title) if !title.is_empty() && !title.contains(char::is_whitespace) => {
+            format!("focuswindow {}", title)
+        }
+        SetClipboard(text) if !text.is_empty() && !text.contains(char::is_whitespace) => {
+            format!("setclipboard {}", text)
+        }
+        _ => return None,
+    })
+}
+
+/// Format a sequence of commands as a script with one command per line.
+///
+/// If one of the commands can't be represented in a script, it is returned as
+/// the error.
+///
+/// ```
+/// use tfc::{Command, Key, MouseButton};
+/// use tfc_interpreter::{format_commands, parse_tokens};
+///
+/// let commands = vec![
+///     Command::KeyDown(Key::Shift),
+///     Command::Delay(20),
+///     Command::MouseClick(MouseButton::Left),
+/// ];
+/// let script = format_commands(&commands).unwrap();
+/// assert_eq!(parse_tokens(script.split_ascii_whitespace()).unwrap(), commands);
+/// ```
+pub fn format_commands(commands: &[Command]) -> Result<String, &Command> {
+    let mut script = String::new();
+    for command in commands.iter() {
+        match format_command(command) {
+            // Writing to a String can't fail.
+            Some(line) => writeln!(script, "{}", line).unwrap(),
+            None => return Err(command),
+        }
+    }
+    Ok(script)
+}
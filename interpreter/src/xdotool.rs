@@ -0,0 +1,457 @@
+use tfc::{Command, Enum, Key, MouseButton};
+use std::{fmt::{self, Display, Formatter}, iter::{Iterator, Peekable}};
+
+// xdotool waits this long between keystrokes unless --delay is given.
+const DEFAULT_KEY_DELAY: u32 = 12;
+// xdotool waits this long between repeated clicks unless --delay is given.
+const DEFAULT_CLICK_DELAY: u32 = 100;
+// One notch of the scroll wheel.
+const SCROLL_NOTCH: i32 = 120;
+
+// The names of xdotool commands, including the ones that aren't supported.
+// These end the argument list of the commands that take any number of
+// arguments.
+const COMMANDS: [&str; 31] = [
+    "key", "keydown", "keyup", "type", "mousemove", "mousemove_relative",
+    "click", "mousedown", "mouseup", "sleep", "getmouselocation", "behave",
+    "behave_screen_edge", "search", "selectwindow", "getactivewindow",
+    "getwindowfocus", "getwindowname", "getwindowpid", "getwindowgeometry",
+    "getdisplaygeometry", "windowactivate", "windowfocus", "windowmove",
+    "windowsize", "windowraise", "windowminimize", "windowkill", "windowclose",
+    "set_desktop", "exec",
+];
+
+#[derive(Debug)]
+pub enum XdotoolError<'a> {
+    UnsupportedCommand(&'a str),
+    UnsupportedOption(&'a str, &'a str),
+    UnsupportedButton(&'a str, u8),
+    InvalidKey(&'a str),
+    InvalidMouseButton(&'a str),
+    InvalidNumber(&'a str),
+    MissingArgument(&'a str),
+}
+
+use XdotoolError::*;
+
+impl<'a> Display for XdotoolError<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UnsupportedCommand(s) => write!(f, "The xdotool command \"{}\" is not supported", s),
+            UnsupportedOption(c, s) => write!(f, "The option \"{}\" of \"{}\" is not supported", s, c),
+            UnsupportedButton(c, b) => write!(f, "Mouse button {} can't be used with \"{}\"", b, c),
+            InvalidKey(s) => write!(f, "Expected key, found \"{}\"", s),
+            InvalidMouseButton(s) => write!(f, "Expected mouse button, found \"{}\"", s),
+            InvalidNumber(s) => write!(f, "Expected number, found \"{}\"", s),
+            MissingArgument(c) => write!(f, "Expected argument for \"{}\", reached end-of-input", c),
+        }
+    }
+}
+
+impl<'a> std::error::Error for XdotoolError<'a> {}
+
+// X keysym names that don't match the name of a Key.
+fn keysym_key(name: &str) -> Option<Key> {
+    use Key::*;
+    Some(match name {
+        "ctrl" | "Control_L" => Control,
+        "Control_R" => RightControl,
+        "shift" | "Shift_L" => Shift,
+        "Shift_R" => RightShift,
+        "alt" | "Alt_L" | "Meta_L" => Alt,
+        "Alt_R" | "Meta_R" | "ISO_Level3_Shift" => RightAlt,
+        "super" | "Super_L" | "Hyper_L" => Meta,
+        "Super_R" | "Hyper_R" => RightMeta,
+        "Caps_Lock" => CapsLock,
+        "Return" | "Linefeed" => ReturnOrEnter,
+        "BackSpace" => DeleteOrBackspace,
+        "Delete" => ForwardDelete,
+        "space" => Space,
+        "minus" => Minus,
+        "equal" => Equal,
+        "bracketleft" => LeftBracket,
+        "bracketright" => RightBracket,
+        "backslash" => Backslash,
+        "semicolon" => Semicolon,
+        "apostrophe" => Quote,
+        "grave" => Grave,
+        "comma" => Comma,
+        "period" => Period,
+        "slash" => Slash,
+        "Menu" => Apps,
+        "Up" => UpArrow,
+        "Right" => RightArrow,
+        "Down" => DownArrow,
+        "Left" => LeftArrow,
+        "Prior" | "Page_Up" => PageUp,
+        "Next" | "Page_Down" => PageDown,
+        "Scroll_Lock" => ScrollLock,
+        "Num_Lock" => NumLock,
+        "0" => N0,
+        "1" => N1,
+        "2" => N2,
+        "3" => N3,
+        "4" => N4,
+        "5" => N5,
+        "6" => N6,
+        "7" => N7,
+        "8" => N8,
+        "9" => N9,
+        "KP_0" | "KP_Insert" => Numpad0,
+        "KP_1" | "KP_End" => Numpad1,
+        "KP_2" | "KP_Down" => Numpad2,
+        "KP_3" | "KP_Next" => Numpad3,
+        "KP_4" | "KP_Left" => Numpad4,
+        "KP_5" | "KP_Begin" => Numpad5,
+        "KP_6" | "KP_Right" => Numpad6,
+        "KP_7" | "KP_Home" => Numpad7,
+        "KP_8" | "KP_Up" => Numpad8,
+        "KP_9" | "KP_Prior" => Numpad9,
+        "KP_Equal" => NumpadEquals,
+        "KP_Divide" => NumpadDivide,
+        "KP_Multiply" => NumpadMultiply,
+        "KP_Subtract" => NumpadMinus,
+        "KP_Add" => NumpadPlus,
+        "KP_Enter" => NumpadEnter,
+        "KP_Decimal" | "KP_Delete" => NumpadDecimal,
+        "XF86AudioNext" => FastForward,
+        "XF86AudioPrev" => Rewind,
+        "XF86AudioPlay" => PlayPause,
+        "XF86AudioRaiseVolume" => VolumeUp,
+        "XF86AudioLowerVolume" => VolumeDown,
+        "XF86AudioMute" => Mute,
+        _ => return None,
+    })
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    keysym_key(name).or_else(|| {
+        Key::iter().find(|key| key.identifier_name().eq_ignore_ascii_case(name))
+    })
+}
+
+// A key sequence such as ctrl+alt+Delete. The keys are pressed in order and
+// released in reverse.
+fn parse_key_sequence(s: &str) -> Result<Vec<Key>, XdotoolError<'_>> {
+    s.split('+')
+        .map(|name| parse_key(name).ok_or(InvalidKey(s)))
+        .collect()
+}
+
+fn parse_u32(s: &str) -> Result<u32, XdotoolError<'_>> {
+    s.parse().map_err(|_| InvalidNumber(s))
+}
+
+fn parse_i32(s: &str) -> Result<i32, XdotoolError<'_>> {
+    s.parse().map_err(|_| InvalidNumber(s))
+}
+
+// Buttons are numbered like X11 (1 is left, 2 is middle, 3 is right and 4 to 7
+// are the scroll wheel) but can also be given by name.
+fn parse_button(s: &str) -> Result<u8, XdotoolError<'_>> {
+    if let Ok(number @ 1..=7) = s.parse::<u8>() {
+        return Ok(number);
+    }
+    match MouseButton::iter().find(|b| b.identifier_name().eq_ignore_ascii_case(s)) {
+        Some(MouseButton::Left) => Ok(1),
+        Some(MouseButton::Middle) => Ok(2),
+        Some(MouseButton::Right) => Ok(3),
+        None => Err(InvalidMouseButton(s)),
+    }
+}
+
+fn button_scroll(button: u8) -> Option<(i32, i32)> {
+    match button {
+        4 => Some((0, -SCROLL_NOTCH)),
+        5 => Some((0, SCROLL_NOTCH)),
+        6 => Some((-SCROLL_NOTCH, 0)),
+        7 => Some((SCROLL_NOTCH, 0)),
+        _ => None,
+    }
+}
+
+fn mouse_button(command: &'static str, button: u8) -> Result<MouseButton, XdotoolError<'static>> {
+    match button {
+        1 => Ok(MouseButton::Left),
+        2 => Ok(MouseButton::Middle),
+        3 => Ok(MouseButton::Right),
+        _ => Err(UnsupportedButton(command, button)),
+    }
+}
+
+fn next<'a, I>(command: &'static str, tokens: &mut Peekable<I>) -> Result<&'a str, XdotoolError<'a>>
+    where I: Iterator<Item = &'a str>
+{
+    tokens.next().ok_or(MissingArgument(command))
+}
+
+// The value of an option that takes a number.
+fn option_value<'a, I>(command: &'static str, tokens: &mut Peekable<I>) -> Result<u32, XdotoolError<'a>>
+    where I: Iterator<Item = &'a str>
+{
+    parse_u32(next(command, tokens)?)
+}
+
+// The arguments of a command that takes any number of arguments. These end at
+// the next command name unless the options were ended with --, in which case
+// the rest of the arguments are taken. That's how a command name such as
+// "click" can be typed.
+fn variadic<'a, I>(tokens: &mut Peekable<I>, to_end: bool) -> Vec<&'a str>
+    where I: Iterator<Item = &'a str>
+{
+    if to_end {
+        return tokens.collect();
+    }
+    let mut args = Vec::new();
+    while let Some(token) = tokens.peek() {
+        if COMMANDS.contains(token) {
+            break;
+        }
+        args.push(*token);
+        tokens.next();
+    }
+    args
+}
+
+fn push_delay(commands: &mut Vec<Command>, delay: u32) {
+    if delay != 0 {
+        commands.push(Command::Delay(delay));
+    }
+}
+
+// key, keydown and keyup
+fn parse_key_command<'a, I>(command: &'static str, tokens: &mut Peekable<I>, commands: &mut Vec<Command>) -> Result<(), XdotoolError<'a>>
+    where I: Iterator<Item = &'a str>
+{
+    let mut delay = DEFAULT_KEY_DELAY;
+    let mut repeat = 1;
+    let mut repeat_delay = 0;
+    let mut to_end = false;
+
+    while let Some(token) = tokens.peek().copied() {
+        if !token.starts_with("--") {
+            break;
+        }
+        tokens.next();
+        match token {
+            "--" => {
+                to_end = true;
+                break;
+            }
+            "--delay" => delay = option_value(command, tokens)?,
+            "--repeat" if command == "key" => repeat = option_value(command, tokens)?,
+            "--repeat-delay" if command == "key" => repeat_delay = option_value(command, tokens)?,
+            _ => return Err(UnsupportedOption(command, token)),
+        }
+    }
+
+    let sequences = variadic(tokens, to_end)
+        .into_iter()
+        .map(parse_key_sequence)
+        .collect::<Result<Vec<_>, _>>()?;
+    if sequences.is_empty() {
+        return Err(MissingArgument(command));
+    }
+
+    for i in 0..repeat {
+        if i != 0 {
+            push_delay(commands, repeat_delay);
+        }
+        for (j, keys) in sequences.iter().enumerate() {
+            if j != 0 {
+                push_delay(commands, delay);
+            }
+            if command != "keyup" {
+                commands.extend(keys.iter().map(|key| Command::KeyDown(*key)));
+            }
+            if command != "keydown" {
+                commands.extend(keys.iter().rev().map(|key| Command::KeyUp(*key)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_type<'a, I>(tokens: &mut Peekable<I>, commands: &mut Vec<Command>) -> Result<(), XdotoolError<'a>>
+    where I: Iterator<Item = &'a str>
+{
+    let mut delay = DEFAULT_KEY_DELAY;
+    let mut to_end = false;
+
+    while let Some(token) = tokens.peek().copied() {
+        if !token.starts_with("--") {
+            break;
+        }
+        tokens.next();
+        match token {
+            "--" => {
+                to_end = true;
+                break;
+            }
+            "--delay" => delay = option_value("type", tokens)?,
+            _ => return Err(UnsupportedOption("type", token)),
+        }
+    }
+
+    let texts = variadic(tokens, to_end);
+    if texts.is_empty() {
+        return Err(MissingArgument("type"));
+    }
+
+    if delay == 0 {
+        commands.extend(texts.iter().map(|text| Command::UnicodeString(text.to_string())));
+    } else {
+        for (i, ch) in texts.iter().flat_map(|text| text.chars()).enumerate() {
+            if i != 0 {
+                commands.push(Command::Delay(delay));
+            }
+            commands.push(Command::UnicodeChar(ch));
+        }
+    }
+
+    Ok(())
+}
+
+// mousemove and mousemove_relative
+fn parse_mouse_move<'a, I>(command: &'static str, tokens: &mut Peekable<I>) -> Result<Command, XdotoolError<'a>>
+    where I: Iterator<Item = &'a str>
+{
+    // xdotool needs -- before negative offsets. It's accepted but not needed.
+    while let Some(token) = tokens.peek().copied() {
+        if !token.starts_with("--") {
+            break;
+        }
+        tokens.next();
+        match token {
+            "--" => break,
+            // The events are generated synchronously anyway.
+            "--sync" => {}
+            _ => return Err(UnsupportedOption(command, token)),
+        }
+    }
+
+    let x = next(command, tokens)?;
+    if x == "restore" {
+        return Err(UnsupportedOption(command, x));
+    }
+    let x = parse_i32(x)?;
+    let y = parse_i32(next(command, tokens)?)?;
+    Ok(if command == "mousemove" {
+        Command::MouseMoveAbs(x, y)
+    } else {
+        Command::MouseMoveRel(x, y)
+    })
+}
+
+// click, mousedown and mouseup
+fn parse_button_command<'a, I>(command: &'static str, tokens: &mut Peekable<I>, commands: &mut Vec<Command>) -> Result<(), XdotoolError<'a>>
+    where I: Iterator<Item = &'a str>
+{
+    let mut delay = DEFAULT_CLICK_DELAY;
+    let mut repeat = 1;
+
+    while let Some(token) = tokens.peek().copied() {
+        if !token.starts_with("--") {
+            break;
+        }
+        tokens.next();
+        match token {
+            "--" => break,
+            "--delay" if command == "click" => delay = option_value(command, tokens)?,
+            "--repeat" if command == "click" => repeat = option_value(command, tokens)?,
+            _ => return Err(UnsupportedOption(command, token)),
+        }
+    }
+
+    let button = parse_button(next(command, tokens)?)?;
+
+    if command != "click" {
+        let button = mouse_button(command, button)?;
+        commands.push(if command == "mousedown" {
+            Command::MouseDown(button)
+        } else {
+            Command::MouseUp(button)
+        });
+        return Ok(());
+    }
+
+    for i in 0..repeat {
+        if i != 0 {
+            push_delay(commands, delay);
+        }
+        commands.push(match button_scroll(button) {
+            Some((dx, dy)) => Command::MouseScroll(dx, dy),
+            None => Command::MouseClick(mouse_button(command, button)?),
+        });
+    }
+
+    Ok(())
+}
+
+fn parse_sleep<'a, I>(tokens: &mut Peekable<I>) -> Result<Command, XdotoolError<'a>>
+    where I: Iterator<Item = &'a str>
+{
+    let token = next("sleep", tokens)?;
+    match token.parse::<f64>() {
+        Ok(seconds) if seconds >= 0.0 && seconds * 1000.0 <= u32::MAX as f64 => {
+            Ok(Command::Delay((seconds * 1000.0).round() as u32))
+        }
+        _ => Err(InvalidNumber(token)),
+    }
+}
+
+/// Translate the arguments of an `xdotool` invocation into commands.
+///
+/// This accepts the commands that generate input, which are `key`, `keydown`,
+/// `keyup`, `type`, `mousemove`, `mousemove_relative`, `click`, `mousedown`,
+/// `mouseup` and `sleep`. Commands can be chained like they can with xdotool.
+/// Commands that take a list of keys or strings consume arguments until the
+/// next command name, so `type click` is two commands. If the options of the
+/// command are ended with `--`, the rest of the arguments are consumed instead
+/// so `type -- click` types "click". The window options and commands that search for or
+/// manipulate windows are not supported.
+///
+/// Keys are X keysym names (such as `Return` or `ctrl`) or the names of
+/// [`Key`](tfc::Key) variants. Like xdotool, there is a delay of 12
+/// milliseconds between keystrokes and 100 milliseconds between repeated
+/// clicks unless `--delay` is given. Mouse buttons 4 to 7 scroll the wheel.
+///
+/// ```
+/// use tfc::{Command, Key, MouseButton};
+/// use tfc_interpreter::parse_xdotool;
+///
+/// let commands = parse_xdotool("key --delay 0 ctrl+s sleep 0.5 click 1".split(' ')).unwrap();
+/// assert_eq!(commands, vec![
+///     Command::KeyDown(Key::Control),
+///     Command::KeyDown(Key::S),
+///     Command::KeyUp(Key::S),
+///     Command::KeyUp(Key::Control),
+///     Command::Delay(500),
+///     Command::MouseClick(MouseButton::Left),
+/// ]);
+/// ```
+pub fn parse_xdotool<'a, I>(tokens: I) -> Result<Vec<Command>, XdotoolError<'a>>
+    where I: IntoIterator<Item = &'a str>
+{
+    let mut tokens = tokens.into_iter().peekable();
+    let mut commands = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "key" => parse_key_command("key", &mut tokens, &mut commands)?,
+            "keydown" => parse_key_command("keydown", &mut tokens, &mut commands)?,
+            "keyup" => parse_key_command("keyup", &mut tokens, &mut commands)?,
+            "type" => parse_type(&mut tokens, &mut commands)?,
+            "mousemove" => commands.push(parse_mouse_move("mousemove", &mut tokens)?),
+            "mousemove_relative" => commands.push(parse_mouse_move("mousemove_relative", &mut tokens)?),
+            "click" => parse_button_command("click", &mut tokens, &mut commands)?,
+            "mousedown" => parse_button_command("mousedown", &mut tokens, &mut commands)?,
+            "mouseup" => parse_button_command("mouseup", &mut tokens, &mut commands)?,
+            "sleep" => commands.push(parse_sleep(&mut tokens)?),
+            _ => return Err(UnsupportedCommand(token)),
+        }
+    }
+
+    Ok(commands)
+}
@@ -0,0 +1,249 @@
+use tfc::{Command, Key, MouseButton};
+use tfc_interpreter::{parse_xdotool, XdotoolError};
+
+fn parse(args: &str) -> Result<Vec<Command>, XdotoolError<'_>> {
+    parse_xdotool(args.split(' '))
+}
+
+#[test]
+fn key_default_delay() {
+    assert_eq!(
+        parse("key a ctrl+b").unwrap(),
+        vec![
+            Command::KeyDown(Key::A),
+            Command::KeyUp(Key::A),
+            Command::Delay(12),
+            Command::KeyDown(Key::Control),
+            Command::KeyDown(Key::B),
+            Command::KeyUp(Key::B),
+            Command::KeyUp(Key::Control),
+        ]
+    );
+}
+
+#[test]
+fn key_delay_and_repeat() {
+    assert_eq!(
+        parse("key --delay 0 --repeat 2 --repeat-delay 5 a Return").unwrap(),
+        vec![
+            Command::KeyDown(Key::A),
+            Command::KeyUp(Key::A),
+            Command::KeyDown(Key::ReturnOrEnter),
+            Command::KeyUp(Key::ReturnOrEnter),
+            Command::Delay(5),
+            Command::KeyDown(Key::A),
+            Command::KeyUp(Key::A),
+            Command::KeyDown(Key::ReturnOrEnter),
+            Command::KeyUp(Key::ReturnOrEnter),
+        ]
+    );
+}
+
+#[test]
+fn type_delay() {
+    assert_eq!(
+        parse("type ab").unwrap(),
+        vec![
+            Command::UnicodeChar('a'),
+            Command::Delay(12),
+            Command::UnicodeChar('b'),
+        ]
+    );
+    assert_eq!(
+        parse("type --delay 0 ab cd").unwrap(),
+        vec![
+            Command::UnicodeString("ab".to_owned()),
+            Command::UnicodeString("cd".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn chaining() {
+    assert_eq!(
+        parse("keydown shift type --delay 0 x keyup shift mousemove 10 20 click 1").unwrap(),
+        vec![
+            Command::KeyDown(Key::Shift),
+            Command::UnicodeString("x".to_owned()),
+            Command::KeyUp(Key::Shift),
+            Command::MouseMoveAbs(10, 20),
+            Command::MouseClick(MouseButton::Left),
+        ]
+    );
+}
+
+#[test]
+fn command_names_end_the_arguments() {
+    assert!(matches!(
+        parse("type click here"),
+        Err(XdotoolError::MissingArgument("type"))
+    ));
+    assert_eq!(
+        parse("type --delay 0 -- click here").unwrap(),
+        vec![
+            Command::UnicodeString("click".to_owned()),
+            Command::UnicodeString("here".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn mouse_move_relative() {
+    assert_eq!(
+        parse("mousemove_relative -- -10 -20").unwrap(),
+        vec![Command::MouseMoveRel(-10, -20)]
+    );
+    assert_eq!(
+        parse("mousemove_relative --sync 5 -5").unwrap(),
+        vec![Command::MouseMoveRel(5, -5)]
+    );
+}
+
+#[test]
+fn scroll_buttons() {
+    assert_eq!(
+        parse("click 4 click 5 click 6 click 7").unwrap(),
+        vec![
+            Command::MouseScroll(0, -120),
+            Command::MouseScroll(0, 120),
+            Command::MouseScroll(-120, 0),
+            Command::MouseScroll(120, 0),
+        ]
+    );
+    assert_eq!(
+        parse("click --repeat 2 5").unwrap(),
+        vec![
+            Command::MouseScroll(0, 120),
+            Command::Delay(100),
+            Command::MouseScroll(0, 120),
+        ]
+    );
+    assert!(matches!(
+        parse("mousedown 4"),
+        Err(XdotoolError::UnsupportedButton("mousedown", 4))
+    ));
+    assert!(matches!(
+        parse("mouseup 7"),
+        Err(XdotoolError::UnsupportedButton("mouseup", 7))
+    ));
+    assert!(matches!(
+        parse("click 8"),
+        Err(XdotoolError::InvalidMouseButton("8"))
+    ));
+}
+
+#[test]
+fn click_delay_and_repeat() {
+    assert_eq!(
+        parse("click --delay 50 --repeat 3 right").unwrap(),
+        vec![
+            Command::MouseClick(MouseButton::Right),
+            Command::Delay(50),
+            Command::MouseClick(MouseButton::Right),
+            Command::Delay(50),
+            Command::MouseClick(MouseButton::Right),
+        ]
+    );
+}
+
+#[test]
+fn sleep_fractions() {
+    assert_eq!(parse("sleep 0.25").unwrap(), vec![Command::Delay(250)]);
+    assert_eq!(parse("sleep 1.5").unwrap(), vec![Command::Delay(1500)]);
+    assert_eq!(parse("sleep 2").unwrap(), vec![Command::Delay(2000)]);
+    assert!(matches!(
+        parse("sleep -1"),
+        Err(XdotoolError::InvalidNumber("-1"))
+    ));
+    assert!(matches!(
+        parse("sleep soon"),
+        Err(XdotoolError::InvalidNumber("soon"))
+    ));
+    assert!(matches!(
+        parse_xdotool(vec!["sleep"]),
+        Err(XdotoolError::MissingArgument("sleep"))
+    ));
+}
+
+#[test]
+fn unsupported_commands() {
+    let commands = [
+        "getmouselocation",
+        "behave",
+        "behave_screen_edge",
+        "search",
+        "selectwindow",
+        "getactivewindow",
+        "getwindowfocus",
+        "getwindowname",
+        "getwindowpid",
+        "getwindowgeometry",
+        "getdisplaygeometry",
+        "windowactivate",
+        "windowfocus",
+        "windowmove",
+        "windowsize",
+        "windowraise",
+        "windowminimize",
+        "windowkill",
+        "windowclose",
+        "set_desktop",
+        "exec",
+        "unknown",
+    ];
+    for command in commands.iter() {
+        match parse_xdotool(vec![*command]) {
+            Err(XdotoolError::UnsupportedCommand(c)) => assert_eq!(c, *command),
+            other => panic!("{}: {:?}", command, other),
+        }
+    }
+}
+
+#[test]
+fn unsupported_options() {
+    let cases = [
+        ("key --window 1 a", "key", "--window"),
+        ("key --clearmodifiers a", "key", "--clearmodifiers"),
+        ("keydown --repeat 2 a", "keydown", "--repeat"),
+        ("keyup --repeat-delay 2 a", "keyup", "--repeat-delay"),
+        ("type --window 1 a", "type", "--window"),
+        ("mousemove --window 1 1 2", "mousemove", "--window"),
+        ("mousemove restore", "mousemove", "restore"),
+        (
+            "mousemove_relative --polar 1 2",
+            "mousemove_relative",
+            "--polar",
+        ),
+        ("click --window 1 1", "click", "--window"),
+        ("mousedown --delay 5 1", "mousedown", "--delay"),
+        ("mouseup --repeat 2 1", "mouseup", "--repeat"),
+    ];
+    for (args, command, option) in cases.iter() {
+        match parse(args) {
+            Err(XdotoolError::UnsupportedOption(c, o)) => {
+                assert_eq!((c, o), (*command, *option), "{}", args)
+            }
+            other => panic!("{}: {:?}", args, other),
+        }
+    }
+}
+
+#[test]
+fn invalid_arguments() {
+    assert!(matches!(
+        parse_xdotool(vec!["key"]),
+        Err(XdotoolError::MissingArgument("key"))
+    ));
+    assert!(matches!(
+        parse("key ctrl+nokey"),
+        Err(XdotoolError::InvalidKey("ctrl+nokey"))
+    ));
+    assert!(matches!(
+        parse("mousemove 1 y"),
+        Err(XdotoolError::InvalidNumber("y"))
+    ));
+    assert!(matches!(
+        parse("key --delay x a"),
+        Err(XdotoolError::InvalidNumber("x"))
+    ));
+}
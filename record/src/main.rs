@@ -0,0 +1,179 @@
+// The input listener is only available on Linux.
+#![cfg_attr(not(target_os = "linux"), allow(dead_code, unused_imports))]
+
+use std::{fs, io::Write, process};
+#[cfg(target_os = "linux")]
+use tfc::InputListener;
+use tfc::{ContextBuilder, Enum, InputEvent, Key, MouseButton, Recorder};
+
+const USAGE: &str = "\
+Usage: tfc-record [options] [output]
+
+Records keyboard and mouse input until the stop key is pressed. The recording
+is written to the output file (or stdout) in the format read by
+tfc-interpreter.
+
+Options:
+  --evdev           Read the devices in /dev/input instead of the X server
+  --display <name>  The X display to record from
+  --stop <key>      The key that stops recording (default: escape)";
+
+struct Options {
+    evdev: bool,
+    display: Option<String>,
+    stop: Key,
+    output: Option<String>,
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    let name = name.to_ascii_lowercase();
+    Key::iter().find(|key| key.identifier_name().to_ascii_lowercase() == name)
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        evdev: false,
+        display: None,
+        stop: Key::Escape,
+        output: None,
+    };
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--evdev" => options.evdev = true,
+            "--display" => match args.next() {
+                Some(display) => options.display = Some(display),
+                None => return Err("Expected display name after --display".to_owned()),
+            },
+            "--stop" => match args.next().as_deref().map(parse_key) {
+                Some(Some(key)) => options.stop = key,
+                _ => return Err("Expected key name after --stop".to_owned()),
+            },
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option \"{}\"", arg)),
+            _ if options.output.is_none() => options.output = Some(arg),
+            _ => return Err(format!("Unexpected argument \"{}\"", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+// Releases without a matching press are from before recording started. For
+// example, releasing the enter key after typing the command to start
+// recording.
+#[derive(Default)]
+struct Pressed {
+    keys: Vec<Key>,
+    buttons: Vec<MouseButton>,
+}
+
+impl Pressed {
+    fn accept(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::KeyDown(key) => {
+                if !self.keys.contains(key) {
+                    self.keys.push(*key);
+                }
+                true
+            }
+            InputEvent::KeyUp(key) => remove(&mut self.keys, key),
+            InputEvent::MouseDown(button) => {
+                if !self.buttons.contains(button) {
+                    self.buttons.push(*button);
+                }
+                true
+            }
+            InputEvent::MouseUp(button) => remove(&mut self.buttons, button),
+            _ => true,
+        }
+    }
+}
+
+fn remove<T: PartialEq>(vec: &mut Vec<T>, item: &T) -> bool {
+    match vec.iter().position(|i| i == item) {
+        Some(index) => {
+            vec.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("tfc-record is only supported on Linux");
+    process::exit(1);
+}
+
+#[cfg(target_os = "linux")]
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut builder = ContextBuilder::new();
+    if let Some(display) = &options.display {
+        builder = builder.display(display);
+    }
+    let listener = if options.evdev {
+        InputListener::evdev(&builder).map_err(|e| e.to_string())
+    } else {
+        InputListener::x11(&builder).map_err(|e| e.to_string())
+    };
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error starting listener: {}", e);
+            process::exit(1);
+        }
+    };
+
+    eprintln!("Recording. Press {} to stop.", options.stop);
+
+    let mut recorder = Recorder::new();
+    let mut pressed = Pressed::default();
+    while let Some(event) = listener.recv() {
+        if event.event == InputEvent::KeyDown(options.stop) {
+            break;
+        }
+        if pressed.accept(&event.event) {
+            recorder.record(&event);
+        }
+    }
+
+    // Anything still held down is released at the end so that replaying the
+    // recording doesn't leave keys stuck.
+    let mut commands = recorder.finish();
+    for key in pressed.keys.drain(..).rev() {
+        commands.push(tfc::Command::KeyUp(key));
+    }
+    for button in pressed.buttons.drain(..).rev() {
+        commands.push(tfc::Command::MouseUp(button));
+    }
+
+    let script = match tfc_interpreter::format_commands(&commands) {
+        Ok(script) => script,
+        Err(command) => {
+            eprintln!("Error formatting command: {:?}", command);
+            process::exit(1);
+        }
+    };
+
+    let result = match &options.output {
+        Some(path) if path != "-" => fs::write(path, script),
+        _ => std::io::stdout().write_all(script.as_bytes()),
+    };
+    if let Err(e) = result {
+        eprintln!("Error writing recording: {}", e);
+        process::exit(1);
+    }
+}
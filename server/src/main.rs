@@ -1,39 +1,127 @@
 mod socket;
 
+use futures::FutureExt;
+use std::{path::Path, thread};
 use tokio::{sync::mpsc, net::TcpListener};
 
-#[tokio::main(flavor="current_thread")]
-async fn main() {
-    let mut tfc_ctx = match tfc::Context::new() {
+type PolicyContext = tfc::PolicyContext<tfc::Context>;
+
+fn create_context() -> Result<PolicyContext, String> {
+    // The policy restricts what the clients are allowed to do.
+    let policy = match std::env::args_os().nth(1) {
+        Some(path) => tfc::Policy::load(Path::new(&path)).map_err(|e| e.to_string())?,
+        None => tfc::Policy::default(),
+    };
+    let ctx = tfc::Context::new().map_err(|e| e.to_string())?;
+    Ok(tfc::PolicyContext::new(ctx, policy))
+}
+
+// The client is told which commands it can send so that it can hide the
+// others. The extended commands can only be executed on Linux.
+fn command_codes(ctx: &PolicyContext) -> Vec<tfc::CommandCode> {
+    ctx.inner()
+        .capabilities()
+        .command_codes()
+        .into_iter()
+        .filter(|code| ctx.policy().allows_command(*code))
+        .filter(|code| cfg!(target_os = "linux") || !code.is_extended())
+        .collect()
+}
+
+// Commands are executed on their own thread because executing them blocks.
+// Executing them on the runtime would stop the connections from being
+// serviced. The context is created on this thread because it can't be sent
+// between threads.
+fn execute_commands(
+    mut ch_rx: mpsc::UnboundedReceiver<tfc::Command>,
+    codes_tx: std::sync::mpsc::Sender<Result<Vec<tfc::CommandCode>, String>>,
+) {
+    let mut tfc_ctx = match create_context() {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("{}", e);
+            let _ = codes_tx.send(Err(e));
             return;
         }
     };
-    let (ch_tx, mut ch_rx) = mpsc::unbounded_channel::<tfc::Command>();
-    let sock_ctx = socket::SocketContext::new(ch_tx);
-
-    tokio::spawn(async move {
-        let listener = match TcpListener::bind("0.0.0.0:80").await {
-            Ok(l) => l,
-            Err(e) => {
-                eprintln!("{}", e);
-                return;
+    if codes_tx.send(Ok(command_codes(&tfc_ctx))).is_err() {
+        return;
+    }
+
+    let mut executor = tfc::Executor::new();
+    let abort = executor.abort_handle();
+
+    // Pressing Escape three times on a real keyboard discards the queued
+    // commands. The Escape presses sent by clients are ignored.
+    #[cfg(target_os = "linux")]
+    let _kill_switch = match tfc::KillSwitch::x11(&tfc::ContextBuilder::new(), abort.clone()) {
+        Ok(k) => Some(k),
+        Err(e) => {
+            eprintln!("Kill switch is unavailable: {}", e);
+            None
+        }
+    };
+
+    loop {
+        let command = match ch_rx.recv().now_or_never() {
+            Some(Some(command)) => command,
+            Some(None) => break,
+            None => {
+                // Nothing was queued so an abort while waiting would only
+                // discard the next command that arrives.
+                let command = match ch_rx.blocking_recv() {
+                    Some(command) => command,
+                    None => break,
+                };
+                abort.reset();
+                command
             }
         };
+        // The policy checks each command before it is executed.
+        let commands = std::slice::from_ref(&command);
+        #[cfg(target_os = "linux")]
+        let result = executor.execute_extended_policy(&mut tfc_ctx, commands);
+        #[cfg(not(target_os = "linux"))]
+        let result = executor.execute_policy(&mut tfc_ctx, commands);
+        match result {
+            Ok(tfc::Completion::Finished) => {}
+            Ok(tfc::Completion::Aborted) => {
+                while let Some(Some(_)) = ch_rx.recv().now_or_never() {}
+                abort.reset();
+                eprintln!("Aborted");
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+#[tokio::main(flavor="current_thread")]
+async fn main() {
+    let (ch_tx, ch_rx) = mpsc::unbounded_channel::<tfc::Command>();
+    let (codes_tx, codes_rx) = std::sync::mpsc::channel();
+    thread::spawn(move || execute_commands(ch_rx, codes_tx));
 
-        while let Ok((stream, addr)) = listener.accept().await {
-            let ctx = sock_ctx.clone();
-            tokio::spawn(async move {
-                ctx.connect(stream, addr).await
-            });
+    let command_codes = match codes_rx.recv() {
+        Ok(Ok(codes)) => codes,
+        Ok(Err(e)) => {
+            eprintln!("{}", e);
+            return;
         }
-    });
+        Err(_) => return,
+    };
+    let sock_ctx = socket::SocketContext::new(ch_tx, &command_codes);
 
-    while let Some(command) = ch_rx.recv().await {
-        if let Err(e) = command.execute_async(&mut tfc_ctx).await {
+    let listener = match TcpListener::bind("0.0.0.0:80").await {
+        Ok(l) => l,
+        Err(e) => {
             eprintln!("{}", e);
+            return;
         }
+    };
+
+    while let Ok((stream, addr)) = listener.accept().await {
+        let ctx = sock_ctx.clone();
+        tokio::spawn(async move {
+            ctx.connect(stream, addr).await
+        });
     }
 }
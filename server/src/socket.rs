@@ -1,6 +1,6 @@
-use openssl::symm::{self, Cipher};
+use openssl::{error::ErrorStack, symm::{self, Cipher}};
 use tokio::{sync::mpsc, net::TcpStream};
-use futures::{stream::SplitStream, StreamExt};
+use futures::{stream::{SplitSink, SplitStream}, SinkExt, StreamExt};
 use qrcode::{QrCode, render::unicode::Dense1x2};
 use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
 use std::{
@@ -15,20 +15,30 @@ const IV_LEN: usize = 12;
 const TAG_LEN: usize = 16;
 const BASE64_LEN: usize = BASE64_PREFIX_LEN + KEY_LEN * 4 / 3 + 3;
 
+// The bits of the flag that the client sends after connecting.
+const FLAG_ENCRYPTION: u8 = 1;
+const FLAG_COMMAND_CODES: u8 = 2;
+
 type EncryptionKey = [u8; KEY_LEN];
 type Receiver = SplitStream<WebSocketStream<TcpStream>>;
+type Sender = SplitSink<WebSocketStream<TcpStream>, Message>;
 
 #[derive(Clone)]
 pub struct SocketContext {
     event: mpsc::UnboundedSender<tfc::Command>,
     connected: Arc<AtomicBool>,
+    command_codes: Arc<[u8]>,
 }
 
 impl SocketContext {
-    pub fn new(event: mpsc::UnboundedSender<tfc::Command>) -> Self {
+    pub fn new(
+        event: mpsc::UnboundedSender<tfc::Command>,
+        command_codes: &[tfc::CommandCode]
+    ) -> Self {
         Self {
             event,
             connected: Arc::new(AtomicBool::new(false)),
+            command_codes: command_codes.iter().map(|code| *code as u8).collect(),
         }
     }
 
@@ -50,15 +60,20 @@ impl SocketContext {
                 return;
             }
         };
-        let (_, mut ws_rx) = ws_stream.split();
+        let (mut ws_tx, mut ws_rx) = ws_stream.split();
 
         println!("Connected to: {}", addr);
 
-        if let Some(encryption_enabled) = self.receive_encryption_flag(&mut ws_rx).await {
-            if encryption_enabled {
-                self.receive_loop(ws_rx, Some(Self::generate_encryption_key())).await;
+        if let Some(flag) = self.receive_flag(&mut ws_rx).await {
+            let key = if flag & FLAG_ENCRYPTION != 0 {
+                Some(Self::generate_encryption_key())
             } else {
-                self.receive_loop(ws_rx, None).await;
+                None
+            };
+            // Older clients don't expect the command codes so they are only
+            // sent if the client asks for them.
+            if flag & FLAG_COMMAND_CODES == 0 || self.send_command_codes(&mut ws_tx, &key).await {
+                self.receive_loop(ws_rx, key).await;
             }
         }
 
@@ -67,13 +82,13 @@ impl SocketContext {
         self.connected.store(false, Ordering::Release);
     }
 
-    async fn receive_encryption_flag(&self, ws_rx: &mut Receiver) -> Option<bool> {
+    async fn receive_flag(&self, ws_rx: &mut Receiver) -> Option<u8> {
         if let Some(flag) = ws_rx.next().await {
             match flag {
                 Ok(f) => {
                     let flag = Self::parse_flag(f);
                     if !flag.is_some() {
-                        eprintln!("Invalid encryption flag");
+                        eprintln!("Invalid flag");
                     }
                     flag
                 }
@@ -87,7 +102,7 @@ impl SocketContext {
         }
     }
 
-    fn parse_flag(message: Message) -> Option<bool> {
+    fn parse_flag(message: Message) -> Option<u8> {
         if !message.is_binary() {
             return None;
         }
@@ -95,11 +110,10 @@ impl SocketContext {
         if data.len() != 1 {
             return None;
         }
-        match data[0] {
-            0 => Some(false),
-            1 => Some(true),
-            _ => None
+        if data[0] & !(FLAG_ENCRYPTION | FLAG_COMMAND_CODES) != 0 {
+            return None;
         }
+        Some(data[0])
     }
 
     fn generate_encryption_key() -> EncryptionKey {
@@ -124,6 +138,48 @@ impl SocketContext {
         key
     }
 
+    // Tell the client which commands it can send. The message contains one
+    // byte for each CommandCode.
+    async fn send_command_codes(&self, ws_tx: &mut Sender, key: &Option<EncryptionKey>) -> bool {
+        let message = match key {
+            Some(key) => match Self::encrypt(key, &self.command_codes) {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return false;
+                }
+            },
+            None => self.command_codes.to_vec(),
+        };
+
+        if let Err(e) = ws_tx.send(Message::Binary(message)).await {
+            eprintln!("{}", e);
+            return false;
+        }
+        true
+    }
+
+    // Encrypt a message in the same format that is expected from the client.
+    fn encrypt(key: &EncryptionKey, plaintext: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        let mut iv = [0; IV_LEN];
+        let mut tag = [0; TAG_LEN];
+        openssl::rand::rand_bytes(&mut iv)?;
+        let ciphertext = symm::encrypt_aead(
+            Cipher::aes_128_gcm(),
+            key,
+            Some(&iv),
+            &[],
+            plaintext,
+            &mut tag
+        )?;
+
+        let mut message = Vec::with_capacity(IV_LEN + ciphertext.len() + TAG_LEN);
+        message.extend_from_slice(&iv);
+        message.extend_from_slice(&ciphertext);
+        message.extend_from_slice(&tag);
+        Ok(message)
+    }
+
     async fn receive_loop(&self, mut ws_rx: Receiver, key: Option<EncryptionKey>) {
         while let Some(message) = ws_rx.next().await {
             match message {
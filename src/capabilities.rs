@@ -0,0 +1,86 @@
+use crate::{CommandCode, Enum};
+
+/// The operations that a [`Context`](crate::Context) supports.
+///
+/// Some operations depend on the platform and some depend on the environment
+/// that the context was created in. This is returned by
+/// `Context::capabilities`.
+///
+/// ```no_run
+/// use tfc::{CommandCode, Context};
+///
+/// let ctx = Context::new().unwrap();
+/// let capabilities = ctx.capabilities();
+/// if !capabilities.supports(CommandCode::FocusWindow) {
+///     println!("Windows can't be focused");
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Capabilities {
+    /// Key events can be generated with
+    /// [`KeyboardContext`](crate::KeyboardContext).
+    pub keyboard: bool,
+    /// Mouse events can be generated with
+    /// [`MouseContext`](crate::MouseContext).
+    pub mouse: bool,
+    /// Characters can be typed with
+    /// [`UnicodeKeyboardContext`](crate::UnicodeKeyboardContext).
+    pub unicode: bool,
+    /// Characters that aren't on the keyboard layout can be typed.
+    ///
+    /// On Linux X11, this needs a keycode without any keysyms so that it can
+    /// be remapped.
+    pub unicode_remapping: bool,
+    /// The cursor location and screen size can be queried with
+    /// [`ScreenContext`](crate::ScreenContext).
+    pub screen: bool,
+    /// The state of the keys can be queried with
+    /// [`KeyStateContext`](crate::KeyStateContext).
+    pub key_state: bool,
+    /// The keyboard layouts can be queried and changed with
+    /// [`LayoutContext`](crate::LayoutContext).
+    pub layout: bool,
+    /// The screen can be captured with
+    /// [`ScreenCaptureContext`](crate::ScreenCaptureContext).
+    pub screen_capture: bool,
+    /// Windows can be listed and managed with
+    /// [`WindowContext`](crate::WindowContext).
+    pub window: bool,
+    /// The clipboard can be read and written with
+    /// [`ClipboardContext`](crate::ClipboardContext).
+    pub clipboard: bool,
+}
+
+impl Capabilities {
+    /// Whether commands with the given code can be executed.
+    ///
+    /// The commands that need the screen, screen capture, window management or
+    /// the clipboard must be executed with
+    /// [`execute_extended`](crate::Command::execute_extended) (see
+    /// [`is_extended`](CommandCode::is_extended)).
+    pub fn supports(&self, code: CommandCode) -> bool {
+        use CommandCode::*;
+        match code {
+            Delay => true,
+            KeyDown | KeyUp | KeyClick | AsciiCharDown | AsciiCharUp | AsciiChar | AsciiString => {
+                self.keyboard
+            }
+            MouseMoveRel | MouseMoveAbs | MouseScroll | MouseDown | MouseUp | MouseClick => {
+                self.mouse
+            }
+            MouseMoveNormalized => self.mouse && self.screen,
+            UnicodeCharDown | UnicodeCharUp | UnicodeChar | UnicodeString => self.unicode,
+            WaitForImage => self.screen_capture,
+            ClickImage => self.screen_capture && self.mouse,
+            FocusWindow => self.window && cfg!(feature = "regex"),
+            SetClipboard => self.clipboard,
+        }
+    }
+
+    /// The codes of the commands that can be executed.
+    pub fn command_codes(&self) -> Vec<CommandCode> {
+        CommandCode::iter()
+            .filter(|code| self.supports(*code))
+            .collect()
+    }
+}
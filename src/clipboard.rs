@@ -0,0 +1,9 @@
+/// A selection that text can be copied to and pasted from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Selection {
+    /// The clipboard that is used by copy (Ctrl+C) and paste (Ctrl+V).
+    Clipboard,
+    /// The text that was most recently selected. This is pasted with the
+    /// middle mouse button. Only Linux-X11 has this selection.
+    Primary,
+}
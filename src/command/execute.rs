@@ -1,10 +1,11 @@
 use super::Command;
-use crate::{traits::*, GenericError};
+use crate::{traits::*, DynContext, DynError, GenericError, Point, Selection, DEFAULT_TOLERANCE};
+use std::time::Duration;
 
 impl Command {
     fn execute_core<C>(&self, ctx: &mut C) -> Result<bool, GenericError<C::PlatformError>>
     where
-        C: FallibleContext + KeyboardContext + MouseContext + AsciiKeyboardContext,
+        C: ?Sized + FallibleContext + KeyboardContext + MouseContext + AsciiKeyboardContext,
     {
         use Command::*;
         match self {
@@ -25,4 +26,178 @@ impl Command {
         }?;
         Ok(true)
     }
+
+    fn execute_unicode<C>(&self, ctx: &mut C) -> Result<(), GenericError<C::PlatformError>>
+    where
+        C: ?Sized + FallibleContext + UnicodeKeyboardContext,
+    {
+        use Command::*;
+        match self {
+            UnicodeCharDown(ch) => ctx.unicode_char_down(*ch),
+            UnicodeCharUp(ch) => ctx.unicode_char_up(*ch),
+            UnicodeChar(ch) => ctx.unicode_char(*ch),
+            UnicodeString(s) => ctx.unicode_string(s.as_str()),
+            _ if self.code().is_extended() => Err(GenericError::Info(
+                "This command must be executed with execute_extended".into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn execute_screen<C>(&self, ctx: &mut C) -> Result<bool, GenericError<C::PlatformError>>
+    where
+        C: ?Sized + FallibleContext + MouseContext + ScreenContext,
+    {
+        if let Command::MouseMoveNormalized(x, y) = self {
+            let max = u16::MAX as f64;
+            ctx.mouse_move_to(Point::normalized(*x as f64 / max, *y as f64 / max))?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn execute_platform<C>(&self, ctx: &mut C) -> Result<bool, GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + MouseContext
+            + ScreenContext
+            + ScreenCaptureContext
+            + WindowContext
+            + ClipboardContext,
+    {
+        use Command::*;
+        let not_found = || GenericError::Info("Image was not found on the screen".into());
+        if self.execute_screen(ctx)? {
+            return Ok(true);
+        }
+        match self {
+            WaitForImage(image, millis) => {
+                let timeout = Duration::from_millis(*millis as u64);
+                ctx.wait_for_image(image, DEFAULT_TOLERANCE, timeout)?
+                    .ok_or_else(not_found)?;
+            }
+            ClickImage(image, button) => {
+                let (x, y) = ctx
+                    .find_on_screen(image, DEFAULT_TOLERANCE)?
+                    .ok_or_else(not_found)?
+                    .center();
+                ctx.mouse_move_abs(x, y)?;
+                ctx.mouse_click(*button)?;
+            }
+            #[cfg(feature = "regex")]
+            FocusWindow(title) => {
+                let title =
+                    regex::Regex::new(title).map_err(|e| GenericError::Info(e.to_string()))?;
+                let window = ctx
+                    .find_window(&|t| title.is_match(t))?
+                    .ok_or_else(|| GenericError::Info("Window was not found".into()))?;
+                ctx.activate_window(window.id)?;
+            }
+            #[cfg(not(feature = "regex"))]
+            FocusWindow(..) => {
+                return Err(GenericError::Info(
+                    "FocusWindow requires the \"regex\" feature".into(),
+                ))
+            }
+            SetClipboard(text) => ctx.set_clipboard_text(Selection::Clipboard, text)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Execute the command on a context.
+    ///
+    /// [`Delay`](Command::Delay) is implemented with [`std::thread::sleep`].
+    /// The commands that need platform-specific contexts (such as
+    /// [`ClickImage`](Command::ClickImage) and
+    /// [`MouseMoveNormalized`](Command::MouseMoveNormalized)) need
+    /// [`execute_extended`](Self::execute_extended) and will return an error
+    /// here.
+    pub fn execute<C>(&self, ctx: &mut C) -> Result<(), GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext,
+    {
+        if self.execute_core(ctx)? {
+            return Ok(());
+        }
+        if let Command::Delay(millis) = self {
+            std::thread::sleep(Duration::from_millis(*millis as u64));
+            return Ok(());
+        }
+        self.execute_unicode(ctx)
+    }
+
+    /// Execute the command on a context asynchronously.
+    ///
+    /// [`Delay`](Command::Delay) is implemented with `tokio::time::sleep`. The
+    /// other commands are executed synchronously.
+    #[cfg(feature = "tokio")]
+    pub async fn execute_async<C>(&self, ctx: &mut C) -> Result<(), GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext,
+    {
+        if self.execute_core(ctx)? {
+            return Ok(());
+        }
+        if let Command::Delay(millis) = self {
+            tokio::time::sleep(Duration::from_millis(*millis as u64)).await;
+            return Ok(());
+        }
+        self.execute_unicode(ctx)
+    }
+
+    /// Execute the command on a context that is chosen at runtime.
+    ///
+    /// This is the same as [`execute`](Self::execute) except that
+    /// [`MouseMoveNormalized`](Command::MouseMoveNormalized) can also be
+    /// executed because a [`DynContext`] can query the screen. The other
+    /// extended commands need a
+    /// [`DynExtendedContext`](crate::DynExtendedContext) to be given to
+    /// [`execute_extended`](Self::execute_extended).
+    pub fn execute_dyn(&self, ctx: &mut dyn DynContext) -> Result<(), DynError> {
+        if self.execute_screen(ctx)? {
+            return Ok(());
+        }
+        self.execute(ctx)
+    }
+
+    /// Execute the command on a context that supports every command.
+    ///
+    /// This is the same as [`execute`](Self::execute) except that the commands
+    /// that need the screen, screen capture, window management and the
+    /// clipboard can also be executed (see
+    /// [`is_extended`](crate::CommandCode::is_extended)).
+    /// [`WaitForImage`](Command::WaitForImage),
+    /// [`ClickImage`](Command::ClickImage) and
+    /// [`FocusWindow`](Command::FocusWindow) return
+    /// [`Info`](GenericError::Info) if the image or window isn't found.
+    pub fn execute_extended<C>(&self, ctx: &mut C) -> Result<(), GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext
+            + ScreenContext
+            + ScreenCaptureContext
+            + WindowContext
+            + ClipboardContext,
+    {
+        if self.execute_platform(ctx)? {
+            return Ok(());
+        }
+        self.execute(ctx)
+    }
 }
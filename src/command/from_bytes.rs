@@ -1,5 +1,5 @@
 use super::Command;
-use crate::{CommandCode, Enum, Key, MouseButton};
+use crate::{CommandCode, Enum, Image, Key, MouseButton};
 use std::fmt::{self, Display, Formatter};
 
 /// Error enum returned by [`Command::from_bytes`].
@@ -42,6 +42,10 @@ fn parse_i32(b_0: u8, b_1: u8) -> i32 {
     (((b_0 as i16) << 8) | (b_1 as i16)) as i32
 }
 
+fn parse_u16(b_0: u8, b_1: u8) -> u16 {
+    ((b_0 as u16) << 8) | (b_1 as u16)
+}
+
 fn parse_u32(b_0: u8, b_1: u8) -> u32 {
     (((b_0 as u16) << 8) | (b_1 as u16)) as u32
 }
@@ -55,6 +59,17 @@ fn parse_string(buf: &[u8]) -> Result<String, CommandBytesError> {
     String::from_utf8(buf.to_owned()).map_err(|_| InvalidUTF8)
 }
 
+// Returns the image and the index of the byte after it.
+fn parse_image(buf: &[u8], start: usize) -> Result<(Image, usize), CommandBytesError> {
+    check_buffer_length(buf, start + 4)?;
+    let width = parse_u32(buf[start], buf[start + 1]);
+    let height = parse_u32(buf[start + 2], buf[start + 3]);
+    let end = start + 4 + width as usize * height as usize * 4;
+    check_buffer_length(buf, end)?;
+    let image = Image::from_rgba(width, height, buf[start + 4..end].to_owned()).unwrap();
+    Ok((image, end))
+}
+
 fn parse_command_code(byte: u8) -> Result<CommandCode, CommandBytesError> {
     CommandCode::from_u8(byte).ok_or(InvalidCommandCode(byte))
 }
@@ -96,6 +111,9 @@ impl Command {
     /// length followed by a sequence of bytes, however the sequence of bytes
     /// are a UTF-8 encoded string.
     ///
+    /// An image is its width and height as unsigned 16-bit big-endian integers
+    /// followed by its RGBA pixels.
+    ///
     /// The function returns the command and the number of bytes that were read
     /// from the buffer.
     ///
@@ -228,6 +246,48 @@ impl Command {
                 check_buffer_length(buf, len)?;
                 Ok((Command::UnicodeString(parse_string(&buf[3..len])?), len))
             }
+
+            CommandCode::WaitForImage => {
+                let (image, len) = parse_image(buf, 1)?;
+                check_buffer_length(buf, len + 2)?;
+                Ok((
+                    Command::WaitForImage(image, parse_u32(buf[len], buf[len + 1])),
+                    len + 2,
+                ))
+            }
+            CommandCode::ClickImage => {
+                let (image, len) = parse_image(buf, 1)?;
+                check_buffer_length(buf, len + 1)?;
+                Ok((
+                    Command::ClickImage(image, parse_mouse_button(buf[len])?),
+                    len + 1,
+                ))
+            }
+
+            CommandCode::FocusWindow => {
+                check_buffer_length(buf, 3)?;
+                let len = 3 + parse_u32(buf[1], buf[2]) as usize;
+                check_buffer_length(buf, len)?;
+                Ok((Command::FocusWindow(parse_string(&buf[3..len])?), len))
+            }
+
+            CommandCode::SetClipboard => {
+                check_buffer_length(buf, 3)?;
+                let len = 3 + parse_u32(buf[1], buf[2]) as usize;
+                check_buffer_length(buf, len)?;
+                Ok((Command::SetClipboard(parse_string(&buf[3..len])?), len))
+            }
+
+            CommandCode::MouseMoveNormalized => {
+                check_buffer_length(buf, 5)?;
+                Ok((
+                    Command::MouseMoveNormalized(
+                        parse_u16(buf[1], buf[2]),
+                        parse_u16(buf[3], buf[4]),
+                    ),
+                    5,
+                ))
+            }
         }
     }
 }
@@ -2,7 +2,7 @@ mod execute;
 mod from_bytes;
 mod to_bytes;
 
-use crate::{Key, MouseButton};
+use crate::{CommandCode, Image, Key, MouseButton};
 
 pub use from_bytes::CommandBytesError;
 
@@ -49,4 +49,56 @@ pub enum Command {
     UnicodeChar(char),
     /// Corresponds to [`unicode_string`](crate::UnicodeKeyboardContext::unicode_string).
     UnicodeString(String),
+    /// Waits up to a number of milliseconds for an image to appear on the
+    /// screen. Corresponds to
+    /// [`wait_for_image`](crate::ScreenCaptureContext::wait_for_image).
+    WaitForImage(Image, u32),
+    /// Clicks a mouse button in the center of an image on the screen. Uses
+    /// [`find_on_screen`](crate::ScreenCaptureContext::find_on_screen).
+    ClickImage(Image, MouseButton),
+    /// Activates the topmost window with a title that matches a regular
+    /// expression. Uses [`find_window`](crate::WindowContext::find_window).
+    /// This requires the `"regex"` feature.
+    FocusWindow(String),
+    /// Puts text into the clipboard. Corresponds to
+    /// [`set_clipboard_text`](crate::ClipboardContext::set_clipboard_text)
+    /// with [`Selection::Clipboard`](crate::Selection::Clipboard).
+    SetClipboard(String),
+    /// Moves the mouse to a position given as a fraction of the size of the
+    /// screen. The coordinates are in units of 1/65535 so `(0, 0)` is the
+    /// top-left of the screen and `(65535, 65535)` is the bottom-right. Uses
+    /// [`mouse_move_to`](crate::MouseContext::mouse_move_to) with a
+    /// [`Normalized`](crate::CoordinateSpace::Normalized) point.
+    MouseMoveNormalized(u16, u16),
+}
+
+impl Command {
+    /// Get the [`CommandCode`] that identifies the variant.
+    pub fn code(&self) -> CommandCode {
+        match self {
+            Command::Delay(..) => CommandCode::Delay,
+            Command::KeyDown(..) => CommandCode::KeyDown,
+            Command::KeyUp(..) => CommandCode::KeyUp,
+            Command::KeyClick(..) => CommandCode::KeyClick,
+            Command::MouseMoveRel(..) => CommandCode::MouseMoveRel,
+            Command::MouseMoveAbs(..) => CommandCode::MouseMoveAbs,
+            Command::MouseScroll(..) => CommandCode::MouseScroll,
+            Command::MouseDown(..) => CommandCode::MouseDown,
+            Command::MouseUp(..) => CommandCode::MouseUp,
+            Command::MouseClick(..) => CommandCode::MouseClick,
+            Command::AsciiCharDown(..) => CommandCode::AsciiCharDown,
+            Command::AsciiCharUp(..) => CommandCode::AsciiCharUp,
+            Command::AsciiChar(..) => CommandCode::AsciiChar,
+            Command::AsciiString(..) => CommandCode::AsciiString,
+            Command::UnicodeCharDown(..) => CommandCode::UnicodeCharDown,
+            Command::UnicodeCharUp(..) => CommandCode::UnicodeCharUp,
+            Command::UnicodeChar(..) => CommandCode::UnicodeChar,
+            Command::UnicodeString(..) => CommandCode::UnicodeString,
+            Command::WaitForImage(..) => CommandCode::WaitForImage,
+            Command::ClickImage(..) => CommandCode::ClickImage,
+            Command::FocusWindow(..) => CommandCode::FocusWindow,
+            Command::SetClipboard(..) => CommandCode::SetClipboard,
+            Command::MouseMoveNormalized(..) => CommandCode::MouseMoveNormalized,
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use super::Command;
-use crate::{CommandCode, Enum};
+use crate::{CommandCode, Enum, Image};
 
 trait ToBytes {
     fn byte_size(&self) -> usize;
@@ -39,6 +39,17 @@ impl ToBytes for i32 {
     }
 }
 
+impl ToBytes for u16 {
+    fn byte_size(&self) -> usize {
+        2
+    }
+
+    fn write_bytes(self, buf: &mut [u8]) {
+        buf[0] = (self >> 8) as u8;
+        buf[1] = self as u8;
+    }
+}
+
 impl ToBytes for u32 {
     fn byte_size(&self) -> usize {
         2
@@ -79,6 +90,19 @@ impl ToBytes for &[u8] {
     }
 }
 
+impl ToBytes for &Image {
+    fn byte_size(&self) -> usize {
+        self.width().byte_size() + self.height().byte_size() + self.as_rgba().len()
+    }
+
+    fn write_bytes(self, buf: &mut [u8]) {
+        let rgba = self.as_rgba();
+        self.width().write_bytes(buf);
+        self.height().write_bytes(&mut buf[2..]);
+        buf[4..4 + rgba.len()].copy_from_slice(rgba);
+    }
+}
+
 macro_rules! byte_size_sum {
     ($first:expr) => {
         $first.byte_size()
@@ -189,6 +213,15 @@ impl Command {
             Command::UnicodeCharUp(ch) => write_command!(buf, UnicodeCharUp, *ch),
             Command::UnicodeChar(ch) => write_command!(buf, UnicodeChar, *ch),
             Command::UnicodeString(string) => write_command!(buf, UnicodeString, string.as_bytes()),
+
+            Command::WaitForImage(image, timeout) => {
+                write_command!(buf, WaitForImage, image, *timeout)
+            }
+            Command::ClickImage(image, button) => write_command!(buf, ClickImage, image, *button),
+
+            Command::FocusWindow(title) => write_command!(buf, FocusWindow, title.as_bytes()),
+            Command::SetClipboard(text) => write_command!(buf, SetClipboard, text.as_bytes()),
+            Command::MouseMoveNormalized(x, y) => write_command!(buf, MouseMoveNormalized, *x, *y),
         }
     }
 
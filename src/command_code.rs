@@ -20,5 +20,27 @@ enumeration!(
         (UnicodeCharUp, "Unicode Character Up"),
         (UnicodeChar, "Unicode Character"),
         (UnicodeString, "Unicode String"),
+        (WaitForImage, "Wait For Image"),
+        (ClickImage, "Click Image"),
+        (FocusWindow, "Focus Window"),
+        (SetClipboard, "Set Clipboard"),
+        (MouseMoveNormalized, "Mouse Move Normalized"),
     ]
 );
+
+impl CommandCode {
+    /// Whether the command must be executed with
+    /// [`execute_extended`](crate::Command::execute_extended).
+    ///
+    /// These commands need the screen, screen capture, window management or
+    /// the clipboard. [`execute`](crate::Command::execute) returns an error
+    /// for them. [`execute_dyn`](crate::Command::execute_dyn) can also
+    /// execute [`MouseMoveNormalized`](Self::MouseMoveNormalized).
+    pub fn is_extended(self) -> bool {
+        use CommandCode::*;
+        matches!(
+            self,
+            MouseMoveNormalized | WaitForImage | ClickImage | FocusWindow | SetClipboard
+        )
+    }
+}
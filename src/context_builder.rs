@@ -0,0 +1,133 @@
+use crate::{Context, Error};
+use std::time::Duration;
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) const DEFAULT_DEVICE_NAME: &str = "The Fat Controller";
+
+/// A builder for configuring a [`Context`].
+///
+/// [`Context::new`] uses the default configuration. Options that don't apply
+/// to the current platform are ignored.
+///
+/// ```no_run
+/// use tfc::{ContextBuilder, Error, traits::*};
+///
+/// fn main() -> Result<(), Error> {
+///     let mut ctx = ContextBuilder::new().display(":99").build()?;
+///     ctx.unicode_string("Hello world!")
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct ContextBuilder {
+    pub(crate) display: Option<String>,
+    pub(crate) screen: Option<i32>,
+    pub(crate) device_name: Option<String>,
+    pub(crate) vendor: u16,
+    pub(crate) product: u16,
+    pub(crate) key_delay: Duration,
+    pub(crate) normalize_key_state: bool,
+}
+
+impl ContextBuilder {
+    /// Create a builder with the default configuration.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the name of the display to connect to (Linux-X11).
+    ///
+    /// This is a name like `":99"` or `"localhost:1.0"`. By default, the
+    /// `DISPLAY` environment variable is used.
+    pub fn display(mut self, name: &str) -> Self {
+        self.display = Some(name.to_owned());
+        self
+    }
+
+    /// Set the screen number to use (Linux-X11).
+    ///
+    /// By default, the default screen of the display is used.
+    pub fn screen(mut self, screen: i32) -> Self {
+        self.screen = Some(screen);
+        self
+    }
+
+    /// Set the name of the virtual input device (Linux-Wayland).
+    ///
+    /// This only applies to contexts created with `build_uinput`, which
+    /// requires the `"uinput"` feature. The name is truncated to 79 bytes. By
+    /// default, the device is called `"The Fat Controller"`.
+    pub fn device_name(mut self, name: &str) -> Self {
+        self.device_name = Some(name.to_owned());
+        self
+    }
+
+    /// Set the USB vendor ID of the virtual input device (Linux-Wayland).
+    ///
+    /// Like [`device_name`](Self::device_name), this only applies to contexts
+    /// created with `build_uinput`.
+    pub fn vendor(mut self, vendor: u16) -> Self {
+        self.vendor = vendor;
+        self
+    }
+
+    /// Set the USB product ID of the virtual input device (Linux-Wayland).
+    ///
+    /// Like [`device_name`](Self::device_name), this only applies to contexts
+    /// created with `build_uinput`.
+    pub fn product(mut self, product: u16) -> Self {
+        self.product = product;
+        self
+    }
+
+    /// Set the delay between characters typed by
+    /// [`unicode_string`](crate::UnicodeKeyboardContext::unicode_string)
+    /// (Linux).
+    ///
+    /// Some applications drop characters when they are typed too quickly,
+    /// particularly when the keyboard mapping changes between characters. By
+    /// default, there is no delay.
+    pub fn key_delay(mut self, delay: Duration) -> Self {
+        self.key_delay = delay;
+        self
+    }
+
+    /// Release held modifiers and turn off Caps Lock while typing strings with
+    /// [`unicode_string`](crate::UnicodeKeyboardContext::unicode_string) or
+    /// [`ascii_string`](crate::AsciiKeyboardContext::ascii_string) (Linux).
+    ///
+    /// This uses
+    /// [`with_normalized_state`](crate::KeyStateContext::with_normalized_state)
+    /// so Caps Lock is turned back on afterwards. By default, strings are
+    /// typed without checking the state of the keyboard.
+    pub fn normalize_key_state(mut self, normalize: bool) -> Self {
+        self.normalize_key_state = normalize;
+        self
+    }
+
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) fn device_name_or_default(&self) -> &str {
+        match &self.device_name {
+            Some(name) => name,
+            None => DEFAULT_DEVICE_NAME,
+        }
+    }
+
+    /// Create a [`Context`] with this configuration.
+    pub fn build(&self) -> Result<Context, Error> {
+        #[cfg(target_os = "linux")]
+        return Context::from_builder(self);
+        #[cfg(not(target_os = "linux"))]
+        return Context::new();
+    }
+
+    /// Create a [`UinputContext`](crate::UinputContext) with this
+    /// configuration.
+    ///
+    /// This uses `/dev/uinput` instead of X11. Only the device name, vendor,
+    /// product, key delay and key state options apply to it.
+    #[cfg(all(target_os = "linux", feature = "uinput"))]
+    pub fn build_uinput(&self) -> Result<crate::UinputContext, crate::UinputError> {
+        crate::UinputContext::from_builder(self)
+    }
+}
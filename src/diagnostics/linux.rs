@@ -0,0 +1,235 @@
+use super::{Diagnostic, DiagnosticStatus};
+use crate::ContextBuilder;
+use std::{ffi::CString, fs};
+#[cfg(feature = "uinput")]
+use std::{fs::OpenOptions, io::ErrorKind};
+use x11::xlib;
+
+#[cfg(feature = "uinput")]
+const UINPUT_PATH: &str = "/dev/uinput";
+
+pub(super) fn diagnose(builder: &ContextBuilder, diagnostics: &mut Vec<Diagnostic>) {
+    x11(builder, diagnostics);
+    // /dev/uinput is only used by UinputContext.
+    #[cfg(feature = "uinput")]
+    diagnostics.push(uinput());
+    diagnostics.push(input_group());
+}
+
+unsafe fn has_extension(display: *mut xlib::Display, name: &str) -> bool {
+    let name = CString::new(name).unwrap();
+    let mut opcode = 0;
+    let mut event_base = 0;
+    let mut error_base = 0;
+    xlib::XQueryExtension(
+        display,
+        name.as_ptr(),
+        &mut opcode,
+        &mut event_base,
+        &mut error_base,
+    ) != xlib::False
+}
+
+fn x11(builder: &ContextBuilder, diagnostics: &mut Vec<Diagnostic>) {
+    let display_name = builder
+        .display
+        .clone()
+        .or_else(|| std::env::var("DISPLAY").ok());
+
+    let display = match &display_name {
+        Some(name) => match CString::new(name.as_str()) {
+            Ok(name) => unsafe { xlib::XOpenDisplay(name.as_ptr()) },
+            Err(_) => std::ptr::null_mut(),
+        },
+        None => std::ptr::null_mut(),
+    };
+
+    if display.is_null() {
+        diagnostics.push(match &display_name {
+            Some(name) => Diagnostic::problem(
+                "display",
+                DiagnosticStatus::Error,
+                format!("Couldn't connect to the X server at \"{}\"", name),
+                "Check that the X server is running and that this user is allowed to \
+                 connect to it (see xhost)",
+            ),
+            None => Diagnostic::problem(
+                "display",
+                DiagnosticStatus::Error,
+                "The DISPLAY environment variable isn't set",
+                "Set DISPLAY to the X server, for example \"export DISPLAY=:0\". On \
+                 Wayland, the XWayland server must be running",
+            ),
+        });
+        for check in ["xtest", "xkb", "xrandr", "keycode"] {
+            diagnostics.push(Diagnostic::skipped(
+                check,
+                "Needs a connection to the X server",
+            ));
+        }
+        return;
+    }
+
+    diagnostics.push(Diagnostic::ok(
+        "display",
+        format!(
+            "Connected to the X server at \"{}\"",
+            display_name.unwrap_or_default()
+        ),
+    ));
+
+    let (xtest, xkb, xrandr) = unsafe {
+        let extensions = (
+            has_extension(display, "XTEST"),
+            has_extension(display, "XKEYBOARD"),
+            has_extension(display, "RANDR"),
+        );
+        xlib::XCloseDisplay(display);
+        extensions
+    };
+
+    diagnostics.push(if xtest {
+        Diagnostic::ok("xtest", "The XTest extension is available")
+    } else {
+        Diagnostic::problem(
+            "xtest",
+            DiagnosticStatus::Error,
+            "The XTest extension is unavailable so events can't be generated",
+            "Enable the XTEST extension in the X server configuration (it may have been \
+             disabled with \"-extension XTEST\")",
+        )
+    });
+    diagnostics.push(if xkb {
+        Diagnostic::ok("xkb", "The XKB extension is available")
+    } else {
+        Diagnostic::problem(
+            "xkb",
+            DiagnosticStatus::Error,
+            "The XKB extension is unavailable so the keyboard layout can't be read",
+            "Enable the XKEYBOARD extension in the X server configuration",
+        )
+    });
+    diagnostics.push(if xrandr {
+        Diagnostic::ok("xrandr", "The XRandR extension is available")
+    } else {
+        Diagnostic::problem(
+            "xrandr",
+            DiagnosticStatus::Warning,
+            "The XRandR extension is unavailable so the monitors can't be listed and \
+             the whole screen is treated as one monitor",
+            "Enable the RANDR extension in the X server configuration",
+        )
+    });
+
+    if !xtest || !xkb {
+        diagnostics.push(Diagnostic::skipped(
+            "keycode",
+            "Needs the XTest and XKB extensions",
+        ));
+        return;
+    }
+
+    diagnostics.push(match builder.build() {
+        Ok(ctx) if ctx.capabilities().unicode_remapping => {
+            Diagnostic::ok("keycode", "There is a free keycode for remapping")
+        }
+        Ok(_) => Diagnostic::problem(
+            "keycode",
+            DiagnosticStatus::Warning,
+            "Every keycode has a keysym so characters that aren't on the keyboard \
+             layout can't be typed",
+            "Free a keycode that isn't on the keyboard, for example \
+             \"xmodmap -e 'keycode 255 ='\"",
+        ),
+        Err(e) => Diagnostic::problem(
+            "keycode",
+            DiagnosticStatus::Error,
+            format!("Couldn't create a context: {}", e),
+            "Check the errors above",
+        ),
+    });
+}
+
+#[cfg(feature = "uinput")]
+fn uinput() -> Diagnostic {
+    match OpenOptions::new().write(true).open(UINPUT_PATH) {
+        Ok(_) => Diagnostic::ok("uinput", format!("{} is writable", UINPUT_PATH)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Diagnostic::problem(
+            "uinput",
+            DiagnosticStatus::Warning,
+            format!(
+                "{} doesn't exist so UinputContext can't be used",
+                UINPUT_PATH
+            ),
+            "Load the uinput kernel module with \"sudo modprobe uinput\"",
+        ),
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => Diagnostic::problem(
+            "uinput",
+            DiagnosticStatus::Warning,
+            format!(
+                "{} isn't writable by this user so UinputContext can't be used",
+                UINPUT_PATH
+            ),
+            "Add the udev rule 'KERNEL==\"uinput\", MODE=\"0660\", GROUP=\"uinput\", \
+             OPTIONS+=\"static_node=uinput\"' to /etc/udev/rules.d/60-tfc.rules and add \
+             this user to the uinput group",
+        ),
+        Err(e) => Diagnostic::problem(
+            "uinput",
+            DiagnosticStatus::Warning,
+            format!("Couldn't open {}: {}", UINPUT_PATH, e),
+            format!("Check the permissions of {}", UINPUT_PATH),
+        ),
+    }
+}
+
+// Find the ID of a group in /etc/group.
+fn group_id(name: &str) -> Option<u32> {
+    fs::read_to_string("/etc/group")
+        .ok()?
+        .lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.len() >= 3 && fields[0] == name)?[2]
+        .parse()
+        .ok()
+}
+
+// The effective group and the supplementary groups of this process.
+fn process_groups() -> Vec<u32> {
+    let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+    let mut groups = Vec::new();
+    for line in status.lines() {
+        if let Some(gids) = line.strip_prefix("Gid:") {
+            // The real, effective, saved and file system IDs.
+            groups.extend(
+                gids.split_whitespace()
+                    .nth(1)
+                    .and_then(|id| id.parse::<u32>().ok()),
+            );
+        } else if let Some(gids) = line.strip_prefix("Groups:") {
+            groups.extend(
+                gids.split_whitespace()
+                    .filter_map(|id| id.parse::<u32>().ok()),
+            );
+        }
+    }
+    groups
+}
+
+fn input_group() -> Diagnostic {
+    let gid = match group_id("input") {
+        Some(gid) => gid,
+        None => return Diagnostic::skipped("input_group", "There is no input group"),
+    };
+    if process_groups().contains(&gid) {
+        Diagnostic::ok("input_group", "This user is in the input group")
+    } else {
+        Diagnostic::problem(
+            "input_group",
+            DiagnosticStatus::Warning,
+            "This user isn't in the input group so the devices in /dev/input can't be \
+             read (needed by --evdev)",
+            "Run \"sudo usermod -aG input $USER\" and then log out and back in",
+        )
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(target_os = "linux")]
+mod linux;
+
+use crate::ContextBuilder;
+use std::fmt::{self, Display, Formatter};
+
+/// The outcome of a [`Diagnostic`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DiagnosticStatus {
+    /// The check passed.
+    Ok,
+    /// Some features won't work.
+    Warning,
+    /// Events can't be generated.
+    Error,
+    /// The check wasn't performed because an earlier check failed.
+    Skipped,
+}
+
+impl DiagnosticStatus {
+    /// The name of the status in lowercase.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticStatus::Ok => "ok",
+            DiagnosticStatus::Warning => "warning",
+            DiagnosticStatus::Error => "error",
+            DiagnosticStatus::Skipped => "skipped",
+        }
+    }
+}
+
+impl Display for DiagnosticStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The result of checking one part of the environment.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// A short name for the check, such as `"xtest"`.
+    pub check: &'static str,
+    /// Whether the check passed.
+    pub status: DiagnosticStatus,
+    /// A description of what was found.
+    pub message: String,
+    /// What the user can do to fix the problem.
+    pub fix: Option<String>,
+}
+
+impl Diagnostic {
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            status: DiagnosticStatus::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        check: &'static str,
+        status: DiagnosticStatus,
+        message: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            check,
+            status,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn skipped(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            status: DiagnosticStatus::Skipped,
+            message: message.into(),
+            fix: None,
+        }
+    }
+}
+
+/// Check the environment for the common reasons that a
+/// [`Context`](crate::Context) can't be created or some features don't work.
+///
+/// On Linux, this checks the connection to the X server, the XTest, XKB and
+/// XRandR extensions, whether there is a free keycode for typing characters
+/// that aren't on the keyboard layout and membership of the `input` group.
+/// Access to `/dev/uinput` is also checked if the `uinput` feature is enabled.
+/// On the other platforms, this checks that a context can be created.
+///
+/// ```no_run
+/// use tfc::{ContextBuilder, DiagnosticStatus};
+///
+/// for diagnostic in tfc::diagnose(&ContextBuilder::new()) {
+///     if diagnostic.status != DiagnosticStatus::Ok {
+///         println!("{}: {}", diagnostic.check, diagnostic.message);
+///     }
+/// }
+/// ```
+pub fn diagnose(builder: &ContextBuilder) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    #[cfg(target_os = "linux")]
+    linux::diagnose(builder, &mut diagnostics);
+    #[cfg(not(target_os = "linux"))]
+    diagnostics.push(context(builder));
+    diagnostics
+}
+
+#[cfg(not(target_os = "linux"))]
+fn context(builder: &ContextBuilder) -> Diagnostic {
+    #[cfg(target_os = "macos")]
+    const FIX: &str = "Allow the terminal (or the application using TFC) to control \
+                       the computer in System Settings > Privacy & Security > Accessibility";
+    #[cfg(not(target_os = "macos"))]
+    const FIX: &str = "Events can't be sent to applications running as administrator \
+                       unless TFC is also running as administrator";
+
+    match builder.build() {
+        Ok(_) => Diagnostic::ok("context", "A context can be created"),
+        Err(e) => Diagnostic::problem(
+            "context",
+            DiagnosticStatus::Error,
+            format!("Couldn't create a context: {}", e),
+            FIX,
+        ),
+    }
+}
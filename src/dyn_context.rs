@@ -0,0 +1,325 @@
+use crate::{
+    traits::*, GenericError, Image, Key, MouseButton, Point, Rect, Selection, WindowId, WindowInfo,
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+/// The platform-specific error of a [`DynContext`].
+///
+/// This holds the platform-specific error of the underlying context.
+#[derive(Debug)]
+pub struct DynPlatformError(Box<dyn std::error::Error + Send + Sync>);
+
+impl DynPlatformError {
+    /// Get the underlying error if it has the given type.
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        self.0.downcast_ref()
+    }
+
+    /// Unwrap the underlying error.
+    pub fn into_inner(self) -> Box<dyn std::error::Error + Send + Sync> {
+        self.0
+    }
+}
+
+impl Display for DynPlatformError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for DynPlatformError {}
+
+/// Error type returned by a [`DynContext`].
+pub type DynError = GenericError<DynPlatformError>;
+
+fn erase<T, P>(result: Result<T, GenericError<P>>) -> Result<T, DynError>
+where
+    P: std::error::Error + Send + Sync + 'static,
+{
+    result.map_err(|e| e.map_platform(|p| DynPlatformError(Box::new(p))))
+}
+
+// Generate a list of methods in one of three forms:
+//
+// - `decl` declares the `dyn_` methods of DynContext or DynExtendedContext.
+// - `erase` implements the `dyn_` methods for every context by calling the
+//   trait methods and erasing the error.
+// - `object` implements the trait methods for the trait objects by calling
+//   the `dyn_` methods.
+//
+// The methods with default implementations are included so that the
+// overrides of the underlying context are used.
+macro_rules! dyn_methods {
+    ($mode:ident;) => {};
+    (
+        $mode:ident;
+        fn $name:ident => $dyn_name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;
+        $($rest:tt)*
+    ) => {
+        dyn_method!($mode, [&self], self, $name, $dyn_name, [$($arg: $ty),*], $ret);
+        dyn_methods!($mode; $($rest)*);
+    };
+    (
+        $mode:ident;
+        fn $name:ident => $dyn_name:ident(&mut self $(, $arg:ident: $ty:ty)*) -> $ret:ty;
+        $($rest:tt)*
+    ) => {
+        dyn_method!($mode, [&mut self], self, $name, $dyn_name, [$($arg: $ty),*], $ret);
+        dyn_methods!($mode; $($rest)*);
+    };
+}
+
+macro_rules! dyn_method {
+    (
+        decl, [$($recv:tt)*], $this:ident, $name:ident, $dyn_name:ident,
+        [$($arg:ident: $ty:ty),*], $ret:ty
+    ) => {
+        fn $dyn_name($($recv)* $(, $arg: $ty)*) -> Result<$ret, DynError>;
+    };
+    (
+        erase, [$($recv:tt)*], $this:ident, $name:ident, $dyn_name:ident,
+        [$($arg:ident: $ty:ty),*], $ret:ty
+    ) => {
+        fn $dyn_name($($recv)* $(, $arg: $ty)*) -> Result<$ret, DynError> {
+            erase($this.$name($($arg),*))
+        }
+    };
+    (
+        object, [$($recv:tt)*], $this:ident, $name:ident, $dyn_name:ident,
+        [$($arg:ident: $ty:ty),*], $ret:ty
+    ) => {
+        fn $name($($recv)* $(, $arg: $ty)*) -> Result<$ret, DynError> {
+            $this.$dyn_name($($arg),*)
+        }
+    };
+}
+
+macro_rules! keyboard_methods {
+    ($mode:ident) => {
+        dyn_methods! {
+            $mode;
+            fn key_down => dyn_key_down(&mut self, key: Key) -> ();
+            fn key_up => dyn_key_up(&mut self, key: Key) -> ();
+            fn key_click => dyn_key_click(&mut self, key: Key) -> ();
+            fn begin_string => dyn_begin_string(&mut self) -> bool;
+            fn end_string => dyn_end_string(&mut self, caps_lock: bool) -> ();
+        }
+    };
+}
+
+macro_rules! unicode_methods {
+    ($mode:ident) => {
+        dyn_methods! {
+            $mode;
+            fn unicode_char_down => dyn_unicode_char_down(&mut self, ch: char) -> ();
+            fn unicode_char_up => dyn_unicode_char_up(&mut self, ch: char) -> ();
+            fn unicode_char => dyn_unicode_char(&mut self, ch: char) -> ();
+            fn unicode_string => dyn_unicode_string(&mut self, s: &str) -> ();
+        }
+    };
+}
+
+macro_rules! mouse_methods {
+    ($mode:ident) => {
+        dyn_methods! {
+            $mode;
+            fn mouse_move_rel => dyn_mouse_move_rel(&mut self, dx: i32, dy: i32) -> ();
+            fn mouse_move_abs => dyn_mouse_move_abs(&mut self, x: i32, y: i32) -> ();
+            fn mouse_scroll => dyn_mouse_scroll(&mut self, dx: i32, dy: i32) -> ();
+            fn mouse_down => dyn_mouse_down(&mut self, button: MouseButton) -> ();
+            fn mouse_up => dyn_mouse_up(&mut self, button: MouseButton) -> ();
+            fn mouse_click => dyn_mouse_click(&mut self, button: MouseButton) -> ();
+        }
+    };
+}
+
+macro_rules! screen_methods {
+    ($mode:ident) => {
+        dyn_methods! {
+            $mode;
+            fn cursor_location => dyn_cursor_location(&self) -> (i32, i32);
+            fn screen_size => dyn_screen_size(&self) -> (i32, i32);
+            fn monitors => dyn_monitors(&self) -> Vec<Rect>;
+            fn to_pixels => dyn_to_pixels(&self, point: Point) -> (i32, i32);
+            fn cursor_location_normalized => dyn_cursor_location_normalized(&self) -> Point;
+        }
+    };
+}
+
+macro_rules! screen_capture_methods {
+    ($mode:ident) => {
+        dyn_methods! {
+            $mode;
+            fn pixel => dyn_pixel(&self, x: i32, y: i32) -> [u8; 4];
+            fn capture => dyn_capture(&self, rect: Rect) -> Image;
+        }
+    };
+}
+
+macro_rules! window_methods {
+    ($mode:ident) => {
+        dyn_methods! {
+            $mode;
+            fn windows => dyn_windows(&self) -> Vec<WindowInfo>;
+            fn active_window => dyn_active_window(&self) -> Option<WindowId>;
+            fn activate_window => dyn_activate_window(&mut self, id: WindowId) -> ();
+            fn raise_window => dyn_raise_window(&mut self, id: WindowId) -> ();
+            fn minimize_window => dyn_minimize_window(&mut self, id: WindowId) -> ();
+            fn move_window => dyn_move_window(&mut self, id: WindowId, x: i32, y: i32) -> ();
+            fn resize_window => dyn_resize_window(
+                &mut self,
+                id: WindowId,
+                width: u32,
+                height: u32
+            ) -> ();
+            fn find_window => dyn_find_window(
+                &self,
+                title: &dyn Fn(&str) -> bool
+            ) -> Option<WindowInfo>;
+            fn wait_for_window => dyn_wait_for_window(
+                &self,
+                title: &dyn Fn(&str) -> bool,
+                timeout: Duration
+            ) -> Option<WindowInfo>;
+        }
+    };
+}
+
+macro_rules! clipboard_methods {
+    ($mode:ident) => {
+        dyn_methods! {
+            $mode;
+            fn clipboard_text => dyn_clipboard_text(&self, selection: Selection) -> Option<String>;
+            fn set_clipboard_text => dyn_set_clipboard_text(
+                &mut self,
+                selection: Selection,
+                text: &str
+            ) -> ();
+            fn clear_clipboard => dyn_clear_clipboard(&mut self, selection: Selection) -> ();
+        }
+    };
+}
+
+/// An object-safe context for choosing the backend at runtime.
+///
+/// The [`traits`](crate::traits) can't be used as trait objects because the
+/// error type depends on the context. This trait erases the error type into a
+/// [`DynError`] so that a `Box<dyn DynContext>` can hold any context. It is
+/// implemented for every context that implements [`KeyboardContext`],
+/// [`UnicodeKeyboardContext`], [`MouseContext`] and [`ScreenContext`], so
+/// other crates can provide a backend by implementing those traits. A backend
+/// that can't query the screen can return [`Info`](GenericError::Info) from
+/// the [`ScreenContext`] methods.
+///
+/// The methods aren't meant to be called directly. `dyn DynContext`
+/// implements the traits so it can be used like any other context, and a
+/// [`Command`](crate::Command) can be executed on it with
+/// [`execute_dyn`](crate::Command::execute_dyn) or an
+/// [`Executor`](crate::Executor).
+///
+/// ```no_run
+/// use tfc::{traits::*, Context, DynContext, Recorder};
+///
+/// let mut ctx: Box<dyn DynContext> = if std::env::args().any(|a| a == "--record") {
+///     Box::new(Recorder::new())
+/// } else {
+///     Box::new(Context::new().unwrap())
+/// };
+/// ctx.unicode_string("Hello").unwrap();
+/// ```
+pub trait DynContext {
+    keyboard_methods!(decl);
+    unicode_methods!(decl);
+    mouse_methods!(decl);
+    screen_methods!(decl);
+}
+
+/// An object-safe context that supports every command.
+///
+/// This extends [`DynContext`] with the screen capture, window and clipboard
+/// methods. It is implemented for every context that also implements
+/// [`ScreenCaptureContext`], [`WindowContext`] and [`ClipboardContext`]. A `dyn DynExtendedContext` can be passed to
+/// [`execute_extended`](crate::Command::execute_extended).
+pub trait DynExtendedContext: DynContext {
+    /// Convert to a [`DynContext`].
+    fn as_dyn_context(&mut self) -> &mut dyn DynContext;
+
+    screen_capture_methods!(decl);
+    window_methods!(decl);
+    clipboard_methods!(decl);
+}
+
+impl<C> DynContext for C
+where
+    C: KeyboardContext + UnicodeKeyboardContext + MouseContext + ScreenContext,
+    C::PlatformError: Send + Sync + 'static,
+{
+    keyboard_methods!(erase);
+    unicode_methods!(erase);
+    mouse_methods!(erase);
+    screen_methods!(erase);
+}
+
+impl<C> DynExtendedContext for C
+where
+    C: KeyboardContext
+        + UnicodeKeyboardContext
+        + MouseContext
+        + ScreenContext
+        + ScreenCaptureContext
+        + WindowContext
+        + ClipboardContext,
+    C::PlatformError: Send + Sync + 'static,
+{
+    fn as_dyn_context(&mut self) -> &mut dyn DynContext {
+        self
+    }
+
+    screen_capture_methods!(erase);
+    window_methods!(erase);
+    clipboard_methods!(erase);
+}
+
+// Implement the traits for the trait objects so that they can be used with
+// generic code such as Command::execute.
+macro_rules! impl_dyn_traits {
+    ($ty:ty) => {
+        impl FallibleContext for $ty {
+            type PlatformError = DynPlatformError;
+        }
+
+        impl KeyboardContext for $ty {
+            keyboard_methods!(object);
+        }
+
+        impl UnicodeKeyboardContext for $ty {
+            unicode_methods!(object);
+        }
+
+        impl MouseContext for $ty {
+            mouse_methods!(object);
+        }
+
+        impl ScreenContext for $ty {
+            screen_methods!(object);
+        }
+    };
+}
+
+impl_dyn_traits!(dyn DynContext + '_);
+impl_dyn_traits!(dyn DynExtendedContext + '_);
+
+impl ScreenCaptureContext for dyn DynExtendedContext + '_ {
+    screen_capture_methods!(object);
+}
+
+impl WindowContext for dyn DynExtendedContext + '_ {
+    window_methods!(object);
+}
+
+impl ClipboardContext for dyn DynExtendedContext + '_ {
+    clipboard_methods!(object);
+}
@@ -111,7 +111,7 @@ macro_rules! enumeration {
         ///
         /// This implements the [`Enum`] trait.
         #[repr(u8)]
-        #[derive(Copy, Clone, Eq, PartialEq)]
+        #[derive(Copy, Clone, Eq, PartialEq, Hash)]
         pub enum $name {
             $($identifier_name),*
         }
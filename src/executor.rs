@@ -0,0 +1,435 @@
+use crate::{
+    traits::*, Command, DynContext, DynError, GenericError, Key, MouseButton, PolicyContext,
+};
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+// How often an asynchronous delay checks whether it has been aborted.
+#[cfg(feature = "tokio")]
+const ABORT_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A shared flag that stops an [`Executor`].
+///
+/// The handle can be cloned and sent to other threads. Calling
+/// [`abort`](Self::abort) on any of the clones aborts every executor that is
+/// using the handle. The handle stays aborted until it is
+/// [`reset`](Self::reset).
+#[derive(Clone, Debug, Default)]
+pub struct AbortHandle {
+    inner: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl AbortHandle {
+    /// Create a handle that hasn't been aborted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, bool> {
+        match self.inner.0.lock() {
+            Ok(aborted) => aborted,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Abort the executors that are using this handle.
+    ///
+    /// Any delays that are in progress are interrupted.
+    pub fn abort(&self) {
+        *self.lock() = true;
+        self.inner.1.notify_all();
+    }
+
+    /// Check whether the handle has been aborted.
+    pub fn is_aborted(&self) -> bool {
+        *self.lock()
+    }
+
+    /// Clear the flag so that the handle can be used again.
+    pub fn reset(&self) {
+        *self.lock() = false;
+    }
+
+    /// Block the current thread for a duration or until the handle is aborted.
+    ///
+    /// Returns `false` if the handle was aborted.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        let mut aborted = self.lock();
+        while !*aborted {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            aborted = match self.inner.1.wait_timeout(aborted, deadline - now) {
+                Ok((aborted, _)) => aborted,
+                Err(poisoned) => poisoned.into_inner().0,
+            };
+        }
+        !*aborted
+    }
+
+    /// Wait for a duration or until the handle is aborted.
+    ///
+    /// This is the same as [`sleep`](Self::sleep) except that the handle is
+    /// polled with `tokio::time::sleep`.
+    #[cfg(feature = "tokio")]
+    pub async fn sleep_async(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        while !self.is_aborted() {
+            let now = Instant::now();
+            if now >= deadline {
+                return true;
+            }
+            tokio::time::sleep(ABORT_CHECK_INTERVAL.min(deadline - now)).await;
+        }
+        false
+    }
+}
+
+/// Whether an [`Executor`] finished executing its commands.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Completion {
+    /// All of the commands were executed.
+    Finished,
+    /// The [`AbortHandle`] was aborted before all of the commands were
+    /// executed.
+    Aborted,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Held {
+    Key(Key),
+    Button(MouseButton),
+    Ascii(u8),
+    Unicode(char),
+}
+
+/// Executes sequences of [`Command`]s that can be aborted.
+///
+/// The [`AbortHandle`] is checked before each command and
+/// [`Delay`](Command::Delay)s are interrupted as soon as it is aborted. When
+/// that happens, the keys and mouse buttons that were pressed by the commands
+/// and haven't been released yet are released. They are also released if a
+/// command fails. A single command that takes a long time, such as a long
+/// [`UnicodeString`](Command::UnicodeString) or a
+/// [`WaitForImage`](Command::WaitForImage), is not interrupted.
+///
+/// ```no_run
+/// use std::{thread, time::Duration};
+/// use tfc::{Command, Completion, Context, Executor, Key};
+///
+/// let mut ctx = Context::new().unwrap();
+/// let mut executor = Executor::new();
+/// let handle = executor.abort_handle();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(1));
+///     handle.abort();
+/// });
+///
+/// let commands = [Command::KeyDown(Key::Shift), Command::Delay(10000)];
+/// // Shift is released when the delay is aborted.
+/// assert_eq!(executor.execute(&mut ctx, &commands).unwrap(), Completion::Aborted);
+/// ```
+#[derive(Debug, Default)]
+pub struct Executor {
+    abort: AbortHandle,
+    held: Vec<Held>,
+}
+
+impl Executor {
+    /// Create an executor with a new [`AbortHandle`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an executor that uses an existing [`AbortHandle`].
+    pub fn with_abort_handle(abort: AbortHandle) -> Self {
+        Self {
+            abort,
+            held: Vec::new(),
+        }
+    }
+
+    /// Get a handle that can be used to abort the executor.
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.abort.clone()
+    }
+
+    fn hold(&mut self, command: &Command) {
+        let (held, down) = match command {
+            Command::KeyDown(key) => (Held::Key(*key), true),
+            Command::KeyUp(key) => (Held::Key(*key), false),
+            Command::MouseDown(button) => (Held::Button(*button), true),
+            Command::MouseUp(button) => (Held::Button(*button), false),
+            Command::AsciiCharDown(ch) => (Held::Ascii(*ch), true),
+            Command::AsciiCharUp(ch) => (Held::Ascii(*ch), false),
+            Command::UnicodeCharDown(ch) => (Held::Unicode(*ch), true),
+            Command::UnicodeCharUp(ch) => (Held::Unicode(*ch), false),
+            _ => return,
+        };
+        self.held.retain(|h| *h != held);
+        if down {
+            self.held.push(held);
+        }
+    }
+
+    /// Release the keys and mouse buttons that are still held by the
+    /// commands.
+    ///
+    /// They are released in the reverse order that they were pressed. This is
+    /// called automatically when the executor is aborted or a command fails.
+    pub fn release_all<C>(&mut self, ctx: &mut C) -> Result<(), GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext,
+    {
+        // Every release is attempted even if one of them fails.
+        let mut result = Ok(());
+        while let Some(held) = self.held.pop() {
+            let released = match held {
+                Held::Key(key) => ctx.key_up(key),
+                Held::Button(button) => ctx.mouse_up(button),
+                Held::Ascii(ch) => ctx.ascii_char_up(ch),
+                Held::Unicode(ch) => ctx.unicode_char_up(ch),
+            };
+            if result.is_ok() {
+                result = released;
+            }
+        }
+        result
+    }
+
+    fn aborted<C>(&mut self, ctx: &mut C) -> Result<Completion, GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext,
+    {
+        self.release_all(ctx)?;
+        Ok(Completion::Aborted)
+    }
+
+    // The error from the command is returned rather than any error from
+    // releasing the held keys.
+    fn failed<C>(
+        &mut self,
+        ctx: &mut C,
+        error: GenericError<C::PlatformError>,
+    ) -> Result<Completion, GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext,
+    {
+        let _ = self.release_all(ctx);
+        Err(error)
+    }
+
+    // Each command is checked before the executor handles it so that delays
+    // are checked too.
+    fn run<C, K, F>(
+        &mut self,
+        ctx: &mut C,
+        commands: &[Command],
+        mut check: K,
+        mut execute: F,
+    ) -> Result<Completion, GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext,
+        K: FnMut(&Command, &mut C) -> Result<(), GenericError<C::PlatformError>>,
+        F: FnMut(&Command, &mut C) -> Result<(), GenericError<C::PlatformError>>,
+    {
+        for command in commands.iter() {
+            if self.abort.is_aborted() {
+                return self.aborted(ctx);
+            }
+            if let Err(e) = check(command, ctx) {
+                return self.failed(ctx, e);
+            }
+            if let Command::Delay(millis) = command {
+                if !self.abort.sleep(Duration::from_millis(*millis as u64)) {
+                    return self.aborted(ctx);
+                }
+                continue;
+            }
+            if let Err(e) = execute(command, ctx) {
+                return self.failed(ctx, e);
+            }
+            self.hold(command);
+        }
+        Ok(Completion::Finished)
+    }
+
+    /// Execute a sequence of commands with [`Command::execute`].
+    ///
+    /// If the handle is aborted, the held keys and mouse buttons are released
+    /// and [`Aborted`](Completion::Aborted) is returned. The handle is not
+    /// reset so subsequent calls will be aborted immediately until it is.
+    pub fn execute<C>(
+        &mut self,
+        ctx: &mut C,
+        commands: &[Command],
+    ) -> Result<Completion, GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext,
+    {
+        self.run(
+            ctx,
+            commands,
+            |_, _| Ok(()),
+            |command, ctx| command.execute(ctx),
+        )
+    }
+
+    /// Execute a sequence of commands with [`Command::execute_extended`].
+    pub fn execute_extended<C>(
+        &mut self,
+        ctx: &mut C,
+        commands: &[Command],
+    ) -> Result<Completion, GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext
+            + ScreenContext
+            + ScreenCaptureContext
+            + WindowContext
+            + ClipboardContext,
+    {
+        self.run(
+            ctx,
+            commands,
+            |_, _| Ok(()),
+            |command, ctx| command.execute_extended(ctx),
+        )
+    }
+
+    /// Execute a sequence of commands on a [`PolicyContext`] with
+    /// [`Command::execute`].
+    ///
+    /// Each command is checked with
+    /// [`check_command`](PolicyContext::check_command) before it is executed
+    /// so that the allowed commands and the rate limit are enforced. A command
+    /// that is denied fails like any other.
+    pub fn execute_policy<C>(
+        &mut self,
+        ctx: &mut PolicyContext<C>,
+        commands: &[Command],
+    ) -> Result<Completion, GenericError<C::PlatformError>>
+    where
+        C: KeyboardContext + MouseContext + ScreenContext + UnicodeKeyboardContext,
+    {
+        self.run(
+            ctx,
+            commands,
+            |command, ctx| ctx.check_command(command),
+            |command, ctx| command.execute(ctx),
+        )
+    }
+
+    /// Execute a sequence of commands on a [`PolicyContext`] with
+    /// [`Command::execute_extended`].
+    ///
+    /// The commands are checked in the same way as
+    /// [`execute_policy`](Self::execute_policy).
+    pub fn execute_extended_policy<C>(
+        &mut self,
+        ctx: &mut PolicyContext<C>,
+        commands: &[Command],
+    ) -> Result<Completion, GenericError<C::PlatformError>>
+    where
+        C: KeyboardContext
+            + MouseContext
+            + UnicodeKeyboardContext
+            + ScreenContext
+            + ScreenCaptureContext
+            + WindowContext
+            + ClipboardContext,
+    {
+        self.run(
+            ctx,
+            commands,
+            |command, ctx| ctx.check_command(command),
+            |command, ctx| command.execute_extended(ctx),
+        )
+    }
+
+    /// Execute a sequence of commands with [`Command::execute_dyn`].
+    pub fn execute_dyn(
+        &mut self,
+        ctx: &mut dyn DynContext,
+        commands: &[Command],
+    ) -> Result<Completion, DynError> {
+        self.run(
+            ctx,
+            commands,
+            |_, _| Ok(()),
+            |command, ctx| command.execute_dyn(ctx),
+        )
+    }
+
+    /// Execute a single command with [`Command::execute_async`].
+    ///
+    /// Commands are taken one at a time so that they can be received from a
+    /// channel. [`Delay`](Command::Delay)s check the handle every 50
+    /// milliseconds.
+    #[cfg(feature = "tokio")]
+    pub async fn execute_async<C>(
+        &mut self,
+        ctx: &mut C,
+        command: &Command,
+    ) -> Result<Completion, GenericError<C::PlatformError>>
+    where
+        C: ?Sized
+            + FallibleContext
+            + KeyboardContext
+            + MouseContext
+            + AsciiKeyboardContext
+            + UnicodeKeyboardContext,
+    {
+        if self.abort.is_aborted() {
+            return self.aborted(ctx);
+        }
+        if let Command::Delay(millis) = command {
+            if !self
+                .abort
+                .sleep_async(Duration::from_millis(*millis as u64))
+                .await
+            {
+                return self.aborted(ctx);
+            }
+            return Ok(Completion::Finished);
+        }
+        if let Err(e) = command.execute(ctx) {
+            return self.failed(ctx, e);
+        }
+        self.hold(command);
+        Ok(Completion::Finished)
+    }
+}
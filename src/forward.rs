@@ -0,0 +1,163 @@
+// The wrappers (such as LoggingContext) implement the traits by forwarding
+// each call to the context that they wrap. `forward!` generates these impls
+// from a single list of the methods of each trait. The methods with default
+// implementations are forwarded too so that the overrides of the inner
+// context (such as begin_string on Linux) are used.
+//
+// Each method calls a callback macro with the receiver and the call:
+//
+//     $call!(&self, method(arg1, arg2))
+//     $call!(&mut self, method(arg1, arg2))
+//
+// The methods with a `where Self: ...` clause can't be forwarded because the
+// inner context isn't known to satisfy the bound. Their default
+// implementations call the other methods which are forwarded.
+
+macro_rules! forward_methods {
+    ($call:ident;) => {};
+    (
+        $call:ident;
+        fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;
+        $($rest:tt)*
+    ) => {
+        fn $name(&self $(, $arg: $ty)*) -> Result<$ret, $crate::GenericError<Self::PlatformError>> {
+            $call!(&self, $name($($arg),*))
+        }
+        forward_methods!($call; $($rest)*);
+    };
+    (
+        $call:ident;
+        fn $name:ident(&mut self $(, $arg:ident: $ty:ty)*) -> $ret:ty;
+        $($rest:tt)*
+    ) => {
+        fn $name(
+            &mut self
+            $(, $arg: $ty)*
+        ) -> Result<$ret, $crate::GenericError<Self::PlatformError>> {
+            $call!(&mut self, $name($($arg),*))
+        }
+        forward_methods!($call; $($rest)*);
+    };
+}
+
+macro_rules! forward {
+    (impl[$($gen:tt)*] KeyboardContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::KeyboardContext for $ty {
+            forward_methods! {
+                $call;
+                fn key_down(&mut self, key: $crate::Key) -> ();
+                fn key_up(&mut self, key: $crate::Key) -> ();
+                fn key_click(&mut self, key: $crate::Key) -> ();
+                fn begin_string(&mut self) -> bool;
+                fn end_string(&mut self, caps_lock: bool) -> ();
+            }
+        }
+    };
+    (impl[$($gen:tt)*] UnicodeKeyboardContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::UnicodeKeyboardContext for $ty {
+            forward_methods! {
+                $call;
+                fn unicode_char_down(&mut self, ch: char) -> ();
+                fn unicode_char_up(&mut self, ch: char) -> ();
+                fn unicode_char(&mut self, ch: char) -> ();
+                fn unicode_string(&mut self, s: &str) -> ();
+            }
+        }
+    };
+    (impl[$($gen:tt)*] MouseContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::MouseContext for $ty {
+            forward_methods! {
+                $call;
+                fn mouse_move_rel(&mut self, dx: i32, dy: i32) -> ();
+                fn mouse_move_abs(&mut self, x: i32, y: i32) -> ();
+                fn mouse_scroll(&mut self, dx: i32, dy: i32) -> ();
+                fn mouse_down(&mut self, button: $crate::MouseButton) -> ();
+                fn mouse_up(&mut self, button: $crate::MouseButton) -> ();
+                fn mouse_click(&mut self, button: $crate::MouseButton) -> ();
+            }
+        }
+    };
+    (impl[$($gen:tt)*] ScreenContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::ScreenContext for $ty {
+            forward_methods! {
+                $call;
+                fn cursor_location(&self) -> (i32, i32);
+                fn screen_size(&self) -> (i32, i32);
+                fn monitors(&self) -> Vec<$crate::Rect>;
+                fn to_pixels(&self, point: $crate::Point) -> (i32, i32);
+                fn cursor_location_normalized(&self) -> $crate::Point;
+            }
+        }
+    };
+    (impl[$($gen:tt)*] KeyStateContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::KeyStateContext for $ty {
+            forward_methods! {
+                $call;
+                fn is_key_pressed(&self, key: $crate::Key) -> bool;
+                fn modifier_state(&self) -> $crate::Modifiers;
+                fn caps_lock(&self) -> bool;
+                fn num_lock(&self) -> bool;
+            }
+        }
+    };
+    (impl[$($gen:tt)*] LayoutContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::LayoutContext for $ty {
+            forward_methods! {
+                $call;
+                fn layout_names(&self) -> Vec<String>;
+                fn active_layout(&self) -> u8;
+                fn set_active_layout(&mut self, layout: u8) -> ();
+                fn char_layout(&self, ch: char) -> Option<$crate::CharLayout>;
+            }
+        }
+    };
+    (impl[$($gen:tt)*] ScreenCaptureContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::ScreenCaptureContext for $ty {
+            forward_methods! {
+                $call;
+                fn pixel(&self, x: i32, y: i32) -> [u8; 4];
+                fn capture(&self, rect: $crate::Rect) -> $crate::Image;
+            }
+        }
+    };
+    (impl[$($gen:tt)*] WindowContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::WindowContext for $ty {
+            forward_methods! {
+                $call;
+                fn windows(&self) -> Vec<$crate::WindowInfo>;
+                fn active_window(&self) -> Option<$crate::WindowId>;
+                fn activate_window(&mut self, id: $crate::WindowId) -> ();
+                fn raise_window(&mut self, id: $crate::WindowId) -> ();
+                fn minimize_window(&mut self, id: $crate::WindowId) -> ();
+                fn move_window(&mut self, id: $crate::WindowId, x: i32, y: i32) -> ();
+                fn resize_window(&mut self, id: $crate::WindowId, width: u32, height: u32) -> ();
+                fn find_window(&self, title: &dyn Fn(&str) -> bool) -> Option<$crate::WindowInfo>;
+                fn wait_for_window(
+                    &self,
+                    title: &dyn Fn(&str) -> bool,
+                    timeout: ::std::time::Duration
+                ) -> Option<$crate::WindowInfo>;
+            }
+        }
+    };
+    (impl[$($gen:tt)*] ClipboardContext for $ty:ty => $call:ident) => {
+        impl<$($gen)*> $crate::ClipboardContext for $ty {
+            forward_methods! {
+                $call;
+                fn clipboard_text(&self, selection: $crate::Selection) -> Option<String>;
+                fn set_clipboard_text(&mut self, selection: $crate::Selection, text: &str) -> ();
+                fn clear_clipboard(&mut self, selection: $crate::Selection) -> ();
+            }
+        }
+    };
+}
+
+// A callback for `forward!` that calls the same method on `self.inner`.
+macro_rules! to_inner {
+    (&mut $this:ident, $name:ident($($arg:expr),*)) => {
+        $this.inner.$name($($arg),*)
+    };
+    (&$this:ident, $name:ident($($arg:expr),*)) => {
+        $this.inner.$name($($arg),*)
+    };
+}
@@ -29,6 +29,33 @@ pub enum GenericError<P: std::error::Error> {
     /// `Unknown` error will be returned.
     Info(String),
     Unknown,
+    /// The operation was denied by a [`Policy`](crate::Policy).
+    ///
+    /// This is returned by [`PolicyContext`](crate::PolicyContext). The string
+    /// describes the rule that was violated.
+    Denied(String),
+}
+
+impl<P: std::error::Error> GenericError<P> {
+    /// Convert the platform-specific error with a function.
+    ///
+    /// The other variants are left unchanged. This is useful for contexts that
+    /// wrap other contexts.
+    pub fn map_platform<Q, F>(self, f: F) -> GenericError<Q>
+    where
+        Q: std::error::Error,
+        F: FnOnce(P) -> Q,
+    {
+        use GenericError::*;
+        match self {
+            Platform(p) => Platform(f(p)),
+            UnsupportedAscii(ch) => UnsupportedAscii(ch),
+            UnsupportedUnicode(ch) => UnsupportedUnicode(ch),
+            Info(s) => Info(s),
+            Unknown => Unknown,
+            Denied(s) => Denied(s),
+        }
+    }
 }
 
 impl<P: std::error::Error> Display for GenericError<P> {
@@ -40,6 +67,7 @@ impl<P: std::error::Error> Display for GenericError<P> {
             UnsupportedUnicode(ch) => write!(f, "Unsupported Unicode character ('{}')", ch),
             Unknown => write!(f, "Unknown error"),
             Info(s) => write!(f, "{}", s),
+            Denied(s) => write!(f, "Denied by policy: {}", s),
         }
     }
 }
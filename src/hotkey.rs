@@ -0,0 +1,129 @@
+use crate::{Enum, Key};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// A set of modifier keys.
+///
+/// The left and right variants of a modifier key are not distinguished.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Modifiers {
+    pub control: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    /// Check whether none of the modifiers are set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Update the modifiers after a key was pressed or released.
+    ///
+    /// Returns `false` if the key isn't a modifier.
+    pub fn update(&mut self, key: Key, down: bool) -> bool {
+        use Key::*;
+        match key {
+            Control | RightControl => self.control = down,
+            Shift | RightShift => self.shift = down,
+            Alt | RightAlt => self.alt = down,
+            Meta | RightMeta => self.meta = down,
+            _ => return false,
+        }
+        true
+    }
+
+    /// The keys that correspond to the modifiers that are set.
+    ///
+    /// Only the left variant of each key is returned.
+    pub fn keys(&self) -> Vec<Key> {
+        let mut keys = Vec::new();
+        if self.control {
+            keys.push(Key::Control);
+        }
+        if self.shift {
+            keys.push(Key::Shift);
+        }
+        if self.alt {
+            keys.push(Key::Alt);
+        }
+        if self.meta {
+            keys.push(Key::Meta);
+        }
+        keys
+    }
+}
+
+/// A key combined with a set of modifiers, such as `Super+F1`.
+///
+/// Hotkeys can be parsed from strings. The modifiers and the key are separated
+/// by `+` and are case-insensitive. The key is the name of a [`Key`] variant.
+/// `ctrl`, `control`, `shift`, `alt`, `meta`, `super`, `win` and `cmd` are
+/// accepted as modifiers.
+///
+/// ```
+/// use tfc::{Hotkey, Key};
+///
+/// let hotkey: Hotkey = "Super+F1".parse().unwrap();
+/// assert_eq!(hotkey.key, Key::F1);
+/// assert!(hotkey.modifiers.meta);
+/// assert_eq!(hotkey.to_string(), "meta+f1");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Hotkey {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+/// The error returned when a [`Hotkey`] can't be parsed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HotkeyParseError(String);
+
+impl Display for HotkeyParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid hotkey \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for HotkeyParseError {}
+
+fn parse_key(name: &str) -> Option<Key> {
+    Key::iter().find(|key| key.identifier_name().eq_ignore_ascii_case(name))
+}
+
+impl FromStr for Hotkey {
+    type Err = HotkeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || HotkeyParseError(s.to_owned());
+        let mut parts = s.split('+').map(str::trim);
+        let key_name = parts.next_back().ok_or_else(error)?;
+        let key = parse_key(key_name).ok_or_else(error)?;
+
+        let mut modifiers = Modifiers::default();
+        for part in parts {
+            let modifier = match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut modifiers.control,
+                "shift" => &mut modifiers.shift,
+                "alt" => &mut modifiers.alt,
+                "meta" | "super" | "win" | "cmd" => &mut modifiers.meta,
+                _ => return Err(error()),
+            };
+            *modifier = true;
+        }
+
+        Ok(Self { modifiers, key })
+    }
+}
+
+impl Display for Hotkey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for key in self.modifiers.keys() {
+            write!(f, "{}+", key.identifier_name().to_ascii_lowercase())?;
+        }
+        write!(f, "{}", self.key.identifier_name().to_ascii_lowercase())
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{
+    linux_x11::GrabReader, ContextBuilder, Error, Hotkey, InputEvent, InputListener, Modifiers,
+};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+// How often the evdev thread checks whether it should stop.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+// The readers are only held so that they are stopped when the listener is
+// dropped.
+#[allow(dead_code)]
+enum Source {
+    X11(GrabReader),
+    Evdev(EvdevHotkeys),
+}
+
+/// Listens for global hotkeys (Linux).
+///
+/// The hotkeys are given when the listener is created. Each time one of them
+/// is pressed, its index is received. On X11, the hotkeys are grabbed so they
+/// won't reach the focused window. Reading from `/dev/input` can't stop other
+/// applications from seeing the keys.
+///
+/// Only presses on real devices are received. The events generated by a
+/// [`Context`](crate::Context) while performing the action for a hotkey don't
+/// trigger any hotkeys.
+///
+/// ```no_run
+/// use tfc::{traits::*, Context, ContextBuilder, Hotkey, HotkeyListener};
+///
+/// let hotkeys = ["super+f1".parse::<Hotkey>().unwrap()];
+/// let listener = HotkeyListener::x11(&ContextBuilder::new(), &hotkeys).unwrap();
+/// let mut ctx = Context::new().unwrap();
+/// while let Some(_) = listener.recv() {
+///     ctx.unicode_string("Kind regards").unwrap();
+/// }
+/// ```
+pub struct HotkeyListener {
+    receiver: Receiver<usize>,
+    _source: Source,
+}
+
+impl HotkeyListener {
+    /// Grab the hotkeys on the display chosen by the builder.
+    ///
+    /// An error is returned if another client has already grabbed one of the
+    /// hotkeys. Key presses generated with XTest are ignored.
+    pub fn x11(builder: &ContextBuilder, hotkeys: &[Hotkey]) -> Result<Self, Error> {
+        let (sender, receiver) = mpsc::channel();
+        let reader = GrabReader::open(builder, hotkeys, sender)?;
+        Ok(Self {
+            receiver,
+            _source: Source::X11(reader),
+        })
+    }
+
+    /// Listen for the hotkeys on the devices in `/dev/input`.
+    ///
+    /// This works without X11 but requires permission to read `/dev/input`.
+    /// The virtual device that a context created from the builder would use is
+    /// ignored.
+    pub fn evdev(builder: &ContextBuilder, hotkeys: &[Hotkey]) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let listener = InputListener::evdev(builder)?;
+        let hotkeys = EvdevHotkeys::spawn(listener, hotkeys.to_vec(), sender);
+        Ok(Self {
+            receiver,
+            _source: Source::Evdev(hotkeys),
+        })
+    }
+
+    /// Wait for a hotkey to be pressed and return its index.
+    ///
+    /// `None` is returned if the listener has stopped.
+    pub fn recv(&self) -> Option<usize> {
+        self.receiver.recv().ok()
+    }
+
+    /// Wait for a hotkey to be pressed with a timeout.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<usize, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+}
+
+struct EvdevHotkeys {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EvdevHotkeys {
+    fn spawn(listener: InputListener, hotkeys: Vec<Hotkey>, sender: Sender<usize>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread = thread::spawn(move || {
+            let mut modifiers = Modifiers::default();
+            while !thread_stop.load(Ordering::Relaxed) {
+                let event = match listener.recv_timeout(STOP_CHECK_INTERVAL) {
+                    Ok(event) => event.event,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                };
+                let key = match event {
+                    InputEvent::KeyDown(key) => key,
+                    InputEvent::KeyUp(key) => {
+                        modifiers.update(key, false);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if modifiers.update(key, true) {
+                    continue;
+                }
+                let pressed = Hotkey { modifiers, key };
+                if let Some(index) = hotkeys.iter().position(|hotkey| *hotkey == pressed) {
+                    if sender.send(index).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for EvdevHotkeys {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
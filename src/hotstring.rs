@@ -0,0 +1,247 @@
+use crate::{
+    GenericError, InputEvent, Key, KeyboardContext, Modifiers, TimedInputEvent,
+    UnicodeKeyboardContext,
+};
+
+/// A piece of text that is replaced with another when it's typed.
+///
+/// By default, the trigger must be typed at the start of a word and is
+/// expanded when it's followed by an ending character (whitespace or
+/// punctuation). The trigger is matched case-insensitively and the case of
+/// the expansion follows the case of the typed trigger. `btw` expands to
+/// `by the way` and `Btw` expands to `By the way`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Hotstring {
+    pub trigger: String,
+    pub expansion: String,
+    /// Expand as soon as the trigger is typed instead of waiting for an
+    /// ending character.
+    pub immediate: bool,
+    /// Only match the trigger if it's typed with the same case. The
+    /// expansion is then typed as it is.
+    pub case_sensitive: bool,
+    /// Allow the trigger to be typed in the middle of a word.
+    pub inside_word: bool,
+}
+
+impl Hotstring {
+    /// Create a hotstring with the default options.
+    pub fn new(trigger: &str, expansion: &str) -> Self {
+        Self {
+            trigger: trigger.to_owned(),
+            expansion: expansion.to_owned(),
+            immediate: false,
+            case_sensitive: false,
+            inside_word: false,
+        }
+    }
+}
+
+/// The edit that replaces a typed trigger with its expansion.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Expansion {
+    /// The index of the [`Hotstring`].
+    pub index: usize,
+    /// The number of characters to erase.
+    pub erase: usize,
+    /// The text to type after erasing.
+    pub text: String,
+}
+
+impl Expansion {
+    /// Erase the trigger with [`Key::DeleteOrBackspace`] and type the
+    /// expansion.
+    pub fn perform<C>(&self, ctx: &mut C) -> Result<(), GenericError<C::PlatformError>>
+    where
+        C: KeyboardContext + UnicodeKeyboardContext,
+    {
+        for _ in 0..self.erase {
+            ctx.key_click(Key::DeleteOrBackspace)?;
+        }
+        ctx.unicode_string(&self.text)
+    }
+}
+
+/// Detects hotstrings in a stream of input events.
+///
+/// The engine keeps a buffer of the characters that were recently typed.
+/// Backspace removes a character from the buffer. Clicking, pressing a key
+/// that doesn't produce a character (such as the arrow keys) or pressing a
+/// shortcut clears the buffer because the caret has probably moved.
+///
+/// The events generated by performing an expansion shouldn't be fed back
+/// into the engine.
+///
+/// ```
+/// use std::time::Instant;
+/// use tfc::{Hotstring, HotstringEngine, InputEvent, Key, TimedInputEvent};
+///
+/// let mut engine = HotstringEngine::new(vec![Hotstring::new("btw", "by the way")]);
+/// let mut expansion = None;
+/// for (key, ch) in [(Key::B, 'B'), (Key::T, 't'), (Key::W, 'w'), (Key::Space, ' ')] {
+///     expansion = engine.feed(&TimedInputEvent {
+///         event: InputEvent::KeyDown(key),
+///         ch: Some(ch),
+///         time: Instant::now(),
+///     });
+/// }
+///
+/// let expansion = expansion.unwrap();
+/// assert_eq!(expansion.erase, 4);
+/// assert_eq!(expansion.text, "By the way ");
+/// ```
+#[derive(Debug)]
+pub struct HotstringEngine {
+    hotstrings: Vec<Hotstring>,
+    buffer: Vec<char>,
+    capacity: usize,
+    modifiers: Modifiers,
+}
+
+fn is_ending_char(ch: char) -> bool {
+    ch.is_whitespace() || ch.is_ascii_punctuation()
+}
+
+fn chars_eq(a: char, b: char, case_sensitive: bool) -> bool {
+    a == b || (!case_sensitive && a.to_lowercase().eq(b.to_lowercase()))
+}
+
+// Make the case of the expansion follow the case of the typed trigger.
+fn conform_case(expansion: &str, trigger: &str, typed: &[char]) -> String {
+    let typed = typed.iter().copied().filter(|c| c.is_alphabetic()).collect::<Vec<_>>();
+    let trigger = trigger.chars().filter(|c| c.is_alphabetic()).collect::<Vec<_>>();
+    let all_upper = |letters: &[char]| letters.iter().all(|c| c.is_uppercase());
+    let first_upper = |letters: &[char]| matches!(letters.first(), Some(c) if c.is_uppercase());
+
+    if typed.len() > 1 && all_upper(&typed) && !all_upper(&trigger) {
+        expansion.to_uppercase()
+    } else if first_upper(&typed) && !first_upper(&trigger) {
+        let mut chars = expansion.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    } else {
+        expansion.to_owned()
+    }
+}
+
+impl HotstringEngine {
+    /// Create an engine that detects the given hotstrings.
+    pub fn new(hotstrings: Vec<Hotstring>) -> Self {
+        // One extra character for the ending character and one more for the
+        // character before the trigger.
+        let capacity = hotstrings
+            .iter()
+            .map(|h| h.trigger.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 2;
+        Self {
+            hotstrings,
+            buffer: Vec::with_capacity(capacity),
+            capacity,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    /// The hotstrings that the engine detects.
+    pub fn hotstrings(&self) -> &[Hotstring] {
+        &self.hotstrings
+    }
+
+    /// Forget the characters that have been typed.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Process an input event.
+    ///
+    /// If this event completes a hotstring, the expansion is returned and the
+    /// buffer is cleared.
+    pub fn feed(&mut self, event: &TimedInputEvent) -> Option<Expansion> {
+        let key = match event.event {
+            InputEvent::KeyDown(key) => key,
+            InputEvent::KeyUp(key) => {
+                self.modifiers.update(key, false);
+                return None;
+            }
+            InputEvent::MouseDown(_) => {
+                self.reset();
+                return None;
+            }
+            _ => return None,
+        };
+
+        if self.modifiers.update(key, true) {
+            return None;
+        }
+        if key == Key::DeleteOrBackspace {
+            self.buffer.pop();
+            return None;
+        }
+        let shortcut = self.modifiers.control || self.modifiers.alt || self.modifiers.meta;
+        let ch = match event.ch {
+            Some(ch) if !shortcut => ch,
+            _ => {
+                self.reset();
+                return None;
+            }
+        };
+
+        if self.buffer.len() == self.capacity {
+            self.buffer.remove(0);
+        }
+        self.buffer.push(ch);
+
+        let expansion = self
+            .hotstrings
+            .iter()
+            .enumerate()
+            .find_map(|(index, hotstring)| self.check(index, hotstring));
+        if expansion.is_some() {
+            self.reset();
+        }
+        expansion
+    }
+
+    fn check(&self, index: usize, hotstring: &Hotstring) -> Option<Expansion> {
+        let (ending, typed) = if hotstring.immediate {
+            (None, &self.buffer[..])
+        } else {
+            match self.buffer.split_last() {
+                Some((last, rest)) if is_ending_char(*last) => (Some(*last), rest),
+                _ => return None,
+            }
+        };
+
+        let length = hotstring.trigger.chars().count();
+        if length == 0 || typed.len() < length {
+            return None;
+        }
+        let (before, typed) = typed.split_at(typed.len() - length);
+        let matches = typed
+            .iter()
+            .zip(hotstring.trigger.chars())
+            .all(|(a, b)| chars_eq(*a, b, hotstring.case_sensitive));
+        if !matches {
+            return None;
+        }
+        if !hotstring.inside_word && matches!(before.last(), Some(ch) if ch.is_alphanumeric()) {
+            return None;
+        }
+
+        let mut text = if hotstring.case_sensitive {
+            hotstring.expansion.clone()
+        } else {
+            conform_case(&hotstring.expansion, &hotstring.trigger, typed)
+        };
+        text.extend(ending);
+
+        Some(Expansion {
+            index,
+            erase: length + ending.map_or(0, |_| 1),
+            text,
+        })
+    }
+}
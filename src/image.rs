@@ -0,0 +1,178 @@
+/// A rectangle in screen coordinates.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Get the point at the center of the rectangle.
+    pub fn center(&self) -> (i32, i32) {
+        (
+            self.x + (self.width / 2) as i32,
+            self.y + (self.height / 2) as i32,
+        )
+    }
+
+    /// Check whether a point is inside the rectangle.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && ((x - self.x) as u32) < self.width
+            && ((y - self.y) as u32) < self.height
+    }
+}
+
+/// An image stored as 8-bit RGBA pixels in row-major order.
+///
+/// ```
+/// use tfc::Image;
+///
+/// let mut image = Image::new(2, 2);
+/// image.set_pixel(1, 0, [255, 0, 0, 255]);
+/// assert_eq!(image.pixel(1, 0), [255, 0, 0, 255]);
+/// assert_eq!(image.pixel(0, 1), [0, 0, 0, 0]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Create an image where every pixel is transparent black.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Create an image from RGBA pixel data.
+    ///
+    /// Returns `None` if the length of the data doesn't match the size.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        if data.len() == width as usize * height as usize * 4 {
+            Some(Self {
+                width,
+                height,
+                data,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the RGBA pixel data.
+    pub fn as_rgba(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Take the RGBA pixel data.
+    pub fn into_rgba(self) -> Vec<u8> {
+        self.data
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height, "pixel out of bounds");
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    /// Get the color of a pixel.
+    ///
+    /// Panics if the pixel is outside of the image.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = self.index(x, y);
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.data[index..index + 4]);
+        pixel
+    }
+
+    /// Set the color of a pixel.
+    ///
+    /// Panics if the pixel is outside of the image.
+    pub fn set_pixel(&mut self, x: u32, y: u32, pixel: [u8; 4]) {
+        let index = self.index(x, y);
+        self.data[index..index + 4].copy_from_slice(&pixel);
+    }
+
+    /// Load a PNG file.
+    ///
+    /// The image is converted to 8-bit RGBA.
+    #[cfg(feature = "png")]
+    pub fn load_png<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        use std::io::{Error, ErrorKind};
+
+        let mut decoder = png::Decoder::new(std::fs::File::open(path)?);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        buffer.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|g| [*g, *g, *g, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "Unexpected indexed color",
+                ))
+            }
+        };
+
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            data,
+        })
+    }
+
+    /// Save the image to a PNG file.
+    #[cfg(feature = "png")]
+    pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        use std::io::{BufWriter, Error};
+
+        let file = BufWriter::new(std::fs::File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.data))
+            .map_err(Error::other)
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{Image, Rect};
+use std::cmp::Ordering;
+
+/// The tolerance used by [`Command::WaitForImage`](crate::Command::WaitForImage)
+/// and [`Command::ClickImage`](crate::Command::ClickImage).
+pub const DEFAULT_TOLERANCE: f32 = 0.05;
+
+// The template is never shrunk to be smaller than this.
+const MIN_TEMPLATE_SIZE: u32 = 8;
+const MAX_LEVELS: usize = 4;
+// Shrinking blurs the images so the scores at the coarse levels can be much
+// lower than the final score. Rather than using a threshold, the best few
+// positions are refined.
+const MAX_CANDIDATES: usize = 16;
+// A region with a standard deviation below this (in 8-bit levels) is
+// considered to be a solid color.
+const FLAT_DEVIATION: f32 = 1.0;
+
+// The RGB channels of an image as floats.
+struct Plane {
+    width: u32,
+    height: u32,
+    data: Vec<f32>,
+}
+
+impl Plane {
+    fn from_image(image: &Image) -> Self {
+        Self {
+            width: image.width(),
+            height: image.height(),
+            data: image
+                .as_rgba()
+                .chunks_exact(4)
+                .flat_map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+                .collect(),
+        }
+    }
+
+    // Halve the size by averaging each 2x2 block.
+    fn downsample(&self) -> Self {
+        let width = self.width / 2;
+        let height = self.height / 2;
+        let mut data = Vec::with_capacity(width as usize * height as usize * 3);
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                for c in 0..3 {
+                    let at = |dx: usize, dy: usize| {
+                        self.data[((2 * y + dy) * self.width as usize + 2 * x + dx) * 3 + c]
+                    };
+                    data.push((at(0, 0) + at(1, 0) + at(0, 1) + at(1, 1)) / 4.0);
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    fn row(&self, x: u32, y: u32, width: u32) -> &[f32] {
+        let start = (y as usize * self.width as usize + x as usize) * 3;
+        &self.data[start..start + width as usize * 3]
+    }
+}
+
+struct Template {
+    plane: Plane,
+    mean: f32,
+    deviations: Vec<f32>,
+    norm: f32,
+}
+
+impl Template {
+    fn new(plane: Plane) -> Self {
+        let mean = plane.data.iter().sum::<f32>() / plane.data.len() as f32;
+        let deviations: Vec<f32> = plane.data.iter().map(|v| v - mean).collect();
+        let norm = deviations.iter().map(|d| d * d).sum::<f32>().sqrt();
+        Self {
+            plane,
+            mean,
+            deviations,
+            norm,
+        }
+    }
+
+    fn is_flat(&self) -> bool {
+        is_flat(self.norm, self.deviations.len())
+    }
+}
+
+fn is_flat(norm: f32, len: usize) -> bool {
+    norm < FLAT_DEVIATION * (len as f32).sqrt()
+}
+
+// The normalized cross-correlation between the template and the region of the
+// image at the given position.
+fn score(image: &Plane, template: &Template, x: u32, y: u32) -> f32 {
+    let width = template.plane.width;
+    let height = template.plane.height;
+    let len = template.deviations.len();
+
+    let mut sum = 0.0;
+    for ty in 0..height {
+        sum += image.row(x, y + ty, width).iter().sum::<f32>();
+    }
+    let mean = sum / len as f32;
+
+    let mut cross = 0.0;
+    let mut variance = 0.0;
+    let deviation_rows = template.deviations.chunks_exact(width as usize * 3);
+    for (ty, deviations) in (0..height).zip(deviation_rows) {
+        for (value, t) in image.row(x, y + ty, width).iter().zip(deviations) {
+            let d = value - mean;
+            cross += d * t;
+            variance += d * d;
+        }
+    }
+    let norm = variance.sqrt();
+
+    // The correlation is undefined for solid colors so the means are compared
+    // instead.
+    match (is_flat(norm, len), template.is_flat()) {
+        (true, true) => 1.0 - (mean - template.mean).abs() / 255.0,
+        (false, false) => cross / (norm * template.norm),
+        _ => 0.0,
+    }
+}
+
+fn by_score(a: &(f32, u32, u32), b: &(f32, u32, u32)) -> Ordering {
+    b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal)
+}
+
+/// Find the region of an image that best matches a template.
+///
+/// The images are compared using normalized cross-correlation so the search
+/// isn't affected by uniform changes in brightness or contrast. The search is
+/// performed on shrunken copies of the images first and then refined. The
+/// `tolerance` ranges from 0 to 1. A region matches if its correlation with
+/// the template is at least `1 - tolerance`. `None` is returned if no region
+/// matches.
+///
+/// ```
+/// use tfc::{find_image, Image, Rect};
+///
+/// let mut image = Image::new(32, 32);
+/// let mut template = Image::new(3, 2);
+/// for (i, (x, y)) in [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)].iter().enumerate() {
+///     let pixel = [i as u8 * 40, 255 - i as u8 * 40, 0, 255];
+///     image.set_pixel(20 + x, 10 + y, pixel);
+///     template.set_pixel(*x, *y, pixel);
+/// }
+///
+/// assert_eq!(find_image(&image, &template, 0.01), Some(Rect::new(20, 10, 3, 2)));
+/// ```
+pub fn find_image(image: &Image, template: &Image, tolerance: f32) -> Option<Rect> {
+    if template.width() == 0
+        || template.height() == 0
+        || template.width() > image.width()
+        || template.height() > image.height()
+    {
+        return None;
+    }
+    let threshold = 1.0 - tolerance;
+
+    let mut images = vec![Plane::from_image(image)];
+    let mut templates = vec![Template::new(Plane::from_image(template))];
+    while images.len() < MAX_LEVELS {
+        let template = &templates.last().unwrap().plane;
+        if template.width / 2 < MIN_TEMPLATE_SIZE || template.height / 2 < MIN_TEMPLATE_SIZE {
+            break;
+        }
+        let template = Template::new(template.downsample());
+        images.push(images.last().unwrap().downsample());
+        templates.push(template);
+    }
+
+    // Exhaustive search at the coarsest level.
+    let coarsest = images.len() - 1;
+    let image = &images[coarsest];
+    let template = &templates[coarsest];
+    let mut scores = Vec::new();
+    for y in 0..=image.height - template.plane.height {
+        for x in 0..=image.width - template.plane.width {
+            let score = score(image, template, x, y);
+            if coarsest > 0 || score >= threshold {
+                scores.push((score, x, y));
+            }
+        }
+    }
+    scores.sort_by(by_score);
+
+    // Skip candidates that overlap a better one.
+    let mut candidates: Vec<(f32, u32, u32)> = Vec::new();
+    let near_x = (template.plane.width / 2).max(1);
+    let near_y = (template.plane.height / 2).max(1);
+    for candidate in scores {
+        if candidates.len() == MAX_CANDIDATES {
+            break;
+        }
+        let overlaps = candidates
+            .iter()
+            .any(|(_, x, y)| x.abs_diff(candidate.1) < near_x && y.abs_diff(candidate.2) < near_y);
+        if !overlaps {
+            candidates.push(candidate);
+        }
+    }
+
+    // Refine each candidate in the neighbourhood of its position at each of the
+    // finer levels.
+    for level in (0..coarsest).rev() {
+        let image = &images[level];
+        let template = &templates[level];
+        let max_x = image.width - template.plane.width;
+        let max_y = image.height - template.plane.height;
+        for candidate in candidates.iter_mut() {
+            let mut best = (f32::MIN, 0, 0);
+            for y in (candidate.2 * 2).saturating_sub(1)..=(candidate.2 * 2 + 2).min(max_y) {
+                for x in (candidate.1 * 2).saturating_sub(1)..=(candidate.1 * 2 + 2).min(max_x) {
+                    let score = score(image, template, x, y);
+                    if score > best.0 {
+                        best = (score, x, y);
+                    }
+                }
+            }
+            *candidate = best;
+        }
+    }
+
+    candidates.sort_by(by_score);
+    candidates
+        .first()
+        .filter(|(score, _, _)| *score >= threshold)
+        .map(|(_, x, y)| {
+            Rect::new(
+                *x as i32,
+                *y as i32,
+                templates[0].plane.width,
+                templates[0].plane.height,
+            )
+        })
+}
@@ -0,0 +1,44 @@
+use crate::{Key, MouseButton};
+use std::time::Instant;
+
+/// An event generated by a real input device.
+///
+/// Each variant corresponds to a [`Command`](crate::Command) that would
+/// reproduce the event. Events are received from an
+/// [`InputListener`](crate::InputListener).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InputEvent {
+    /// A key was pressed.
+    KeyDown(Key),
+    /// A key was released.
+    KeyUp(Key),
+    /// A mouse button was pressed.
+    MouseDown(MouseButton),
+    /// A mouse button was released.
+    MouseUp(MouseButton),
+    /// The mouse moved by a relative amount.
+    MouseMoveRel(i32, i32),
+    /// The mouse moved to an absolute position on the screen.
+    MouseMoveAbs(i32, i32),
+    /// The mouse wheel scrolled. The units are the same as
+    /// [`mouse_scroll`](crate::MouseContext::mouse_scroll).
+    MouseScroll(i32, i32),
+}
+
+/// An [`InputEvent`] along with the time that it was received.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TimedInputEvent {
+    /// The event itself.
+    pub event: InputEvent,
+    /// The character that a key press produced, if any.
+    ///
+    /// This takes the state of the modifier keys into account. It is only
+    /// available for [`InputEvent::KeyDown`].
+    pub ch: Option<char>,
+    /// The time that the event was received.
+    ///
+    /// On Linux-X11, this is derived from the timestamp that the server gave
+    /// the event so the intervals between events are accurate even though
+    /// events are received in bursts.
+    pub time: Instant,
+}
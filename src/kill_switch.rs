@@ -0,0 +1,97 @@
+use crate::{AbortHandle, ContextBuilder, Error, InputEvent, InputListener, Key};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::RecvTimeoutError,
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+// How often the thread checks whether it should stop.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+// The key must be pressed this many times within the interval.
+const PRESS_COUNT: usize = 3;
+const PRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Aborts an [`Executor`](crate::Executor) when Escape is pressed three times
+/// (Linux).
+///
+/// The presses must be within a second of each other and no other key may be
+/// pressed in between. The handle is aborted each time this happens so it
+/// should be [`reset`](AbortHandle::reset) once the abort has been dealt with.
+///
+/// Only presses on real devices count. The events generated by a
+/// [`Context`](crate::Context) are ignored so a script (or a remote client)
+/// that presses Escape three times can't abort itself.
+///
+/// ```no_run
+/// use tfc::{Command, Context, ContextBuilder, Executor, KillSwitch};
+///
+/// let mut ctx = Context::new().unwrap();
+/// let mut executor = Executor::new();
+/// let _kill_switch = KillSwitch::x11(&ContextBuilder::new(), executor.abort_handle()).unwrap();
+/// executor.execute(&mut ctx, &[Command::Delay(60000)]).unwrap();
+/// ```
+pub struct KillSwitch {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl KillSwitch {
+    /// Listen for the kill hotkey on the display chosen by the builder.
+    ///
+    /// This requires XInput 2.1 so that key presses generated with XTest can
+    /// be ignored.
+    pub fn x11(builder: &ContextBuilder, abort: AbortHandle) -> Result<Self, Error> {
+        Ok(Self::spawn(InputListener::x11_keys(builder)?, abort))
+    }
+
+    /// Listen for the kill hotkey on the devices in `/dev/input`.
+    pub fn evdev(builder: &ContextBuilder, abort: AbortHandle) -> io::Result<Self> {
+        Ok(Self::spawn(InputListener::evdev(builder)?, abort))
+    }
+
+    fn spawn(listener: InputListener, abort: AbortHandle) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread = thread::spawn(move || {
+            let mut presses: Vec<Instant> = Vec::new();
+            while !thread_stop.load(Ordering::Relaxed) {
+                let event = match listener.recv_timeout(STOP_CHECK_INTERVAL) {
+                    Ok(event) => event,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                };
+                match event.event {
+                    InputEvent::KeyDown(Key::Escape) => {
+                        presses.retain(|time| event.time.duration_since(*time) < PRESS_INTERVAL);
+                        presses.push(event.time);
+                        if presses.len() >= PRESS_COUNT {
+                            presses.clear();
+                            abort.abort();
+                        }
+                    }
+                    InputEvent::KeyDown(_) => presses.clear(),
+                    _ => {}
+                }
+            }
+        });
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for KillSwitch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
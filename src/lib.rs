@@ -22,38 +22,86 @@
 //! command, send it over a network, deserialize it and then execute it. In
 //! fact, this is what [TFC-server](https://crates.io/crates/tfc-server) does.
 
+mod capabilities;
+mod clipboard;
 mod command;
 #[macro_use]
 mod r#enum;
 mod command_code;
+mod context_builder;
+mod diagnostics;
+#[macro_use]
+mod forward;
+mod dyn_context;
+mod executor;
 mod generic_error;
+mod hotkey;
+mod hotstring;
+mod image;
+mod image_search;
+mod input_event;
 mod key;
+mod middleware;
 mod mouse_button;
+mod point;
+mod policy;
+mod recorder;
 mod utils;
+mod window;
 
+pub use capabilities::*;
+pub use clipboard::*;
 pub use command::*;
 pub use command_code::*;
+pub use context_builder::*;
+pub use diagnostics::*;
+pub use dyn_context::*;
+pub use executor::*;
 pub use generic_error::*;
+pub use hotkey::*;
+pub use hotstring::*;
+pub use image::*;
+pub use image_search::*;
+pub use input_event::*;
 pub use key::*;
+pub use middleware::*;
 pub use mouse_button::*;
+pub use point::*;
+pub use policy::*;
 pub use r#enum::*;
+pub use recorder::*;
+pub use window::*;
 
 /// A collection of traits that [`Context`] implements.
 pub mod traits;
 pub use traits::*;
 
+#[cfg(target_os = "linux")]
+mod hotkey_listener;
 #[cfg(target_os = "linux")]
 mod linux_common;
+#[cfg(target_os = "linux")]
+pub use hotkey_listener::*;
+#[cfg(target_os = "linux")]
+mod kill_switch;
+#[cfg(target_os = "linux")]
+pub use kill_switch::*;
+#[cfg(target_os = "linux")]
+mod listener;
+#[cfg(target_os = "linux")]
+pub use listener::*;
 
-// #[cfg(all(target_os = "linux", not(x11)))]
-// mod linux_wayland;
-// #[cfg(all(target_os = "linux", not(x11)))]
-// pub use linux_wayland::Context;
+#[cfg(all(target_os = "linux", feature = "uinput"))]
+mod linux_wayland;
+#[cfg(all(target_os = "linux", feature = "uinput"))]
+pub use linux_wayland::Context as UinputContext;
+#[cfg(all(target_os = "linux", feature = "uinput", feature = "ascii-fallback"))]
+pub use linux_wayland::{HexInputStrategy, UnicodeStrategy};
 
 #[cfg(all(target_os = "linux"))]
 mod linux_x11;
 #[cfg(all(target_os = "linux"))]
-pub use linux_x11::Context;
+pub use linux_x11::{Context, TargetedContext};
 
 #[cfg(target_os = "macos")]
 mod macos;
@@ -67,3 +115,7 @@ pub use windows::Context;
 
 /// Convenience type alias for [`GenericError`].
 pub type Error = GenericError<<Context as FallibleContext>::PlatformError>;
+
+/// Convenience type alias for the [`GenericError`] of [`UinputContext`].
+#[cfg(all(target_os = "linux", feature = "uinput"))]
+pub type UinputError = GenericError<<UinputContext as FallibleContext>::PlatformError>;
@@ -0,0 +1,222 @@
+use super::{ffi, from_key_code};
+use crate::{traits::ascii_from_key, InputEvent, Key, MouseButton, TimedInputEvent};
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    os::{
+        raw::{c_int, c_long},
+        unix::io::AsRawFd,
+    },
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+// The input_event struct begins with a timeval which is two longs. This is
+// followed by a u16 type, a u16 code and an i32 value.
+const EVENT_SIZE: usize = 2 * std::mem::size_of::<c_long>() + 8;
+
+// How often the reader threads check whether they should stop.
+const POLL_TIMEOUT_MS: c_int = 100;
+
+// The number of scroll units in one notch of the wheel.
+const SCROLL_NOTCH: i32 = 120;
+
+/// Reads events from the devices in `/dev/input`.
+///
+/// Each device is read on its own thread. The threads are stopped when this is
+/// dropped.
+pub struct EvdevReader {
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl EvdevReader {
+    /// Start reading from every event device that can be opened, except for
+    /// those with one of the given names.
+    ///
+    /// An error is returned if no devices could be opened. This is usually
+    /// because the user isn't in the `input` group.
+    pub fn open(ignore_names: &[String], sender: Sender<TimedInputEvent>) -> io::Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut threads = Vec::new();
+        let mut last_error = None;
+
+        for entry in fs::read_dir("/dev/input")? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let file_name = match file_name.to_str() {
+                Some(name) if name.starts_with("event") => name,
+                _ => continue,
+            };
+
+            let name_path = format!("/sys/class/input/{}/device/name", file_name);
+            if let Ok(name) = fs::read_to_string(name_path) {
+                if ignore_names.iter().any(|ignored| ignored == name.trim_end()) {
+                    continue;
+                }
+            }
+
+            let file = match File::open(entry.path()) {
+                Ok(file) => file,
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            let sender = sender.clone();
+            let stop = stop.clone();
+            threads.push(thread::spawn(move || read_device(file, sender, &stop)));
+        }
+
+        if threads.is_empty() {
+            return Err(last_error.unwrap_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "No input devices were found")
+            }));
+        }
+
+        Ok(Self { stop, threads })
+    }
+}
+
+impl Drop for EvdevReader {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[derive(Default)]
+struct DeviceState {
+    shift: u8,
+    rel_x: i32,
+    rel_y: i32,
+    scroll_x: i32,
+    scroll_y: i32,
+}
+
+fn to_mouse_button(code: u16) -> Option<MouseButton> {
+    match code {
+        ffi::BTN_LEFT => Some(MouseButton::Left),
+        ffi::BTN_RIGHT => Some(MouseButton::Right),
+        ffi::BTN_MIDDLE => Some(MouseButton::Middle),
+        _ => None,
+    }
+}
+
+impl DeviceState {
+    fn handle(&mut self, type_: u16, code: u16, value: i32, events: &mut Vec<TimedInputEvent>) {
+        let mut push = |event, ch| {
+            events.push(TimedInputEvent {
+                event,
+                ch,
+                time: Instant::now(),
+            })
+        };
+
+        match (type_, code) {
+            // Auto-repeat is ignored.
+            (ffi::EV_KEY, _) if value == 2 => {}
+            (ffi::EV_KEY, _) => {
+                let down = value != 0;
+                if let Some(button) = to_mouse_button(code) {
+                    if down {
+                        push(InputEvent::MouseDown(button), None);
+                    } else {
+                        push(InputEvent::MouseUp(button), None);
+                    }
+                } else if let Some(key) = from_key_code(code) {
+                    if key == Key::Shift || key == Key::RightShift {
+                        if down {
+                            self.shift = self.shift.saturating_add(1);
+                        } else {
+                            self.shift = self.shift.saturating_sub(1);
+                        }
+                    }
+                    if down {
+                        let ch = ascii_from_key(key, self.shift != 0).map(char::from);
+                        push(InputEvent::KeyDown(key), ch);
+                    } else {
+                        push(InputEvent::KeyUp(key), None);
+                    }
+                }
+            }
+
+            (ffi::EV_REL, ffi::REL_X) => self.rel_x += value,
+            (ffi::EV_REL, ffi::REL_Y) => self.rel_y += value,
+            (ffi::EV_REL, ffi::REL_HWHEEL) => self.scroll_x += value * SCROLL_NOTCH,
+            (ffi::EV_REL, ffi::REL_WHEEL) => self.scroll_y -= value * SCROLL_NOTCH,
+
+            // Relative motion is reported one axis at a time so it's collected
+            // until the end of the report.
+            (ffi::EV_SYN, ffi::SYN_REPORT) => {
+                if self.rel_x != 0 || self.rel_y != 0 {
+                    push(InputEvent::MouseMoveRel(self.rel_x, self.rel_y), None);
+                }
+                if self.scroll_x != 0 || self.scroll_y != 0 {
+                    push(InputEvent::MouseScroll(self.scroll_x, self.scroll_y), None);
+                }
+                self.rel_x = 0;
+                self.rel_y = 0;
+                self.scroll_x = 0;
+                self.scroll_y = 0;
+            }
+
+            _ => {}
+        }
+    }
+}
+
+fn read_device(mut file: File, sender: Sender<TimedInputEvent>, stop: &AtomicBool) {
+    let mut state = DeviceState::default();
+    let mut buffer = [0; EVENT_SIZE * 64];
+    let mut events = Vec::new();
+
+    while !stop.load(Ordering::Relaxed) {
+        let mut fds = ffi::pollfd {
+            fd: file.as_raw_fd(),
+            events: ffi::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { ffi::poll(&mut fds, 1, POLL_TIMEOUT_MS) };
+        if ready < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return;
+        }
+        if ready == 0 {
+            continue;
+        }
+        // The device was probably unplugged.
+        if fds.revents & (ffi::POLLERR | ffi::POLLHUP) != 0 {
+            return;
+        }
+
+        let size = match file.read(&mut buffer) {
+            Ok(0) | Err(_) => return,
+            Ok(size) => size,
+        };
+
+        for event in buffer[..size].chunks_exact(EVENT_SIZE) {
+            let data = &event[EVENT_SIZE - 8..];
+            let type_ = u16::from_ne_bytes([data[0], data[1]]);
+            let code = u16::from_ne_bytes([data[2], data[3]]);
+            let value = i32::from_ne_bytes([data[4], data[5], data[6], data[7]]);
+            state.handle(type_, code, value, &mut events);
+        }
+
+        for event in events.drain(..) {
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 
 mod input_event_codes;
+mod poll;
 
 pub use input_event_codes::*;
+pub use poll::*;
@@ -0,0 +1,20 @@
+// poll.h
+// https://man7.org/linux/man-pages/man2/poll.2.html
+
+use std::os::raw::{c_int, c_short, c_ulong};
+
+pub const POLLIN: c_short = 0x001;
+pub const POLLERR: c_short = 0x008;
+pub const POLLHUP: c_short = 0x010;
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct pollfd {
+    pub fd: c_int,
+    pub events: c_short,
+    pub revents: c_short,
+}
+
+extern "C" {
+    pub fn poll(fds: *mut pollfd, nfds: c_ulong, timeout: c_int) -> c_int;
+}
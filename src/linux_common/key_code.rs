@@ -1,4 +1,4 @@
-use crate::Key;
+use crate::{Enum, Key};
 
 pub fn to_key_code(key: Key) -> u16 {
     use super::ffi::*;
@@ -121,3 +121,11 @@ pub fn to_key_code(key: Key) -> u16 {
         Mute => KEY_MUTE,
     }
 }
+
+/// Find the key for an evdev key code. This is the inverse of [`to_key_code`].
+pub fn from_key_code(key_code: u16) -> Option<Key> {
+    if key_code == super::ffi::KEY_RESERVED {
+        return None;
+    }
+    Key::iter().find(|key| to_key_code(*key) == key_code)
+}
@@ -1,6 +1,8 @@
-mod ffi;
+mod evdev;
+pub(crate) mod ffi;
 mod key_code;
 mod scroll;
 
+pub use evdev::*;
 pub use key_code::*;
 pub use scroll::*;
@@ -14,6 +14,11 @@ impl PlatformError {
             Self(NonZeroInt::new_unchecked(*ffi::__errno_location()))
         }
     }
+
+    pub(super) fn from_io(error: &std::io::Error) -> Self {
+        let code = error.raw_os_error().unwrap_or(ffi::EIO);
+        Self(NonZeroInt::new(code).unwrap_or_else(|| NonZeroInt::new(ffi::EIO).unwrap()))
+    }
 }
 
 impl Display for PlatformError {
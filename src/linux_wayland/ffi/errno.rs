@@ -2,7 +2,10 @@
 
 use std::os::raw::c_int;
 
-extern {
+pub const EIO: c_int = 5;
+pub const EAGAIN: c_int = 11;
+
+extern "C" {
     // https://man7.org/linux/man-pages/man3/errno.3.html
     pub fn __errno_location() -> *mut c_int;
 }
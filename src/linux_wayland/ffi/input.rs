@@ -1,5 +1,7 @@
 // https://github.com/torvalds/linux/blob/master/include/uapi/linux/input.h
 
+use super::{_IOC, _IOC_READ};
+
 #[repr(C)]
 #[allow(non_camel_case_types)]
 pub struct timeval {
@@ -27,3 +29,9 @@ pub struct input_id {
 }
 
 pub const BUS_USB: u16 = 0x03;
+
+// The state of every key as a bit vector with one bit per key code.
+#[allow(non_snake_case)]
+pub const fn EVIOCGKEY(len: u32) -> u32 {
+    _IOC(_IOC_READ, 'E' as u32, 0x18, len)
+}
@@ -1,37 +0,0 @@
-// https://www.kernel.org/doc/html/latest/input/event-codes.html
-// https://github.com/torvalds/linux/blob/master/include/uapi/linux/input-event-codes.h
-
-// Part of this same header is in linux_common/ffi/input_event_codes.rs
-
-pub const EV_SYN: u16 = 0x00;
-pub const EV_KEY: u16 = 0x01;
-pub const EV_REL: u16 = 0x02;
-pub const EV_ABS: u16 = 0x03;
-pub const EV_MSC: u16 = 0x04;
-pub const EV_SW: u16 = 0x05;
-pub const EV_LED: u16 = 0x11;
-pub const EV_SND: u16 = 0x12;
-pub const EV_REP: u16 = 0x14;
-pub const EV_FF: u16 = 0x15;
-pub const EV_PWR: u16 = 0x16;
-pub const EV_FF_STATUS: u16 = 0x17;
-pub const EV_MAX: u16 = 0x1f;
-pub const EV_CNT: u16 = EV_MAX + 1;
-
-pub const SYN_REPORT: u16 = 0;
-pub const SYN_CONFIG: u16 = 1;
-pub const SYN_MT_REPORT: u16 = 2;
-pub const SYN_DROPPED: u16 = 3;
-pub const SYN_MAX: u16 = 0xf;
-pub const SYN_CNT: u16 = SYN_MAX + 1;
-
-pub const BTN_LEFT: u16 = 0x110;
-pub const BTN_RIGHT: u16 = 0x111;
-pub const BTN_MIDDLE: u16 = 0x112;
-
-pub const REL_X: u16 = 0x00;
-pub const REL_Y: u16 = 0x01;
-pub const REL_HWHEEL: u16 = 0x06;
-pub const REL_WHEEL: u16 = 0x08;
-pub const REL_WHEEL_HI_RES: u16 = 0x0b;
-pub const REL_HWHEEL_HI_RES: u16 = 0x0c;
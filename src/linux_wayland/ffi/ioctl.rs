@@ -19,10 +19,10 @@ const _IOC_DIRSHIFT: u32 = _IOC_SIZESHIFT + _IOC_SIZEBITS;
 
 const _IOC_NONE: u32 = 0;
 const _IOC_WRITE: u32 = 1;
-const _IOC_READ: u32 = 2;
+pub const _IOC_READ: u32 = 2;
 
 #[allow(non_snake_case)]
-const fn _IOC(dir: u32, type_: u32, nr: u32, size: u32) -> u32 {
+pub const fn _IOC(dir: u32, type_: u32, nr: u32, size: u32) -> u32 {
     (dir << _IOC_DIRSHIFT)
         | (type_ << _IOC_TYPESHIFT)
         | (nr << _IOC_NRSHIFT)
@@ -44,7 +44,7 @@ pub const fn _IOW<T>(type_: u32, nr: u32) -> u32 {
     _IOC(_IOC_WRITE, type_, nr, std::mem::size_of::<T>() as u32)
 }
 
-extern {
+extern "C" {
     // https://man7.org/linux/man-pages/man2/ioctl.2.html
     pub fn ioctl(fd: c_int, request: u32, ...) -> c_int;
 }
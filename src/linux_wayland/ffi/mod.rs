@@ -3,16 +3,16 @@
 mod errno;
 mod fcntl;
 mod input;
-mod input_event_codes;
 mod ioctl;
 mod string;
 mod uinput;
 mod unistd;
 
+// The event codes are shared with the event reader in linux_common.
+pub use crate::linux_common::ffi::*;
 pub use errno::*;
 pub use fcntl::*;
 pub use input::*;
-pub use input_event_codes::*;
 pub use ioctl::*;
 pub use string::*;
 pub use uinput::*;
@@ -2,7 +2,7 @@
 
 use std::os::raw::c_int;
 
-extern {
+extern "C" {
     // https://man7.org/linux/man-pages/man3/strerror_l.3.html
     pub fn strerror(errnum: c_int) -> *const u8;
 
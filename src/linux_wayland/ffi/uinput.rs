@@ -23,3 +23,4 @@ pub const UI_SET_EVBIT: u32 = _IOW::<c_int>(UINPUT_IOCTL_BASE, 100);
 pub const UI_SET_KEYBIT: u32 = _IOW::<c_int>(UINPUT_IOCTL_BASE, 101);
 pub const UI_SET_RELBIT: u32 = _IOW::<c_int>(UINPUT_IOCTL_BASE, 102);
 pub const UI_SET_ABSBIT: u32 = _IOW::<c_int>(UINPUT_IOCTL_BASE, 103);
+pub const UI_SET_LEDBIT: u32 = _IOW::<c_int>(UINPUT_IOCTL_BASE, 105);
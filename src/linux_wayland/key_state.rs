@@ -0,0 +1,111 @@
+use super::{ffi, Context, Error, PlatformError};
+use crate::{linux_common, Enum, Key, Modifiers};
+use std::{
+    ffi::c_void,
+    fs::{self, File},
+    os::unix::io::AsRawFd,
+};
+
+const KEY_BITS_LEN: usize = ffi::KEY_CNT as usize / 8;
+
+// Get the state of every key on every device that can be read. The state of a
+// key is the union of its state on each device.
+fn pressed_key_bits() -> Result<[u8; KEY_BITS_LEN], Error> {
+    let mut bits = [0; KEY_BITS_LEN];
+    let mut last_error = None;
+    let mut read_any = false;
+
+    let entries = fs::read_dir("/dev/input").map_err(|e| Error::Platform(PlatformError::from_io(&e)))?;
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with("event") {
+            continue;
+        }
+        let file = match File::open(entry.path()) {
+            Ok(file) => file,
+            Err(e) => {
+                last_error = Some(e);
+                continue;
+            }
+        };
+
+        let mut device_bits = [0u8; KEY_BITS_LEN];
+        let request = ffi::EVIOCGKEY(KEY_BITS_LEN as u32);
+        if unsafe { ffi::ioctl(file.as_raw_fd(), request, device_bits.as_mut_ptr()) } == -1 {
+            continue;
+        }
+        for (bits, device_bits) in bits.iter_mut().zip(device_bits.iter()) {
+            *bits |= device_bits;
+        }
+        read_any = true;
+    }
+
+    match (read_any, last_error) {
+        (false, Some(e)) => Err(Error::Platform(PlatformError::from_io(&e))),
+        _ => Ok(bits),
+    }
+}
+
+fn is_down(bits: &[u8; KEY_BITS_LEN], key: Key) -> bool {
+    let code = linux_common::to_key_code(key) as usize;
+    bits[code / 8] & (1 << (code % 8)) != 0
+}
+
+impl Context {
+    // The LED events that the compositor writes to the device are read back
+    // from the uinput file.
+    fn update_leds(&self) -> Result<(), Error> {
+        let size = std::mem::size_of::<ffi::input_event>();
+        loop {
+            let mut event: ffi::input_event = unsafe { std::mem::zeroed() };
+            let read = unsafe {
+                ffi::read(self.file, &mut event as *mut ffi::input_event as *mut c_void, size)
+            };
+            if read == -1 {
+                if unsafe { *ffi::__errno_location() } == ffi::EAGAIN {
+                    return Ok(());
+                }
+                return Err(Error::Platform(PlatformError::errno()));
+            }
+            if read != size as isize {
+                return Err(Error::Unknown);
+            }
+            if event.type_ == ffi::EV_LED {
+                let bit = 1 << event.code;
+                if event.value != 0 {
+                    self.leds.set(self.leds.get() | bit);
+                } else {
+                    self.leds.set(self.leds.get() & !bit);
+                }
+            }
+        }
+    }
+
+    fn led(&self, code: u16) -> Result<bool, Error> {
+        self.update_leds()?;
+        Ok(self.leds.get() & (1 << code) != 0)
+    }
+}
+
+impl crate::KeyStateContext for Context {
+    fn is_key_pressed(&self, key: Key) -> Result<bool, Error> {
+        let bits = pressed_key_bits()?;
+        Ok(is_down(&bits, key))
+    }
+
+    fn modifier_state(&self) -> Result<Modifiers, Error> {
+        let bits = pressed_key_bits()?;
+        let mut modifiers = Modifiers::default();
+        for key in Key::iter().filter(|key| is_down(&bits, *key)) {
+            modifiers.update(key, true);
+        }
+        Ok(modifiers)
+    }
+
+    fn caps_lock(&self) -> Result<bool, Error> {
+        self.led(ffi::LED_CAPSL)
+    }
+
+    fn num_lock(&self) -> Result<bool, Error> {
+        self.led(ffi::LED_NUML)
+    }
+}
@@ -14,10 +14,66 @@ impl crate::KeyboardContext for Context {
     fn key_up(&mut self, key: Key) -> Result<(), Error> {
         key_event(self, key, false)
     }
+
+    fn begin_string(&mut self) -> Result<bool, Error> {
+        if self.normalize_key_state {
+            crate::KeyStateContext::normalize_state(self)
+        } else {
+            Ok(false)
+        }
+    }
 }
 
 #[cfg(feature = "ascii-fallback")]
-use crate::{GenericError, FallibleContext, AsciiKeyboardContext};
+use crate::{GenericError, AsciiKeyboardContext, KeyStateContext, traits::is_supported_ascii};
+
+#[cfg(feature = "ascii-fallback")]
+impl Context {
+    fn on_layout(ch: char) -> bool {
+        ch.is_ascii() && is_supported_ascii(ch as u8)
+    }
+
+    fn can_type(&self, ch: char) -> bool {
+        Self::on_layout(ch) || match &self.unicode_strategy {
+            Some(strategy) => strategy.supports(ch),
+            None => false,
+        }
+    }
+
+    fn type_string(&mut self, s: &str) -> Result<(), Error> {
+        for ch in s.chars() {
+            if !self.can_type(ch) {
+                return Err(Error::UnsupportedUnicode(ch));
+            }
+        }
+        for (index, ch) in s.chars().enumerate() {
+            if index != 0 && self.key_delay != std::time::Duration::ZERO && !self.is_batching() {
+                std::thread::sleep(self.key_delay);
+            }
+            self.type_char(ch)?;
+        }
+        Ok(())
+    }
+
+    fn type_char(&mut self, ch: char) -> Result<(), Error> {
+        if Self::on_layout(ch) {
+            return self.ascii_char(ch as u8);
+        }
+        // The strategy is taken out of the context for the duration of the
+        // call so that it can borrow the context mutably.
+        let mut strategy = match self.unicode_strategy.take() {
+            Some(strategy) => strategy,
+            None => return Err(Error::UnsupportedUnicode(ch)),
+        };
+        let result = if strategy.supports(ch) {
+            strategy.type_char(self, ch)
+        } else {
+            Err(Error::UnsupportedUnicode(ch))
+        };
+        self.unicode_strategy = Some(strategy);
+        result
+    }
+}
 
 #[cfg(feature = "ascii-fallback")]
 impl crate::UnicodeKeyboardContext for Context {
@@ -42,19 +98,14 @@ impl crate::UnicodeKeyboardContext for Context {
     }
 
     fn unicode_char(&mut self, ch: char) -> Result<(), GenericError<Self::PlatformError>> {
-        if !ch.is_ascii() {
-            return Err(Error::UnsupportedUnicode(ch));
-        }
-        match self.ascii_char(ch as u8) {
-            Err(Error::UnsupportedAscii(c)) => Err(Error::UnsupportedUnicode(c as char)),
-            other => other,
-        }
+        self.type_char(ch)
     }
 
     fn unicode_string(&mut self, s: &str) -> Result<(), GenericError<Self::PlatformError>> {
-        match self.ascii_string(s.as_bytes()) {
-            Err(Error::UnsupportedAscii(c)) => Err(Error::UnsupportedUnicode(c as char)),
-            other => other,
+        if self.normalize_key_state {
+            self.with_normalized_state(|ctx| ctx.type_string(s))
+        } else {
+            self.type_string(s)
         }
     }
 }
@@ -1,7 +1,14 @@
 mod ffi;
 mod error;
+mod key_state;
 mod keyboard;
 mod mouse;
+mod screen;
+#[cfg(feature = "ascii-fallback")]
+mod unicode;
+
+#[cfg(feature = "ascii-fallback")]
+pub use unicode::*;
 
 // The implementation of this module is adapted from here:
 // https://www.kernel.org/doc/html/latest/input/uinput.html
@@ -9,36 +16,63 @@ mod mouse;
 use error::PlatformError;
 type Error = crate::GenericError<PlatformError>;
 
-/// The main context used for generating events (Linux-Wayland).
+/// The context that generates events with `/dev/uinput` (Linux-Wayland).
+///
+/// This is only available with the `"uinput"` feature. It is exported as
+/// `UinputContext` and can be created with
+/// [`ContextBuilder::build_uinput`](crate::ContextBuilder::build_uinput).
 ///
 /// The most useful methods are on the [`traits`](crate::traits), however the
-/// Linux-Wayland context doesn't implement
-/// [`ScreenContext`](crate::ScreenContext) or
-/// [`UnicodeKeyboardContext`](crate::UnicodeKeyboardContext) but the Linux-X11
-/// context does. The `"ascii-fallback"` feature may be enabled to provide an
+/// [`ScreenContext`](crate::ScreenContext) methods of the Linux-Wayland
+/// context always return [`Info`](crate::GenericError::Info) and it doesn't
+/// implement [`UnicodeKeyboardContext`](crate::UnicodeKeyboardContext) but the
+/// Linux-X11 context does. The `"ascii-fallback"` feature may be enabled to provide an
 /// implementation of [`UnicodeKeyboardContext`](crate::UnicodeKeyboardContext)
-/// that uses [`AsciiKeyboardContext`](crate::AsciiKeyboardContext).
+/// that uses [`AsciiKeyboardContext`](crate::AsciiKeyboardContext). With
+/// that feature enabled, a [`UnicodeStrategy`] may also be set to type
+/// characters beyond ASCII.
 pub struct Context {
     file: std::os::raw::c_int,
     scroll: crate::linux_common::ScrollAccum,
+    key_delay: std::time::Duration,
+    normalize_key_state: bool,
+    // The state of the keyboard LEDs as a bit set of LED codes.
+    leds: std::cell::Cell<u32>,
+    // The events that are queued during a batch.
+    batch: std::cell::RefCell<Option<Vec<ffi::input_event>>>,
+    #[cfg(feature = "ascii-fallback")]
+    unicode_strategy: Option<Box<dyn UnicodeStrategy>>,
 }
 
 impl Context {
     pub fn new() -> Result<Self, Error> {
+        Self::from_builder(&Default::default())
+    }
+
+    pub(crate) fn from_builder(builder: &crate::ContextBuilder) -> Result<Self, Error> {
         let file = unsafe {
-            ffi::open(b"/dev/uinput\0".as_ptr(), ffi::O_WRONLY | ffi::O_NONBLOCK)
+            ffi::open(b"/dev/uinput\0".as_ptr(), ffi::O_RDWR | ffi::O_NONBLOCK)
         };
         if file == -1 {
             return Err(Error::Platform(PlatformError::errno()))
         }
 
-        let ctx = Self { file, scroll: Default::default() };
+        let ctx = Self {
+            file,
+            scroll: Default::default(),
+            key_delay: builder.key_delay,
+            normalize_key_state: builder.normalize_key_state,
+            leds: Default::default(),
+            batch: Default::default(),
+            #[cfg(feature = "ascii-fallback")]
+            unicode_strategy: None,
+        };
 
         ctx.ioctl(ffi::UI_SET_EVBIT, ffi::EV_KEY)?;
         ctx.ioctl(ffi::UI_SET_EVBIT, ffi::EV_REL)?;
+        ctx.ioctl(ffi::UI_SET_EVBIT, ffi::EV_LED)?;
 
-        for k in 0..crate::Key::COUNT {
-            let key = unsafe { std::mem::transmute(k) };
+        for key in <crate::Key as crate::Enum>::iter() {
             let key_code = crate::linux_common::to_key_code(key) as std::os::raw::c_int;
             ctx.ioctl(ffi::UI_SET_KEYBIT, key_code)?;
         }
@@ -47,6 +81,11 @@ impl Context {
         ctx.ioctl(ffi::UI_SET_KEYBIT, ffi::BTN_RIGHT)?;
         ctx.ioctl(ffi::UI_SET_KEYBIT, ffi::BTN_MIDDLE)?;
 
+        // The LEDs are set by the compositor so that the lock state can be
+        // read back.
+        ctx.ioctl(ffi::UI_SET_LEDBIT, ffi::LED_NUML as std::os::raw::c_int)?;
+        ctx.ioctl(ffi::UI_SET_LEDBIT, ffi::LED_CAPSL as std::os::raw::c_int)?;
+
         ctx.ioctl(ffi::UI_SET_RELBIT, ffi::REL_X)?;
         ctx.ioctl(ffi::UI_SET_RELBIT, ffi::REL_Y)?;
         // ctx.ioctl(ffi::UI_SET_RELBIT, ffi::REL_HWHEEL_HI_RES)?;
@@ -56,8 +95,12 @@ impl Context {
 
         let mut setup: ffi::uinput_setup = unsafe { std::mem::zeroed() };
         setup.id.bustype = ffi::BUS_USB;
-        let name = b"The Fat Controller";
-        setup.name[..name.len()].copy_from_slice(name);
+        setup.id.vendor = builder.vendor;
+        setup.id.product = builder.product;
+        let name = builder.device_name_or_default().as_bytes();
+        // The last byte must be left as a null terminator.
+        let len = name.len().min(setup.name.len() - 1);
+        setup.name[..len].copy_from_slice(&name[..len]);
 
         ctx.ioctl(ffi::UI_DEV_SETUP, &setup)?;
         ctx.ioctl_0(ffi::UI_DEV_CREATE)?;
@@ -65,6 +108,47 @@ impl Context {
         Ok(ctx)
     }
 
+    /// Set the strategy used to type characters that aren't on the keyboard
+    /// layout.
+    ///
+    /// By default, there is no strategy so only ASCII characters can be typed.
+    /// Characters that are on the layout are always typed directly. The
+    /// strategy is only used by
+    /// [`unicode_char`](crate::UnicodeKeyboardContext::unicode_char) and
+    /// [`unicode_string`](crate::UnicodeKeyboardContext::unicode_string)
+    /// because it cannot separate key presses from key releases.
+    #[cfg(feature = "ascii-fallback")]
+    pub fn set_unicode_strategy(&mut self, strategy: Option<Box<dyn UnicodeStrategy>>) {
+        self.unicode_strategy = strategy;
+    }
+
+    /// Generate a sequence of events with a single write to the device.
+    ///
+    /// Within a batch, the events are queued and only written once the
+    /// function returns. This is much faster for long sequences of events,
+    /// such as streaming mouse movements. Each event is still followed by its
+    /// own `SYN_REPORT` so that a press and release of the same key aren't
+    /// merged. The [key delay](crate::ContextBuilder::key_delay) is ignored
+    /// within a batch.
+    pub fn batch<T, F>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        if self.is_batching() {
+            return f(self);
+        }
+        *self.batch.borrow_mut() = Some(Vec::new());
+        let result = f(self);
+        let events = self.batch.borrow_mut().take().unwrap_or_default();
+        // The events queued before an error are still written.
+        let written = self.write_events(&events);
+        result.and_then(|value| written.map(|_| value))
+    }
+
+    fn is_batching(&self) -> bool {
+        self.batch.borrow().is_some()
+    }
+
     fn ioctl<T>(&self, request: u32, arg: T) -> Result<(), Error> {
         unsafe {
             if ffi::ioctl(self.file, request, arg) == -1 {
@@ -95,9 +179,20 @@ impl Context {
             code,
             value,
         };
-        let size = std::mem::size_of::<ffi::input_event>();
+        if let Some(events) = self.batch.borrow_mut().as_mut() {
+            events.push(event);
+            return Ok(());
+        }
+        self.write_events(&[event])
+    }
+
+    fn write_events(&self, events: &[ffi::input_event]) -> Result<(), Error> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let size = std::mem::size_of_val(events);
         let written = unsafe {
-            ffi::write(self.file, std::mem::transmute(&event), size)
+            ffi::write(self.file, events.as_ptr() as *const std::ffi::c_void, size)
         };
         if written == -1 {
             Err(Error::Platform(PlatformError::errno()))
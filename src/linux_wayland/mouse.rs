@@ -19,7 +19,7 @@ impl crate::MouseContext for Context {
     }
 
     fn mouse_move_abs(&mut self, x: i32, y: i32) -> Result<(), Error> {
-        self.mouse_move_rel(i32::min_value(), i32::min_value())?;
+        self.mouse_move_rel(i32::MIN, i32::MIN)?;
         self.mouse_move_rel(x, y)
     }
 
@@ -0,0 +1,13 @@
+use super::{Context, Error};
+
+// The screen can't be queried under Wayland. The trait is implemented so that
+// the context can be used as a DynContext.
+impl crate::ScreenContext for Context {
+    fn cursor_location(&self) -> Result<(i32, i32), Error> {
+        Err(Error::Info("The cursor location can't be queried under Wayland".into()))
+    }
+
+    fn screen_size(&self) -> Result<(i32, i32), Error> {
+        Err(Error::Info("The screen size can't be queried under Wayland".into()))
+    }
+}
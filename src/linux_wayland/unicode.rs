@@ -0,0 +1,61 @@
+use crate::{Key, KeyboardContext};
+use super::{Context, Error};
+
+/// A scheme for typing characters that aren't on the keyboard layout
+/// (Linux-Wayland).
+///
+/// The uinput device can only press keys so characters that aren't on the
+/// layout have to be typed using some cooperation from the desktop (an input
+/// method, a compose key, etc). A strategy encapsulates one of these schemes.
+/// See [`Context::set_unicode_strategy`].
+pub trait UnicodeStrategy: Send {
+    /// Check whether this strategy is able to type the given character.
+    fn supports(&self, ch: char) -> bool;
+
+    /// Generate the key presses and releases necessary to type the given
+    /// character.
+    ///
+    /// This is only called for characters where
+    /// [`supports`](UnicodeStrategy::supports) returned `true`.
+    fn type_char(&mut self, ctx: &mut Context, ch: char) -> Result<(), Error>;
+}
+
+/// Types a Unicode code point using the `Ctrl+Shift+U <hex> Space` sequence.
+///
+/// This sequence is understood by GTK applications and by IBus. Other input
+/// methods and toolkits may ignore it. The hexadecimal digits are pressed at
+/// their positions on a US keyboard layout.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HexInputStrategy;
+
+fn hex_digit_key(digit: u32) -> Key {
+    use Key::*;
+    const KEYS: [Key; 16] = [N0, N1, N2, N3, N4, N5, N6, N7, N8, N9, A, B, C, D, E, F];
+    KEYS[digit as usize]
+}
+
+impl UnicodeStrategy for HexInputStrategy {
+    fn supports(&self, ch: char) -> bool {
+        !ch.is_control()
+    }
+
+    fn type_char(&mut self, ctx: &mut Context, ch: char) -> Result<(), Error> {
+        // The modifiers are released even if a later event fails so that they
+        // aren't left stuck.
+        ctx.key_down(Key::Control)?;
+        let result = ctx.key_down(Key::Shift).and_then(|_| {
+            let result = ctx.key_click(Key::U);
+            let released = ctx.key_up(Key::Shift);
+            result.and(released)
+        });
+        let released = ctx.key_up(Key::Control);
+        result.and(released)?;
+
+        for digit in format!("{:x}", ch as u32).chars() {
+            // to_digit can't fail because the string was formatted as hex.
+            ctx.key_click(hex_digit_key(digit.to_digit(16).unwrap()))?;
+        }
+
+        ctx.key_click(Key::Space)
+    }
+}
@@ -0,0 +1,193 @@
+use super::{ffi, trap::trap_display_errors, Context, Error, PlatformError};
+use crate::{Image, Rect, ScreenContext};
+use std::{
+    os::raw::{c_int, c_ulong, c_void},
+    sync::atomic::Ordering,
+};
+use x11::xlib;
+
+const ALL_PLANES: c_ulong = !0;
+
+// Get the position of the lowest set bit and the number of bits in a channel
+// mask.
+fn mask_shift(mask: c_ulong) -> (u32, u32) {
+    if mask == 0 {
+        (0, 0)
+    } else {
+        (
+            mask.trailing_zeros(),
+            (mask >> mask.trailing_zeros()).count_ones(),
+        )
+    }
+}
+
+fn channel(pixel: c_ulong, (shift, bits): (u32, u32)) -> u8 {
+    if bits == 0 {
+        return 0;
+    }
+    let value = (pixel >> shift) & ((1 << bits) - 1);
+    // Scale to 8 bits.
+    ((value * 255) / ((1 << bits) - 1)) as u8
+}
+
+// Convert an XImage in the ZPixmap format to RGBA.
+unsafe fn to_image(ximage: *mut xlib::XImage) -> Image {
+    let ximage = &mut *ximage;
+    let width = ximage.width as u32;
+    let height = ximage.height as u32;
+    let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+
+    let fast = ximage.bits_per_pixel == 32
+        && ximage.byte_order == xlib::LSBFirst
+        && ximage.red_mask == 0xFF0000
+        && ximage.green_mask == 0xFF00
+        && ximage.blue_mask == 0xFF;
+
+    if fast {
+        // The common case of BGRX bytes.
+        for y in 0..height as usize {
+            let row = ximage.data.add(y * ximage.bytes_per_line as usize) as *const u8;
+            let row = std::slice::from_raw_parts(row, width as usize * 4);
+            for pixel in row.chunks_exact(4) {
+                data.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+            }
+        }
+    } else {
+        let red = mask_shift(ximage.red_mask);
+        let green = mask_shift(ximage.green_mask);
+        let blue = mask_shift(ximage.blue_mask);
+        for y in 0..height as c_int {
+            for x in 0..width as c_int {
+                let pixel = xlib::XGetPixel(ximage, x, y);
+                data.extend_from_slice(&[
+                    channel(pixel, red),
+                    channel(pixel, green),
+                    channel(pixel, blue),
+                    255,
+                ]);
+            }
+        }
+    }
+
+    Image::from_rgba(width, height, data).unwrap()
+}
+
+impl Context {
+    pub(super) fn root_window(&self) -> xlib::Window {
+        unsafe { xlib::XRootWindow(self.display as *mut xlib::Display, self.screen_number) }
+    }
+
+    fn capture_xgetimage(&self, rect: Rect) -> Result<Image, Error> {
+        unsafe {
+            let ximage = xlib::XGetImage(
+                self.display as *mut xlib::Display,
+                self.root_window(),
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                ALL_PLANES,
+                xlib::ZPixmap,
+            );
+            if ximage.is_null() {
+                return Err(Error::Platform(PlatformError::XGetImage));
+            }
+            let image = to_image(ximage);
+            xlib::XDestroyImage(ximage);
+            Ok(image)
+        }
+    }
+
+    // Returns None if shared memory can't be used.
+    fn capture_shm(&self, rect: Rect) -> Option<Image> {
+        unsafe {
+            let display = self.display as *mut xlib::Display;
+            let mut info: ffi::XShmSegmentInfo = std::mem::zeroed();
+            let ximage = ffi::XShmCreateImage(
+                self.display,
+                xlib::XDefaultVisual(display, self.screen_number),
+                xlib::XDefaultDepth(display, self.screen_number) as u32,
+                xlib::ZPixmap,
+                std::ptr::null_mut(),
+                &mut info,
+                rect.width,
+                rect.height,
+            );
+            if ximage.is_null() {
+                return None;
+            }
+
+            let size = (*ximage).bytes_per_line as usize * rect.height as usize;
+            info.shmid = ffi::shmget(ffi::IPC_PRIVATE, size, ffi::IPC_CREAT | 0o600);
+            if info.shmid == -1 {
+                xlib::XDestroyImage(ximage);
+                return None;
+            }
+            info.shmaddr = ffi::shmat(info.shmid, std::ptr::null(), 0) as *mut _;
+            // The segment is destroyed once it has been detached by both
+            // processes.
+            ffi::shmctl(info.shmid, ffi::IPC_RMID, std::ptr::null_mut());
+            if info.shmaddr as isize == -1 {
+                xlib::XDestroyImage(ximage);
+                return None;
+            }
+            (*ximage).data = info.shmaddr;
+            info.readOnly = ffi::False;
+
+            // Attaching fails with an error if the server can't access the
+            // segment.
+            let attached = trap_display_errors(self.display, || {
+                ffi::XShmAttach(self.display, &mut info) != ffi::False
+            });
+            let image = if let Ok(true) = attached {
+                let got = ffi::XShmGetImage(
+                    self.display,
+                    self.root_window(),
+                    ximage,
+                    rect.x,
+                    rect.y,
+                    ALL_PLANES,
+                ) != ffi::False;
+                ffi::XShmDetach(self.display, &mut info);
+                ffi::XSync(self.display, ffi::False);
+                if got {
+                    Some(to_image(ximage))
+                } else {
+                    None
+                }
+            } else {
+                self.use_shm.store(false, Ordering::Relaxed);
+                None
+            };
+
+            ffi::shmdt(info.shmaddr as *const c_void);
+            // The data belongs to the segment so it mustn't be freed.
+            (*ximage).data = std::ptr::null_mut();
+            xlib::XDestroyImage(ximage);
+            image
+        }
+    }
+}
+
+impl crate::ScreenCaptureContext for Context {
+    fn capture(&self, rect: Rect) -> Result<Image, Error> {
+        let (width, height) = self.screen_size()?;
+        if rect.x < 0
+            || rect.y < 0
+            || rect.x as i64 + rect.width as i64 > width as i64
+            || rect.y as i64 + rect.height as i64 > height as i64
+        {
+            return Err(Error::Info(format!("{:?} is outside of the screen", rect)));
+        }
+        if rect.width == 0 || rect.height == 0 {
+            return Ok(Image::new(rect.width, rect.height));
+        }
+
+        if self.use_shm.load(Ordering::Relaxed) {
+            if let Some(image) = self.capture_shm(rect) {
+                return Ok(image);
+            }
+        }
+        self.capture_xgetimage(rect)
+    }
+}
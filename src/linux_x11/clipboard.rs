@@ -0,0 +1,445 @@
+use super::{ffi, trap::trap_display_errors, Context, Error, PlatformError};
+use crate::{linux_common, Selection};
+use std::{
+    ffi::CString,
+    io,
+    os::raw::{c_int, c_long, c_uchar},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use x11::xlib;
+
+// How often the owner thread checks whether it should stop.
+const POLL_TIMEOUT_MS: c_int = 100;
+// How long to wait for the owner of a selection to send its contents.
+const CONVERT_TIMEOUT: Duration = Duration::from_secs(1);
+const CONVERT_INTERVAL: Duration = Duration::from_millis(5);
+// How long to wait for the owner thread to take a selection.
+const OWN_TIMEOUT: Duration = Duration::from_secs(1);
+// The property on the requestor window that the contents are stored in.
+const PROPERTY_NAME: &str = "TFC_SELECTION";
+
+// Larger properties would need the INCR protocol. The size is in 4 byte units
+// and some space is left for the rest of the request.
+unsafe fn max_text_len(display: *mut xlib::Display) -> usize {
+    (xlib::XMaxRequestSize(display) as usize * 4).saturating_sub(256)
+}
+
+unsafe fn intern_atom(display: *mut xlib::Display, name: &str) -> xlib::Atom {
+    let name = CString::new(name).unwrap();
+    xlib::XInternAtom(display, name.as_ptr(), xlib::False)
+}
+
+#[derive(Copy, Clone)]
+struct Atoms {
+    targets: xlib::Atom,
+    utf8_string: xlib::Atom,
+    text: xlib::Atom,
+}
+
+/// Owns selections on behalf of a context.
+///
+/// X11 selections are not stored by the server. Instead, the owner sends the
+/// contents to each application that asks for them. This is done from a
+/// thread with its own connection so that pasting works while the context is
+/// idle. The selections are released when this is dropped.
+pub(super) struct ClipboardOwner {
+    window: xlib::Window,
+    // A request with no text gives up the selection.
+    requests: Sender<(xlib::Atom, Option<Vec<u8>>)>,
+    replies: Receiver<bool>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ClipboardOwner {
+    /// Start the owner thread on the same display as a context.
+    fn open(display: *mut ffi::Display) -> Result<Self, Error> {
+        unsafe {
+            let name = xlib::XDisplayString(display as *mut xlib::Display);
+            let display = xlib::XOpenDisplay(name);
+            if display.is_null() {
+                return Err(Error::Platform(PlatformError::XOpenDisplay));
+            }
+
+            // Events sent to this window with an empty mask are reported to
+            // this connection so the context can use it to wake up the thread.
+            let root = xlib::XDefaultRootWindow(display);
+            let window = xlib::XCreateSimpleWindow(display, root, 0, 0, 1, 1, 0, 0, 0);
+            let atoms = Atoms {
+                targets: intern_atom(display, "TARGETS"),
+                utf8_string: intern_atom(display, "UTF8_STRING"),
+                text: intern_atom(display, "TEXT"),
+            };
+            xlib::XSync(display, xlib::False);
+
+            let (requests, thread_requests) = mpsc::channel();
+            let (thread_replies, replies) = mpsc::channel();
+            let stop = Arc::new(AtomicBool::new(false));
+            let thread_stop = stop.clone();
+            let display = display as usize;
+            let thread = thread::spawn(move || {
+                let display = display as *mut xlib::Display;
+                serve_selections(
+                    display,
+                    window,
+                    atoms,
+                    &thread_requests,
+                    &thread_replies,
+                    &thread_stop,
+                );
+                xlib::XDestroyWindow(display, window);
+                xlib::XCloseDisplay(display);
+            });
+
+            Ok(Self {
+                window,
+                requests,
+                replies,
+                stop,
+                thread: Some(thread),
+            })
+        }
+    }
+}
+
+impl Drop for ClipboardOwner {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+unsafe fn serve_selections(
+    display: *mut xlib::Display,
+    window: xlib::Window,
+    atoms: Atoms,
+    requests: &Receiver<(xlib::Atom, Option<Vec<u8>>)>,
+    replies: &Sender<bool>,
+    stop: &AtomicBool,
+) {
+    let mut contents = Vec::<(xlib::Atom, Vec<u8>)>::new();
+    let max_length = max_text_len(display);
+
+    while !stop.load(Ordering::Relaxed) {
+        for (selection, text) in requests.try_iter() {
+            contents.retain(|(s, _)| *s != selection);
+            let done = match text {
+                Some(text) => {
+                    contents.push((selection, text));
+                    xlib::XSetSelectionOwner(display, selection, window, xlib::CurrentTime);
+                    xlib::XGetSelectionOwner(display, selection) == window
+                }
+                None => {
+                    if xlib::XGetSelectionOwner(display, selection) == window {
+                        xlib::XSetSelectionOwner(display, selection, 0, xlib::CurrentTime);
+                    }
+                    true
+                }
+            };
+            if replies.send(done).is_err() {
+                return;
+            }
+        }
+
+        if xlib::XPending(display) == 0 {
+            let mut fds = linux_common::ffi::pollfd {
+                fd: xlib::XConnectionNumber(display),
+                events: linux_common::ffi::POLLIN,
+                revents: 0,
+            };
+            let ready = linux_common::ffi::poll(&mut fds, 1, POLL_TIMEOUT_MS);
+            if ready < 0 {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return;
+            }
+            if fds.revents & (linux_common::ffi::POLLERR | linux_common::ffi::POLLHUP) != 0 {
+                return;
+            }
+            // Reads any available data from the connection.
+            if ready == 0 || xlib::XPending(display) == 0 {
+                continue;
+            }
+        }
+
+        let mut event: xlib::XEvent = std::mem::zeroed();
+        xlib::XNextEvent(display, &mut event);
+
+        match event.type_ {
+            xlib::SelectionRequest => {
+                let request = event.selection_request;
+                let text = contents
+                    .iter()
+                    .find(|(s, _)| *s == request.selection)
+                    .map(|(_, text)| text.as_slice())
+                    .filter(|text| text.len() <= max_length);
+                // The requestor may be destroyed at any time.
+                let _ = trap_display_errors(display as *mut ffi::Display, || {
+                    send_selection(display, &request, atoms, text)
+                });
+            }
+            xlib::SelectionClear => {
+                // A clear for a previous ownership can arrive after the
+                // selection was taken again.
+                let selection = event.selection_clear.selection;
+                if xlib::XGetSelectionOwner(display, selection) != window {
+                    contents.retain(|(s, _)| *s != selection);
+                }
+            }
+            // Client messages are only sent to wake up the thread.
+            _ => {}
+        }
+    }
+}
+
+unsafe fn send_selection(
+    display: *mut xlib::Display,
+    request: &xlib::XSelectionRequestEvent,
+    atoms: Atoms,
+    text: Option<&[u8]>,
+) {
+    // Obsolete clients don't set the property.
+    let property = if request.property == 0 {
+        request.target
+    } else {
+        request.property
+    };
+    let mut converted = false;
+
+    if let Some(text) = text {
+        if request.target == atoms.targets {
+            let targets = [
+                atoms.targets,
+                atoms.utf8_string,
+                atoms.text,
+                xlib::XA_STRING,
+            ];
+            xlib::XChangeProperty(
+                display,
+                request.requestor,
+                property,
+                xlib::XA_ATOM,
+                32,
+                xlib::PropModeReplace,
+                targets.as_ptr() as *const c_uchar,
+                targets.len() as c_int,
+            );
+            converted = true;
+        } else if request.target == atoms.utf8_string
+            || request.target == atoms.text
+            || (request.target == xlib::XA_STRING && text.is_ascii())
+        {
+            let type_ = if request.target == atoms.text {
+                atoms.utf8_string
+            } else {
+                request.target
+            };
+            xlib::XChangeProperty(
+                display,
+                request.requestor,
+                property,
+                type_,
+                8,
+                xlib::PropModeReplace,
+                text.as_ptr(),
+                text.len() as c_int,
+            );
+            converted = true;
+        }
+    }
+
+    let mut event: xlib::XEvent = std::mem::zeroed();
+    event.selection = xlib::XSelectionEvent {
+        type_: xlib::SelectionNotify,
+        serial: 0,
+        send_event: xlib::True,
+        display,
+        requestor: request.requestor,
+        selection: request.selection,
+        target: request.target,
+        property: if converted { property } else { 0 },
+        time: request.time,
+    };
+    xlib::XSendEvent(display, request.requestor, xlib::False, 0, &mut event);
+}
+
+impl Context {
+    // Send a request to the owner thread and wait for it to be handled.
+    fn send_clipboard_request(
+        &mut self,
+        selection: Selection,
+        text: Option<Vec<u8>>,
+    ) -> Result<(), Error> {
+        let selection = self.selection_atom(selection);
+        let owner = self.clipboard.as_ref().unwrap();
+        let window = owner.window;
+        // Discard the reply to a request that previously timed out.
+        while owner.replies.try_recv().is_ok() {}
+        if owner.requests.send((selection, text)).is_err() {
+            self.clipboard = None;
+            return Err(Error::Platform(PlatformError::XSetSelectionOwner));
+        }
+
+        self.trap_errors(|ctx| unsafe {
+            let mut event: xlib::XEvent = std::mem::zeroed();
+            event.client_message.type_ = xlib::ClientMessage;
+            event.client_message.window = window;
+            event.client_message.message_type = ctx.atom(PROPERTY_NAME);
+            event.client_message.format = 32;
+            xlib::XSendEvent(ctx.xdisplay(), window, xlib::False, 0, &mut event);
+            // The event must be sent now, even during a batch.
+            xlib::XFlush(ctx.xdisplay());
+            Ok(())
+        })?;
+
+        match self
+            .clipboard
+            .as_ref()
+            .unwrap()
+            .replies
+            .recv_timeout(OWN_TIMEOUT)
+        {
+            Ok(true) => Ok(()),
+            _ => Err(Error::Platform(PlatformError::XSetSelectionOwner)),
+        }
+    }
+
+    fn selection_atom(&self, selection: Selection) -> xlib::Atom {
+        match selection {
+            Selection::Clipboard => self.atom("CLIPBOARD"),
+            Selection::Primary => xlib::XA_PRIMARY,
+        }
+    }
+
+    // Ask the owner of a selection to store its contents on a temporary
+    // window. None is returned if the owner refuses.
+    unsafe fn convert_selection(
+        &self,
+        selection: xlib::Atom,
+        target: xlib::Atom,
+    ) -> Result<Option<Vec<u8>>, PlatformError> {
+        let display = self.xdisplay();
+        let window = xlib::XCreateSimpleWindow(display, self.root_window(), 0, 0, 1, 1, 0, 0, 0);
+        let property = self.atom(PROPERTY_NAME);
+        xlib::XConvertSelection(
+            display,
+            selection,
+            target,
+            property,
+            window,
+            xlib::CurrentTime,
+        );
+        xlib::XFlush(display);
+
+        let deadline = Instant::now() + CONVERT_TIMEOUT;
+        let mut result = Err(PlatformError::XConvertSelection);
+        while Instant::now() < deadline {
+            let mut event: xlib::XEvent = std::mem::zeroed();
+            if xlib::XCheckTypedWindowEvent(display, window, xlib::SelectionNotify, &mut event) == 0
+            {
+                thread::sleep(CONVERT_INTERVAL);
+                continue;
+            }
+            result = Ok(None);
+            if event.selection.property != 0 {
+                result = Ok(read_property(display, window, property, target));
+            }
+            break;
+        }
+
+        xlib::XDestroyWindow(display, window);
+        result
+    }
+}
+
+// Read and delete a property. Properties of other types (such as INCR for
+// large transfers) are treated as missing.
+unsafe fn read_property(
+    display: *mut xlib::Display,
+    window: xlib::Window,
+    property: xlib::Atom,
+    type_: xlib::Atom,
+) -> Option<Vec<u8>> {
+    let mut actual_type = 0;
+    let mut format = 0;
+    let mut count = 0;
+    let mut bytes_after = 0;
+    let mut data: *mut c_uchar = std::ptr::null_mut();
+    let status = xlib::XGetWindowProperty(
+        display,
+        window,
+        property,
+        0,
+        c_long::MAX / 4,
+        xlib::True,
+        xlib::AnyPropertyType as xlib::Atom,
+        &mut actual_type,
+        &mut format,
+        &mut count,
+        &mut bytes_after,
+        &mut data,
+    );
+    if status != xlib::Success as c_int || data.is_null() {
+        return None;
+    }
+    let bytes = std::slice::from_raw_parts(data, count as usize).to_vec();
+    xlib::XFree(data as *mut _);
+    if actual_type == type_ && format == 8 {
+        Some(bytes)
+    } else {
+        None
+    }
+}
+
+impl crate::ClipboardContext for Context {
+    fn clipboard_text(&self, selection: Selection) -> Result<Option<String>, Error> {
+        let selection = self.selection_atom(selection);
+        let utf8_string = self.atom("UTF8_STRING");
+        unsafe {
+            trap_display_errors(self.display, || {
+                if xlib::XGetSelectionOwner(self.xdisplay(), selection) == 0 {
+                    return Ok(None);
+                }
+                if let Some(bytes) = self.convert_selection(selection, utf8_string)? {
+                    return Ok(Some(String::from_utf8_lossy(&bytes).into_owned()));
+                }
+                // STRING is Latin-1 which maps directly to the first 256
+                // code points.
+                Ok(self
+                    .convert_selection(selection, xlib::XA_STRING)?
+                    .map(|bytes| bytes.iter().map(|b| *b as char).collect()))
+            })
+            .and_then(|result| result)
+            .map_err(Error::Platform)
+        }
+    }
+
+    fn set_clipboard_text(&mut self, selection: Selection, text: &str) -> Result<(), Error> {
+        if text.len() > unsafe { max_text_len(self.xdisplay()) } {
+            return Err(Error::Info(
+                "The text is too long to be put into the clipboard".into(),
+            ));
+        }
+        if self.clipboard.is_none() {
+            self.clipboard = Some(ClipboardOwner::open(self.display)?);
+        }
+        self.send_clipboard_request(selection, Some(text.as_bytes().to_vec()))
+    }
+
+    fn clear_clipboard(&mut self, selection: Selection) -> Result<(), Error> {
+        // The selection can only be owned by the owner thread.
+        if self.clipboard.is_none() {
+            return Ok(());
+        }
+        self.send_clipboard_request(selection, None)
+    }
+}
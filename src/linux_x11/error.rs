@@ -15,6 +15,25 @@ pub enum PlatformError {
     NoUnusedKeyCode,
     XkbGetMap,
     XGetModifierMapping,
+    XkbGetNames,
+    XkbLockGroup,
+    XkbQueryExtension,
+    XProtocol {
+        error_code: u8,
+        request_code: u8,
+        minor_code: u8,
+    },
+    ConnectionLost,
+    InvalidScreen,
+    XRecordQueryVersion,
+    XRecordCreateContext,
+    XGrabKey,
+    XkbGetIndicatorState,
+    XGetImage,
+    XSendEvent,
+    XSetSelectionOwner,
+    XConvertSelection,
+    XIQueryVersion,
 }
 
 impl Display for PlatformError {
@@ -32,6 +51,29 @@ impl Display for PlatformError {
             NoUnusedKeyCode => write!(f, "Couldn't find a keycode with no associated keysyms"),
             XkbGetMap => write!(f, "Failed to get keyboard information"),
             XGetModifierMapping => write!(f, "Failed to get modifier key mapping"),
+            XkbGetNames => write!(f, "Failed to get keyboard layout names"),
+            XkbLockGroup => write!(f, "Failed to switch keyboard layout"),
+            XkbQueryExtension => write!(f, "XKB extension is unavailable"),
+            XProtocol {
+                error_code,
+                request_code,
+                minor_code,
+            } => write!(
+                f,
+                "X protocol error {} in request {}.{}",
+                error_code, request_code, minor_code
+            ),
+            ConnectionLost => write!(f, "Lost connection to the X server"),
+            InvalidScreen => write!(f, "Screen number is out of range"),
+            XRecordQueryVersion => write!(f, "RECORD extension is unavailable"),
+            XRecordCreateContext => write!(f, "Failed to start recording input"),
+            XGrabKey => write!(f, "Hotkey is already grabbed by another client"),
+            XkbGetIndicatorState => write!(f, "Failed to get keyboard indicator state"),
+            XGetImage => write!(f, "Failed to capture the screen"),
+            XSendEvent => write!(f, "Failed to send event to window"),
+            XSetSelectionOwner => write!(f, "Failed to take ownership of the selection"),
+            XConvertSelection => write!(f, "Timed out waiting for the selection contents"),
+            XIQueryVersion => write!(f, "XInput 2 extension is unavailable"),
         }
     }
 }
@@ -0,0 +1,16 @@
+// dlfcn.h
+
+use std::ffi::c_void;
+use std::os::raw::{c_char, c_int};
+
+pub const RTLD_DEFAULT: *mut c_void = std::ptr::null_mut();
+pub const RTLD_LAZY: c_int = 1;
+
+extern "C" {
+    // https://man7.org/linux/man-pages/man3/dlopen.3.html
+    pub fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+    pub fn dlclose(handle: *mut c_void) -> c_int;
+
+    // https://man7.org/linux/man-pages/man3/dlsym.3.html
+    pub fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
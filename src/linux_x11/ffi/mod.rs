@@ -1,9 +1,19 @@
+mod dlfcn;
 mod xkb;
+mod xinput2;
 mod xkbcommon;
 mod xlib;
+mod xrandr;
+mod xrecord;
+mod xshm;
 mod xtest;
 
+pub use dlfcn::*;
 pub use xkb::*;
+pub use xinput2::*;
 pub use xkbcommon::*;
 pub use xlib::*;
+pub use xrandr::*;
+pub use xrecord::*;
+pub use xshm::*;
 pub use xtest::*;
@@ -0,0 +1,175 @@
+// X11/extensions/XInput2.h
+// https://www.x.org/releases/X11R7.7/doc/inputproto/XI2proto.txt
+
+use super::{Bool, Display};
+use std::os::raw::{c_char, c_double, c_int, c_uchar, c_ulong};
+use x11::xlib::{Time, Window};
+
+#[allow(non_upper_case_globals)]
+pub const XIAllDevices: c_int = 0;
+#[allow(non_upper_case_globals)]
+pub const XIAllMasterDevices: c_int = 1;
+
+#[allow(non_upper_case_globals)]
+pub const XISlavePointer: c_int = 3;
+#[allow(non_upper_case_globals)]
+pub const XISlaveKeyboard: c_int = 4;
+
+#[allow(non_upper_case_globals)]
+pub const XIGrabModeAsync: c_int = 1;
+
+#[allow(non_upper_case_globals)]
+pub const XIKeyRepeat: c_int = 1 << 16;
+
+#[allow(non_upper_case_globals)]
+pub const XI_KeyPress: c_int = 2;
+#[allow(non_upper_case_globals)]
+pub const XI_KeyRelease: c_int = 3;
+#[allow(non_upper_case_globals)]
+pub const XI_HierarchyChanged: c_int = 11;
+#[allow(non_upper_case_globals)]
+pub const XI_RawKeyPress: c_int = 13;
+#[allow(non_upper_case_globals)]
+pub const XI_RawKeyRelease: c_int = 14;
+
+#[repr(C)]
+pub struct XIEventMask {
+    pub deviceid: c_int,
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+
+#[repr(C)]
+pub struct XIGrabModifiers {
+    pub modifiers: c_int,
+    pub status: c_int,
+}
+
+#[repr(C)]
+pub struct XIAnyClassInfo {
+    pub type_: c_int,
+    pub sourceid: c_int,
+}
+
+#[repr(C)]
+pub struct XIDeviceInfo {
+    pub deviceid: c_int,
+    pub name: *mut c_char,
+    pub use_: c_int,
+    pub attachment: c_int,
+    pub enabled: Bool,
+    pub num_classes: c_int,
+    pub classes: *mut *mut XIAnyClassInfo,
+}
+
+#[repr(C)]
+pub struct XIButtonState {
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+
+#[repr(C)]
+pub struct XIValuatorState {
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+    pub values: *mut c_double,
+}
+
+#[repr(C)]
+pub struct XIModifierState {
+    pub base: c_int,
+    pub latched: c_int,
+    pub locked: c_int,
+    pub effective: c_int,
+}
+
+pub type XIGroupState = XIModifierState;
+
+#[repr(C)]
+pub struct XIDeviceEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    pub sourceid: c_int,
+    pub detail: c_int,
+    pub root: Window,
+    pub event: Window,
+    pub child: Window,
+    pub root_x: c_double,
+    pub root_y: c_double,
+    pub event_x: c_double,
+    pub event_y: c_double,
+    pub flags: c_int,
+    pub buttons: XIButtonState,
+    pub valuators: XIValuatorState,
+    pub mods: XIModifierState,
+    pub group: XIGroupState,
+}
+
+#[repr(C)]
+pub struct XIRawEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    pub sourceid: c_int,
+    pub detail: c_int,
+    pub flags: c_int,
+    pub valuators: XIValuatorState,
+    pub raw_values: *mut c_double,
+}
+
+#[link(name = "Xi")]
+extern "C" {
+    pub fn XIQueryVersion(
+        display: *mut Display,
+        major_version_inout: *mut c_int,
+        minor_version_inout: *mut c_int,
+    ) -> c_int;
+
+    pub fn XIQueryDevice(
+        display: *mut Display,
+        deviceid: c_int,
+        ndevices_return: *mut c_int,
+    ) -> *mut XIDeviceInfo;
+
+    pub fn XIFreeDeviceInfo(info: *mut XIDeviceInfo);
+
+    pub fn XISelectEvents(
+        display: *mut Display,
+        win: Window,
+        masks: *mut XIEventMask,
+        num_masks: c_int,
+    ) -> c_int;
+
+    pub fn XIGrabKeycode(
+        display: *mut Display,
+        deviceid: c_int,
+        keycode: c_int,
+        grab_window: Window,
+        grab_mode: c_int,
+        paired_device_mode: c_int,
+        owner_events: Bool,
+        mask: *mut XIEventMask,
+        num_modifiers: c_int,
+        modifiers_inout: *mut XIGrabModifiers,
+    ) -> c_int;
+
+    pub fn XIUngrabKeycode(
+        display: *mut Display,
+        deviceid: c_int,
+        keycode: c_int,
+        grab_window: Window,
+        num_modifiers: c_int,
+        modifiers: *mut XIGrabModifiers,
+    ) -> c_int;
+}
@@ -42,6 +42,22 @@ pub struct XModifierKeymap {
 unsafe impl Sync for XModifierKeymap {}
 unsafe impl Send for XModifierKeymap {}
 
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XErrorEvent {
+    pub type_: c_int,
+    pub display: *mut Display,
+    pub resourceid: XID,
+    pub serial: c_ulong,
+    pub error_code: u8,
+    pub request_code: u8,
+    pub minor_code: u8,
+}
+
+pub type XErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
+
+pub type XIOErrorExitHandler = Option<unsafe extern "C" fn(*mut Display, *mut c_void)>;
+
 #[link(name = "X11")]
 extern "C" {
     // https://www.x.org/releases/X11R7.5/doc/man/man3/XOpenDisplay.3.html
@@ -53,6 +69,9 @@ extern "C" {
     // Macro directly accesses struct member
     pub fn XDefaultScreen(display: *mut Display) -> c_int;
 
+    // Macro directly accesses struct member
+    pub fn XScreenCount(display: *mut Display) -> c_int;
+
     // Macro directly accesses struct member
     pub fn XScreenOfDisplay(display: *mut Display, screen_number: c_int) -> *mut Screen;
 
@@ -97,9 +116,6 @@ extern "C" {
     // https://www.x.org/releases/X11R7.5/doc/man/man3/XSync.3.html
     pub fn XSync(display: *mut Display, discard: Bool) -> c_int;
 
-    // https://www.x.org/releases/current/doc/man/man3/XFlush.3.xhtml
-    pub fn XFlush(display: *mut Display) -> c_int;
-
     // https://www.x.org/releases/X11R7.5/doc/man/man3/XDisplayKeycodes.3.html
     pub fn XDisplayKeycodes(
         display: *mut Display,
@@ -140,4 +156,13 @@ extern "C" {
 
     // https://www.x.org/releases/X11R6.8.0/doc/XFreeModifierMap.3.html
     pub fn XFreeModifiermap(modmap: *const XModifierKeymap);
+
+    // https://www.x.org/releases/current/doc/man/man3/XSetErrorHandler.3.xhtml
+    pub fn XSetErrorHandler(handler: XErrorHandler) -> XErrorHandler;
 }
+
+// https://www.x.org/releases/current/doc/man/man3/XSetErrorHandler.3.xhtml
+// This is only available since libX11 1.7 so it's looked up at runtime
+// instead of being linked.
+pub type XSetIOErrorExitHandler =
+    unsafe extern "C" fn(display: *mut Display, handler: XIOErrorExitHandler, user_data: *mut c_void);
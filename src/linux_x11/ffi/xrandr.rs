@@ -0,0 +1,32 @@
+// X11/extensions/Xrandr.h
+// libXrandr is loaded at runtime so that it isn't required.
+
+use super::{Atom, Bool, Display, Window};
+use std::os::raw::{c_int, c_ulong};
+
+pub const LIBXRANDR: &[u8] = b"libXrandr.so.2\0";
+
+#[repr(C)]
+pub struct XRRMonitorInfo {
+    pub name: Atom,
+    pub primary: Bool,
+    pub automatic: Bool,
+    pub noutput: c_int,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub mwidth: c_int,
+    pub mheight: c_int,
+    pub outputs: *mut c_ulong,
+}
+
+// Available since libXrandr 1.5
+pub type XRRGetMonitors = unsafe extern "C" fn(
+    display: *mut Display,
+    window: Window,
+    get_active: Bool,
+    nmonitors: *mut c_int,
+) -> *mut XRRMonitorInfo;
+
+pub type XRRFreeMonitors = unsafe extern "C" fn(monitors: *mut XRRMonitorInfo);
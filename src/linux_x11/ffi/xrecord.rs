@@ -0,0 +1,93 @@
+// X11/extensions/record.h
+// https://www.x.org/releases/X11R7.7/doc/libXtst/recordlib.html
+
+use super::{Bool, Display};
+use std::os::raw::{c_char, c_int, c_ulong};
+
+pub type XRecordContext = c_ulong;
+pub type XRecordClientSpec = c_ulong;
+
+#[allow(non_upper_case_globals)]
+pub const XRecordAllClients: XRecordClientSpec = 3;
+
+#[allow(non_upper_case_globals)]
+pub const XRecordFromServer: c_int = 0;
+
+#[repr(C)]
+pub struct XRecordRange8 {
+    pub first: u8,
+    pub last: u8,
+}
+
+#[repr(C)]
+pub struct XRecordRange16 {
+    pub first: u16,
+    pub last: u16,
+}
+
+#[repr(C)]
+pub struct XRecordExtRange {
+    pub ext_major: XRecordRange8,
+    pub ext_minor: XRecordRange16,
+}
+
+#[repr(C)]
+pub struct XRecordRange {
+    pub core_requests: XRecordRange8,
+    pub core_replies: XRecordRange8,
+    pub ext_requests: XRecordExtRange,
+    pub ext_replies: XRecordExtRange,
+    pub delivered_events: XRecordRange8,
+    pub device_events: XRecordRange8,
+    pub errors: XRecordRange8,
+    pub client_started: Bool,
+    pub client_died: Bool,
+}
+
+#[repr(C)]
+pub struct XRecordInterceptData {
+    pub id_base: c_ulong,
+    pub server_time: c_ulong,
+    pub client_seq: c_ulong,
+    pub category: c_int,
+    pub client_swapped: Bool,
+    pub data: *const u8,
+    pub data_len: c_ulong,
+}
+
+pub type XRecordInterceptProc =
+    Option<unsafe extern "C" fn(closure: *mut c_char, data: *mut XRecordInterceptData)>;
+
+#[link(name = "Xtst")]
+extern "C" {
+    pub fn XRecordQueryVersion(
+        display: *mut Display,
+        cmajor_return: *mut c_int,
+        cminor_return: *mut c_int,
+    ) -> Bool;
+
+    pub fn XRecordAllocRange() -> *mut XRecordRange;
+
+    pub fn XRecordCreateContext(
+        display: *mut Display,
+        datum_flags: c_int,
+        clients: *mut XRecordClientSpec,
+        nclients: c_int,
+        ranges: *mut *mut XRecordRange,
+        nranges: c_int,
+    ) -> XRecordContext;
+
+    // Blocks until the context is disabled.
+    pub fn XRecordEnableContext(
+        display: *mut Display,
+        context: XRecordContext,
+        callback: XRecordInterceptProc,
+        closure: *mut c_char,
+    ) -> Bool;
+
+    pub fn XRecordDisableContext(display: *mut Display, context: XRecordContext) -> Bool;
+
+    pub fn XRecordFreeContext(display: *mut Display, context: XRecordContext) -> Bool;
+
+    pub fn XRecordFreeData(data: *mut XRecordInterceptData);
+}
@@ -0,0 +1,63 @@
+// X11/extensions/XShm.h
+// https://www.x.org/releases/X11R7.7/doc/xextproto/shm.html
+
+use super::{Bool, Display};
+use std::os::raw::{c_char, c_int, c_uint, c_ulong, c_void};
+use x11::xlib::{Visual, XImage};
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[allow(non_snake_case)]
+pub struct XShmSegmentInfo {
+    pub shmseg: c_ulong,
+    pub shmid: c_int,
+    pub shmaddr: *mut c_char,
+    pub readOnly: Bool,
+}
+
+#[link(name = "Xext")]
+extern "C" {
+    pub fn XShmQueryExtension(display: *mut Display) -> Bool;
+
+    pub fn XShmCreateImage(
+        display: *mut Display,
+        visual: *mut Visual,
+        depth: c_uint,
+        format: c_int,
+        data: *mut c_char,
+        shminfo: *mut XShmSegmentInfo,
+        width: c_uint,
+        height: c_uint,
+    ) -> *mut XImage;
+
+    pub fn XShmAttach(display: *mut Display, shminfo: *mut XShmSegmentInfo) -> Bool;
+
+    pub fn XShmDetach(display: *mut Display, shminfo: *mut XShmSegmentInfo) -> Bool;
+
+    pub fn XShmGetImage(
+        display: *mut Display,
+        drawable: c_ulong,
+        image: *mut XImage,
+        x: c_int,
+        y: c_int,
+        plane_mask: c_ulong,
+    ) -> Bool;
+}
+
+// sys/shm.h and sys/ipc.h
+
+pub const IPC_PRIVATE: c_int = 0;
+pub const IPC_CREAT: c_int = 0o1000;
+pub const IPC_RMID: c_int = 0;
+
+extern "C" {
+    // https://man7.org/linux/man-pages/man2/shmget.2.html
+    pub fn shmget(key: c_int, size: usize, shmflg: c_int) -> c_int;
+
+    // https://man7.org/linux/man-pages/man2/shmat.2.html
+    pub fn shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) -> *mut c_void;
+    pub fn shmdt(shmaddr: *const c_void) -> c_int;
+
+    // https://man7.org/linux/man-pages/man2/shmctl.2.html
+    pub fn shmctl(shmid: c_int, cmd: c_int, buf: *mut c_void) -> c_int;
+}
@@ -0,0 +1,226 @@
+use super::{
+    ffi, open_display,
+    trap::trap_display_errors,
+    xinput::{self, XTestDevices},
+    Error, PlatformError,
+};
+use crate::{linux_common, ContextBuilder, Hotkey};
+use std::{
+    ffi::c_void,
+    os::raw::{c_int, c_uint},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+use x11::xlib;
+
+// Caps Lock and Num Lock (usually Mod2) would stop a grab from matching so
+// each hotkey is grabbed with every combination of them.
+const IGNORED_MASKS: [c_uint; 4] = [
+    0,
+    xlib::LockMask,
+    xlib::Mod2Mask,
+    xlib::LockMask | xlib::Mod2Mask,
+];
+
+const MODIFIER_MASK: c_uint =
+    xlib::ShiftMask | xlib::ControlMask | xlib::Mod1Mask | xlib::Mod4Mask;
+
+// Alt and Super are assumed to be on Mod1 and Mod4 which is the case for
+// practically every keyboard layout.
+fn modifier_mask(hotkey: &Hotkey) -> c_uint {
+    let mut mask = 0;
+    if hotkey.modifiers.shift {
+        mask |= xlib::ShiftMask;
+    }
+    if hotkey.modifiers.control {
+        mask |= xlib::ControlMask;
+    }
+    if hotkey.modifiers.alt {
+        mask |= xlib::Mod1Mask;
+    }
+    if hotkey.modifiers.meta {
+        mask |= xlib::Mod4Mask;
+    }
+    mask
+}
+
+fn key_code(hotkey: &Hotkey) -> c_int {
+    linux_common::to_key_code(hotkey.key) as c_int + 8
+}
+
+// Each hotkey is grabbed with every combination of the ignored modifiers.
+fn grab_modifiers(hotkey: &Hotkey) -> Vec<ffi::XIGrabModifiers> {
+    IGNORED_MASKS
+        .iter()
+        .map(|ignored| ffi::XIGrabModifiers {
+            modifiers: (modifier_mask(hotkey) | ignored) as c_int,
+            status: 0,
+        })
+        .collect()
+}
+
+/// Grabs hotkeys on the root window so that pressing them is reported to us
+/// instead of the focused window.
+///
+/// The keys are grabbed with XInput 2 so that the device of each press is
+/// known. The index of the hotkey is sent whenever one is pressed on a real
+/// device. Presses generated with XTest are ignored so that the actions of the
+/// hotkeys can't trigger other hotkeys. The keys are ungrabbed when this is
+/// dropped.
+pub struct GrabReader {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl GrabReader {
+    /// Grab the hotkeys on the display chosen by the builder.
+    ///
+    /// An error is returned if any of the hotkeys is already grabbed by
+    /// another client.
+    pub fn open(
+        builder: &ContextBuilder,
+        hotkeys: &[Hotkey],
+        sender: Sender<usize>,
+    ) -> Result<Self, Error> {
+        unsafe {
+            let display = open_display(builder)?;
+
+            let screen = match builder.screen {
+                Some(screen) if screen < 0 || screen >= ffi::XScreenCount(display) => {
+                    ffi::XCloseDisplay(display);
+                    return Err(Error::Platform(PlatformError::InvalidScreen));
+                }
+                Some(screen) => screen,
+                None => ffi::XDefaultScreen(display),
+            };
+            let opcode = match xinput::query_version(display) {
+                Ok(opcode) => opcode,
+                Err(e) => {
+                    ffi::XCloseDisplay(display);
+                    return Err(e);
+                }
+            };
+            let xdisplay = display as *mut xlib::Display;
+            let root = xlib::XRootWindow(xdisplay, screen);
+
+            let grabbed = trap_display_errors(display, || {
+                let mut key_mask = xinput::event_mask(&[ffi::XI_KeyPress, ffi::XI_KeyRelease]);
+                let mut failed = 0;
+                for hotkey in hotkeys {
+                    let mut modifiers = grab_modifiers(hotkey);
+                    let mut mask = ffi::XIEventMask {
+                        deviceid: ffi::XIAllMasterDevices,
+                        mask_len: key_mask.len() as c_int,
+                        mask: key_mask.as_mut_ptr(),
+                    };
+                    // Returns the number of modifier combinations that
+                    // couldn't be grabbed.
+                    failed += ffi::XIGrabKeycode(
+                        display,
+                        ffi::XIAllMasterDevices,
+                        key_code(hotkey),
+                        root,
+                        ffi::XIGrabModeAsync,
+                        ffi::XIGrabModeAsync,
+                        ffi::False,
+                        &mut mask,
+                        modifiers.len() as c_int,
+                        modifiers.as_mut_ptr(),
+                    );
+                }
+
+                // The XTEST devices change along with the device hierarchy.
+                let mut hierarchy_mask = xinput::event_mask(&[ffi::XI_HierarchyChanged]);
+                let mut mask = ffi::XIEventMask {
+                    deviceid: ffi::XIAllDevices,
+                    mask_len: hierarchy_mask.len() as c_int,
+                    mask: hierarchy_mask.as_mut_ptr(),
+                };
+                ffi::XISelectEvents(display, root, &mut mask, 1);
+                failed
+            });
+            if !matches!(grabbed, Ok(0)) {
+                ungrab(display, hotkeys, root);
+                ffi::XCloseDisplay(display);
+                return Err(Error::Platform(PlatformError::XGrabKey));
+            }
+            xlib::XFlush(xdisplay);
+
+            let grabs = hotkeys
+                .iter()
+                .map(|hotkey| (key_code(hotkey), modifier_mask(hotkey)))
+                .collect::<Vec<_>>();
+            let hotkeys = hotkeys.to_vec();
+            let stop = Arc::new(AtomicBool::new(false));
+            let thread_stop = stop.clone();
+            let display = display as usize;
+            let thread = thread::spawn(move || {
+                // Errors are trapped for the lifetime of the thread so that an
+                // error on this connection can't exit the process.
+                let _ = trap_display_errors(display as *mut ffi::Display, || {
+                    let xdisplay = display as *mut xlib::Display;
+                    xinput::read_events(xdisplay, opcode, &thread_stop, |xtest, evtype, data| {
+                        read_grab(&grabs, &sender, xtest, evtype, data)
+                    });
+                    ungrab(display as *mut ffi::Display, &hotkeys, root);
+                });
+                ffi::XCloseDisplay(display as *mut ffi::Display);
+            });
+
+            Ok(Self {
+                stop,
+                thread: Some(thread),
+            })
+        }
+    }
+}
+
+impl Drop for GrabReader {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+unsafe fn ungrab(display: *mut ffi::Display, hotkeys: &[Hotkey], root: xlib::Window) {
+    for hotkey in hotkeys {
+        let mut modifiers = grab_modifiers(hotkey);
+        ffi::XIUngrabKeycode(
+            display,
+            ffi::XIAllMasterDevices,
+            key_code(hotkey),
+            root,
+            modifiers.len() as c_int,
+            modifiers.as_mut_ptr(),
+        );
+    }
+}
+
+// Returns false if the receiver has been dropped.
+unsafe fn read_grab(
+    grabs: &[(c_int, c_uint)],
+    sender: &Sender<usize>,
+    xtest: &XTestDevices,
+    evtype: c_int,
+    data: *const c_void,
+) -> bool {
+    if evtype != ffi::XI_KeyPress {
+        return true;
+    }
+    let event = &*(data as *const ffi::XIDeviceEvent);
+    // Holding down a hotkey only triggers it once.
+    if event.flags & ffi::XIKeyRepeat != 0 || xtest.contains(event.sourceid) {
+        return true;
+    }
+    let state = event.mods.effective as c_uint & MODIFIER_MASK;
+    match grabs.iter().position(|g| *g == (event.detail, state)) {
+        Some(index) => sender.send(index).is_ok(),
+        None => true,
+    }
+}
@@ -0,0 +1,66 @@
+use super::{Context, Error, PlatformError};
+use crate::{linux_common, Enum, Key, Modifiers};
+use std::os::raw::{c_char, c_int};
+use x11::xlib;
+
+// Check whether a key is down in the bit vector returned by XQueryKeymap.
+fn is_down(keys: &[c_char; 32], key: Key) -> bool {
+    let keycode = linux_common::to_key_code(key) as usize + 8;
+    keys[keycode / 8] as u8 & (1 << (keycode % 8)) != 0
+}
+
+impl Context {
+    fn query_keymap(&self) -> [c_char; 32] {
+        let mut keys = [0; 32];
+        unsafe {
+            xlib::XQueryKeymap(self.display as *mut xlib::Display, keys.as_mut_ptr());
+        }
+        keys
+    }
+
+    fn named_indicator(&self, name: &[u8]) -> Result<bool, Error> {
+        unsafe {
+            let display = self.display as *mut xlib::Display;
+            let atom = xlib::XInternAtom(display, name.as_ptr() as *const c_char, xlib::True);
+            if atom == 0 {
+                return Err(Error::Platform(PlatformError::XkbGetIndicatorState));
+            }
+            let mut state: c_int = 0;
+            if xlib::XkbGetNamedIndicator(
+                display,
+                atom,
+                std::ptr::null_mut(),
+                &mut state,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            ) == xlib::False
+            {
+                return Err(Error::Platform(PlatformError::XkbGetIndicatorState));
+            }
+            Ok(state != 0)
+        }
+    }
+}
+
+impl crate::KeyStateContext for Context {
+    fn is_key_pressed(&self, key: Key) -> Result<bool, Error> {
+        Ok(is_down(&self.query_keymap(), key))
+    }
+
+    fn modifier_state(&self) -> Result<Modifiers, Error> {
+        let keys = self.query_keymap();
+        let mut modifiers = Modifiers::default();
+        for key in Key::iter().filter(|key| is_down(&keys, *key)) {
+            modifiers.update(key, true);
+        }
+        Ok(modifiers)
+    }
+
+    fn caps_lock(&self) -> Result<bool, Error> {
+        self.named_indicator(b"Caps Lock\0")
+    }
+
+    fn num_lock(&self) -> Result<bool, Error> {
+        self.named_indicator(b"Num Lock\0")
+    }
+}
@@ -2,19 +2,18 @@ use super::{
     ffi::{self, XKeysymToKeycode},
     Context, Error, KeyInfo, PlatformError,
 };
-use crate::{linux_common, Key};
-use std::{os::raw::c_uint};
+use crate::{linux_common, Key, KeyStateContext};
+use std::{os::raw::c_uint, thread, time::Duration};
 
-fn key_event(ctx: &Context, key: Key, down: bool) -> Result<(), Error> {
-    unsafe {
+fn key_event(ctx: &mut Context, key: Key, down: bool) -> Result<(), Error> {
+    ctx.trap_errors(|ctx| unsafe {
         let key_code = (linux_common::to_key_code(key) + 8) as c_uint;
         let press = if down { ffi::True } else { ffi::False };
         if ffi::XTestFakeKeyEvent(ctx.display, key_code, press, ffi::CurrentTime) == 0 {
             return Err(Error::Platform(PlatformError::XTestFakeKeyEvent));
         }
-        ffi::XSync(ctx.display, ffi::False);
         Ok(())
-    }
+    })
 }
 
 impl crate::KeyboardContext for Context {
@@ -25,6 +24,14 @@ impl crate::KeyboardContext for Context {
     fn key_up(&mut self, key: Key) -> Result<(), Error> {
         key_event(self, key, false)
     }
+
+    fn begin_string(&mut self) -> Result<bool, Error> {
+        if self.normalize_key_state {
+            self.normalize_state()
+        } else {
+            Ok(false)
+        }
+    }
 }
 
 // The implementation of UnicodeKeyboardContext is adapted from here:
@@ -35,17 +42,7 @@ impl crate::KeyboardContext for Context {
 // delay only at the point where the layout changes doesn't work.
 // const KEY_DELAY: Duration = Duration::from_millis(25);
 
-fn info_from_char(ctx: &mut Context, group: u8, ch: char) -> Option<KeyInfo> {
-    let key_map: &std::collections::HashMap<char, KeyInfo> = ctx.key_map_vec.get(group as usize)?;
-    if let Some(info) = key_map.get(&ch) {
-        return Some(*info);
-    }
-
-    if ctx.last_group != group {
-        ctx.recover_remapped_keycodes();
-        ctx.last_group = group;
-    }
-
+fn char_keysym(ch: char) -> Option<ffi::KeySym> {
     let keysym = if ch as u32 >= 0x100 {
         ch as ffi::KeySym + 0x01000000
     } else {
@@ -61,6 +58,31 @@ fn info_from_char(ctx: &mut Context, group: u8, ch: char) -> Option<KeyInfo> {
         }
     }
 
+    Some(keysym)
+}
+
+// Check whether a character can be typed without actually remapping anything.
+fn is_typable(ctx: &Context, group: u8, ch: char) -> bool {
+    let on_layout = match ctx.key_map_vec.get(group as usize) {
+        Some(key_map) => key_map.contains_key(&ch),
+        None => false,
+    };
+    on_layout || char_keysym(ch).is_some()
+}
+
+pub(super) fn info_from_char(ctx: &mut Context, group: u8, ch: char) -> Option<KeyInfo> {
+    let key_map: &std::collections::HashMap<char, KeyInfo> = ctx.key_map_vec.get(group as usize)?;
+    if let Some(info) = key_map.get(&ch) {
+        return Some(*info);
+    }
+
+    if ctx.last_group != group {
+        ctx.recover_remapped_keycodes();
+        ctx.last_group = group;
+    }
+
+    let keysym = char_keysym(ch)?;
+
     if let Some(keycode) = ctx.get_remapped_keycode(keysym) {
         Some(KeyInfo {
             keysym,
@@ -130,7 +152,6 @@ unsafe fn modifier_event(ctx: &Context, modifiers: u8, press: ffi::Bool) -> Resu
                 {
                     return Err(Error::Platform(PlatformError::XTestFakeKeyEvent));
                 }
-                ffi::XSync(ctx.display, ffi::False);
                 break;
             }
         }
@@ -211,18 +232,17 @@ unsafe fn key_with_mods_event(ctx: &Context, info: &KeyInfo, down: bool) -> Resu
         modifier_event(ctx, info.modifiers, ffi::False)?;
     }
 
-    ffi::XFlush(ctx.display);
-    ffi::XSync(ctx.display, ffi::False);
-
     Ok(())
 }
 
 fn char_event(ctx: &mut Context, ch: char, down: bool, up: bool) -> Result<(), Error> {
-    let group = unsafe {
-        let mut state = std::mem::zeroed();
-        ffi::XkbGetState(ctx.display, ffi::XkbUseCoreKbd, &mut state);
-        state.group
-    };
+    // Remapping a keycode can fail so errors need to be trapped.
+    ctx.trap_errors(|ctx| char_event_trapped(ctx, ch, down, up))
+}
+
+fn char_event_trapped(ctx: &mut Context, ch: char, down: bool, up: bool) -> Result<(), Error> {
+    ctx.handle_mapping_events()?;
+    let group = ctx.active_group();
     let info = match info_from_char(ctx, group, ch) {
         Some(info) => info,
         None => return Err(Error::UnsupportedUnicode(ch)),
@@ -250,6 +270,25 @@ fn char_event(ctx: &mut Context, ch: char, down: bool, up: bool) -> Result<(), E
     Ok(())
 }
 
+fn type_string(ctx: &mut Context, s: &str) -> Result<(), Error> {
+    ctx.handle_mapping_events()?;
+    let group = ctx.active_group();
+    for ch in s.chars() {
+        if !is_typable(ctx, group, ch) {
+            return Err(Error::UnsupportedUnicode(ch));
+        }
+    }
+    for (index, ch) in s.chars().enumerate() {
+        // The events in a batch aren't sent until the end so there is no
+        // point delaying them.
+        if index != 0 && ctx.key_delay != Duration::ZERO && !ctx.batching {
+            thread::sleep(ctx.key_delay);
+        }
+        char_event(ctx, ch, true, true)?;
+    }
+    Ok(())
+}
+
 impl crate::UnicodeKeyboardContext for Context {
     fn unicode_char_down(&mut self, ch: char) -> Result<(), Error> {
         char_event(self, ch, true, false)
@@ -263,15 +302,11 @@ impl crate::UnicodeKeyboardContext for Context {
         char_event(self, ch, true, true)
     }
 
-    fn unicode_string(&mut self, _s: &str) -> Result<(), Error> {
-        // for ch in s.chars() {
-        //     if info_from_char(self, ch).is_none() {
-        //         return Err(Error::UnsupportedUnicode(ch));
-        //     }
-        // }
-        // for ch in s.chars() {
-        //     self.unicode_char(ch)?;
-        // }
-        Ok(())
+    fn unicode_string(&mut self, s: &str) -> Result<(), Error> {
+        if self.normalize_key_state {
+            self.with_normalized_state(|ctx| type_string(ctx, s))
+        } else {
+            type_string(self, s)
+        }
     }
 }
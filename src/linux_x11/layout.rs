@@ -0,0 +1,94 @@
+use super::{ffi, Context, Error, PlatformError};
+use crate::CharLayout;
+use std::{
+    ffi::CStr,
+    os::raw::{c_uint, c_ulong},
+};
+use x11::xlib;
+
+const XKB_GROUP_NAMES_MASK: c_uint = 1 << 12;
+const XKB_ALL_CTRLS_MASK: c_ulong = 0xF8001FFF;
+
+unsafe fn atom_name(display: *mut xlib::Display, atom: xlib::Atom) -> String {
+    if atom == 0 {
+        return String::new();
+    }
+    let name = xlib::XGetAtomName(display, atom);
+    if name.is_null() {
+        return String::new();
+    }
+    let string = CStr::from_ptr(name).to_string_lossy().into_owned();
+    xlib::XFree(name as *mut _);
+    string
+}
+
+impl Context {
+    pub(super) fn active_group(&self) -> u8 {
+        unsafe {
+            let mut state = std::mem::zeroed();
+            ffi::XkbGetState(self.display, ffi::XkbUseCoreKbd, &mut state);
+            state.group
+        }
+    }
+}
+
+impl crate::LayoutContext for Context {
+    fn layout_names(&self) -> Result<Vec<String>, Error> {
+        unsafe {
+            let display = self.display as *mut xlib::Display;
+            let keyboard = xlib::XkbAllocKeyboard();
+            if keyboard.is_null() {
+                return Err(Error::Platform(PlatformError::XkbGetNames));
+            }
+            (*keyboard).dpy = display;
+
+            // Both of these return a Status where zero is success.
+            if xlib::XkbGetControls(display, XKB_ALL_CTRLS_MASK, keyboard) != 0
+                || xlib::XkbGetNames(display, XKB_GROUP_NAMES_MASK, keyboard) != 0
+            {
+                xlib::XkbFreeKeyboard(keyboard, 0, xlib::True);
+                return Err(Error::Platform(PlatformError::XkbGetNames));
+            }
+
+            let groups = &(*(*keyboard).names).groups;
+            let count = ((*(*keyboard).ctrls).num_groups as usize).min(groups.len());
+            let names = groups[..count]
+                .iter()
+                .map(|atom| atom_name(display, *atom))
+                .collect();
+
+            xlib::XkbFreeKeyboard(keyboard, 0, xlib::True);
+            Ok(names)
+        }
+    }
+
+    fn active_layout(&self) -> Result<u8, Error> {
+        Ok(self.active_group())
+    }
+
+    fn set_active_layout(&mut self, layout: u8) -> Result<(), Error> {
+        if layout as usize >= self.layout_names()?.len() {
+            return Err(Error::Info(format!("Layout {} is not configured", layout)));
+        }
+        self.trap_errors(|ctx| unsafe {
+            if ffi::XkbLockGroup(ctx.display, ffi::XkbUseCoreKbd, layout as c_uint) == ffi::False {
+                return Err(Error::Platform(PlatformError::XkbLockGroup));
+            }
+            Ok(())
+        })
+    }
+
+    fn char_layout(&self, ch: char) -> Result<Option<CharLayout>, Error> {
+        // Characters are only ever typed from the active group so the other
+        // groups aren't searched.
+        let active = self.active_group();
+        let info = self
+            .key_map_vec
+            .get(active as usize)
+            .and_then(|map| map.get(&ch));
+        Ok(info.map(|info| CharLayout {
+            layout: active,
+            modifiers: info.modifiers,
+        }))
+    }
+}
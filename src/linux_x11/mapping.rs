@@ -0,0 +1,151 @@
+use super::{create_key_map, ffi, find_unused_key_code, Context, Error, PlatformError};
+use std::os::raw::c_int;
+use x11::xlib;
+
+// X11/extensions/XKB.h
+const XKB_MAJOR_VERSION: c_int = 1;
+const XKB_MINOR_VERSION: c_int = 0;
+
+// The cached key maps are built from the keyboard mapping. The mapping can be
+// changed at any time by other clients (setxkbmap, xmodmap, switching layouts
+// in the desktop environment, etc). The server tells us about these changes
+// with MappingNotify and XKB events. Our own remapping of unused keycodes
+// generates these events too but those don't invalidate the cache.
+
+/// Select the XKB events that indicate that the keyboard mapping has changed.
+///
+/// Returns the XKB event base or `None` if XKB is unavailable.
+pub(super) unsafe fn select_mapping_events(display: *mut ffi::Display) -> Option<c_int> {
+    let display = display as *mut xlib::Display;
+    let mut opcode = 0;
+    let mut event_base = 0;
+    let mut error_base = 0;
+    let mut major_version = XKB_MAJOR_VERSION;
+    let mut minor_version = XKB_MINOR_VERSION;
+    if xlib::XkbQueryExtension(
+        display,
+        &mut opcode,
+        &mut event_base,
+        &mut error_base,
+        &mut major_version,
+        &mut minor_version,
+    ) == xlib::False
+    {
+        return None;
+    }
+
+    let mask = xlib::XkbNewKeyboardNotifyMask | xlib::XkbMapNotifyMask;
+    if xlib::XkbSelectEvents(display, ffi::XkbUseCoreKbd, mask, mask) == xlib::False {
+        return None;
+    }
+
+    Some(event_base)
+}
+
+// The predicate for XCheckIfEvent. The argument is the XKB event base.
+unsafe extern "C" fn is_mapping_event(
+    _display: *mut xlib::Display,
+    event: *mut xlib::XEvent,
+    arg: xlib::XPointer,
+) -> xlib::Bool {
+    let type_ = (*event).type_;
+    (type_ == xlib::MappingNotify || type_ == *(arg as *const c_int)) as xlib::Bool
+}
+
+impl Context {
+    pub(super) unsafe fn load_keymap(&mut self) -> Result<(), Error> {
+        // Get the full range of keycodes used by X11. This is probably
+        // always 8-255 on Linux but we should make sure.
+        let mut min_keycode = 0;
+        let mut max_keycode = 0;
+        ffi::XDisplayKeycodes(self.display, &mut min_keycode, &mut max_keycode);
+        let min_keycode = min_keycode as ffi::KeyCode;
+        let max_keycode = max_keycode as ffi::KeyCode;
+
+        let unused_keycodes = find_unused_key_code(self.display, min_keycode, max_keycode)?;
+        let key_map_vec = create_key_map(self.display, min_keycode, max_keycode)?;
+
+        let modifier_map = ffi::XGetModifierMapping(self.display);
+        if modifier_map.is_null() {
+            return Err(Error::Platform(PlatformError::XGetModifierMapping));
+        }
+        if !self.modifier_map.is_null() {
+            ffi::XFreeModifiermap(self.modifier_map);
+        }
+
+        self.key_map_vec = key_map_vec;
+        self.unused_keycodes = unused_keycodes;
+        self.unused_index = 0;
+        self.modifier_map = modifier_map;
+        Ok(())
+    }
+
+    /// Rebuild the cached keyboard mapping.
+    ///
+    /// The mapping is rebuilt automatically when the keyboard mapping changes
+    /// (e.g. `setxkbmap` was run) so calling this shouldn't be necessary.
+    /// Keycodes that were remapped to type characters that aren't on the
+    /// layout are restored first.
+    pub fn refresh_keymap(&mut self) -> Result<(), Error> {
+        self.recover_remapped_keycodes();
+        unsafe { self.load_keymap() }
+    }
+
+    /// Process pending mapping events and rebuild the cached keyboard mapping
+    /// if it's stale.
+    ///
+    /// Only the mapping events are removed from the queue. Any other events
+    /// are left for whoever selected them.
+    pub(super) fn handle_mapping_events(&mut self) -> Result<(), Error> {
+        let display = self.display as *mut xlib::Display;
+        let mut stale = false;
+
+        unsafe {
+            let mut event_base = self.xkb_event_base;
+            let arg = &mut event_base as *mut c_int as xlib::XPointer;
+            loop {
+                let mut event: xlib::XEvent = std::mem::zeroed();
+                if xlib::XCheckIfEvent(display, &mut event, Some(is_mapping_event), arg)
+                    == xlib::False
+                {
+                    break;
+                }
+
+                if event.type_ == xlib::MappingNotify {
+                    xlib::XRefreshKeyboardMapping(&mut event.mapping);
+                    match event.mapping.request {
+                        xlib::MappingKeyboard => {
+                            stale |= !self.is_own_remapping(
+                                event.mapping.first_keycode,
+                                event.mapping.count,
+                            );
+                        }
+                        xlib::MappingModifier => stale = true,
+                        _ => {}
+                    }
+                } else if event.type_ == self.xkb_event_base {
+                    let any = &*(&event as *const xlib::XEvent as *const xlib::XkbAnyEvent);
+                    if any.xkb_type == xlib::XkbNewKeyboardNotify {
+                        stale = true;
+                    } else if any.xkb_type == xlib::XkbMapNotify {
+                        let map =
+                            &*(&event as *const xlib::XEvent as *const xlib::XkbMapNotifyEvent);
+                        stale |=
+                            !self.is_own_remapping(map.first_key_sym as c_int, map.num_key_syms);
+                    }
+                }
+            }
+        }
+
+        if stale {
+            self.refresh_keymap()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_own_remapping(&self, first_keycode: c_int, count: c_int) -> bool {
+        (first_keycode..first_keycode + count)
+            .all(|keycode| self.unused_keycodes.contains(&(keycode as ffi::KeyCode)))
+    }
+}
@@ -1,8 +1,20 @@
+mod capture;
+mod clipboard;
 mod error;
 mod ffi;
+mod grab;
+mod key_state;
 mod keyboard;
+mod layout;
+mod mapping;
 mod mouse;
+mod raw;
+mod record;
 mod screen;
+mod targeted;
+mod trap;
+mod window;
+mod xinput;
 
 // The implementation of Context::new is adapted from here:
 // https://github.com/jordansissel/xdotool/blob/master/xdo.c
@@ -10,8 +22,18 @@ mod screen;
 use crate::linux_x11::ffi::NoSymbol;
 
 use self::ffi::{Display, XKeycodeToKeysym};
+use crate::{Capabilities, ContextBuilder};
 use error::PlatformError;
-use std::{collections::HashMap, ffi::c_int};
+pub(crate) use grab::GrabReader;
+pub(crate) use raw::RawReader;
+pub(crate) use record::RecordReader;
+use std::{
+    collections::HashMap,
+    ffi::{c_int, CString},
+    sync::atomic::AtomicBool,
+    time::Duration,
+};
+pub use targeted::TargetedContext;
 type Error = crate::GenericError<PlatformError>;
 
 #[derive(Copy, Clone, Debug)]
@@ -36,6 +58,17 @@ pub struct Context {
     remap_keysym: HashMap<ffi::KeySym, ffi::KeyCode>,
     modifier_map: *const ffi::XModifierKeymap,
     last_group: u8,
+    xkb_event_base: c_int,
+    connection_lost: Box<AtomicBool>,
+    key_delay: Duration,
+    normalize_key_state: bool,
+    // Cleared if attaching a shared memory segment fails (e.g. the server
+    // is on another machine).
+    use_shm: AtomicBool,
+    // Started the first time a selection is set.
+    clipboard: Option<clipboard::ClipboardOwner>,
+    // Set while the events of a batch are being queued.
+    batching: bool,
 }
 
 unsafe impl Sync for Context {}
@@ -127,12 +160,17 @@ unsafe fn create_key_map(
     const XKB_ALL_NAMES_MASK: c_uint = 0x3fff;
     const XKB_ALL_CTRLS_MASK: std::os::raw::c_ulong = 0xF8001FFF;
 
-    let ndisplay = xlib::XOpenDisplay(std::ptr::null());
+    let xdisplay = display as *mut xlib::Display;
     let keyboard: xlib::XkbDescPtr = xlib::XkbAllocKeyboard();
-    (*keyboard).dpy = ndisplay;
-    xlib::XkbGetNames(ndisplay, XKB_ALL_NAMES_MASK, keyboard);
-    xlib::XkbGetControls(ndisplay, XKB_ALL_CTRLS_MASK, keyboard);
+    if keyboard.is_null() {
+        ffi::XkbFreeClientMap(desc, 0, ffi::True);
+        return Err(Error::Platform(PlatformError::XkbGetNames));
+    }
+    (*keyboard).dpy = xdisplay;
+    xlib::XkbGetNames(xdisplay, XKB_ALL_NAMES_MASK, keyboard);
+    xlib::XkbGetControls(xdisplay, XKB_ALL_CTRLS_MASK, keyboard);
     let num_groups: u8 = (*(*keyboard).names).groups.len() as u8;
+    xlib::XkbFreeKeyboard(keyboard, 0, xlib::True);
     // to-do: Ensure the comment out the following line is ok.
     // num_groups = num_groups - 1;
     ////////////////////////////////////////////////////////////////
@@ -198,60 +236,145 @@ unsafe fn create_key_map(
     Ok(key_map_vec)
 }
 
+/// Open a connection to the display chosen by the builder.
+pub(super) unsafe fn open_display(builder: &ContextBuilder) -> Result<*mut ffi::Display, Error> {
+    let display_name = match &builder.display {
+        Some(name) => match CString::new(name.as_str()) {
+            Ok(name) => Some(name),
+            Err(_) => return Err(Error::Platform(PlatformError::XOpenDisplay)),
+        },
+        None => None,
+    };
+
+    let display = ffi::XOpenDisplay(match &display_name {
+        Some(name) => name.as_ptr() as *const u8,
+        None => std::ptr::null(),
+    });
+    if display.is_null() {
+        Err(Error::Platform(PlatformError::XOpenDisplay))
+    } else {
+        Ok(display)
+    }
+}
+
 impl Context {
     pub fn new() -> Result<Self, Error> {
+        Self::from_builder(&Default::default())
+    }
+
+    /// Generate a sequence of events with a single round trip to the server.
+    ///
+    /// Normally, the context waits for the server to process each event so
+    /// that errors can be reported by the method that caused them. Within a
+    /// batch, the events are queued and only sent once the function returns.
+    /// This is much faster for long sequences of events, such as streaming
+    /// mouse movements. The first error caused by the batch is returned.
+    ///
+    /// The [key delay](crate::ContextBuilder::key_delay) is ignored within a
+    /// batch. Characters that aren't on the keyboard layout are typed by
+    /// remapping a keycode and some applications may not notice that the
+    /// keycode was remapped if the characters are typed too quickly.
+    ///
+    /// ```no_run
+    /// use tfc::{Context, traits::*};
+    ///
+    /// let mut ctx = Context::new().unwrap();
+    /// ctx.batch(|ctx| {
+    ///     for x in 0..100 {
+    ///         ctx.mouse_move_abs(x * 4, 300)?;
+    ///     }
+    ///     ctx.unicode_string("Hello, world!")
+    /// }).unwrap();
+    /// ```
+    pub fn batch<T, F>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        if self.batching {
+            return f(self);
+        }
+        self.trap_errors(|ctx| {
+            ctx.batching = true;
+            let result = f(ctx);
+            ctx.batching = false;
+            result
+        })
+    }
+
+    /// Get the operations that this context supports.
+    ///
+    /// A context can only be created if the XTest extension is available so
+    /// the keyboard and mouse are always supported. Characters that aren't on
+    /// the keyboard layout can only be typed if there is an unused keycode to
+    /// remap.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            keyboard: true,
+            mouse: true,
+            unicode: true,
+            unicode_remapping: !self.unused_keycodes.is_empty(),
+            screen: true,
+            key_state: true,
+            layout: true,
+            screen_capture: true,
+            window: true,
+            clipboard: true,
+        }
+    }
+
+    pub(crate) fn from_builder(builder: &ContextBuilder) -> Result<Self, Error> {
         unsafe {
-            let display = ffi::XOpenDisplay(std::ptr::null());
-            if display.is_null() {
-                return Err(Error::Platform(PlatformError::XOpenDisplay));
-            }
+            let display = open_display(builder)?;
 
             if no_xtest(display) {
                 ffi::XCloseDisplay(display);
                 return Err(Error::Platform(PlatformError::XTestQueryExtension));
             }
 
-            // Get the full range of keycodes used by X11. This is probably
-            // always 8-255 on Linux but we should make sure.
-            let mut min_keycode = 0;
-            let mut max_keycode = 0;
-            ffi::XDisplayKeycodes(display, &mut min_keycode, &mut max_keycode);
-            let min_keycode = min_keycode as ffi::KeyCode;
-            let max_keycode = max_keycode as ffi::KeyCode;
-
-            let unused_keycodes = match find_unused_key_code(display, min_keycode, max_keycode) {
-                Ok(k) => k,
-                Err(e) => {
+            let screen_number = match builder.screen {
+                Some(screen) if screen < 0 || screen >= ffi::XScreenCount(display) => {
                     ffi::XCloseDisplay(display);
-                    return Err(e);
+                    return Err(Error::Platform(PlatformError::InvalidScreen));
                 }
+                Some(screen) => screen,
+                None => ffi::XDefaultScreen(display),
             };
 
-            let key_map_vec = match create_key_map(display, min_keycode, max_keycode) {
-                Ok(m) => m,
-                Err(e) => {
+            let xkb_event_base = match mapping::select_mapping_events(display) {
+                Some(base) => base,
+                None => {
                     ffi::XCloseDisplay(display);
-                    return Err(e);
+                    return Err(Error::Platform(PlatformError::XkbQueryExtension));
                 }
             };
 
-            let modifier_map = ffi::XGetModifierMapping(display);
-            if modifier_map.is_null() {
-                ffi::XCloseDisplay(display);
-                return Err(Error::Platform(PlatformError::XGetModifierMapping));
-            }
-
-            Ok(Self {
+            let mut ctx = Self {
                 display,
-                screen_number: ffi::XDefaultScreen(display),
+                screen_number,
                 scroll: Default::default(),
-                key_map_vec,
-                unused_keycodes,
+                key_map_vec: Vec::new(),
+                unused_keycodes: Vec::new(),
                 unused_index: Default::default(),
                 remap_keysym: Default::default(),
-                modifier_map,
+                modifier_map: std::ptr::null(),
                 last_group: 0,
-            })
+                xkb_event_base,
+                connection_lost: Box::new(AtomicBool::new(false)),
+                key_delay: builder.key_delay,
+                normalize_key_state: builder.normalize_key_state,
+                use_shm: AtomicBool::new(ffi::XShmQueryExtension(display) != ffi::False),
+                clipboard: None,
+                batching: false,
+            };
+
+            // Losing the connection shouldn't exit the process.
+            trap::set_connection_lost_flag(display, &ctx.connection_lost);
+
+            // The display is closed when the context is dropped so there's no
+            // need to clean up if this fails.
+            ctx.load_keymap()?;
+
+            Ok(ctx)
         }
     }
 
@@ -289,21 +412,19 @@ impl Context {
     }
 
     pub fn get_remapped_keycode(&self, keysym: ffi::KeySym) -> Option<ffi::KeyCode> {
-        if let Some(keycode) = self.remap_keysym.get(&keysym).copied() {
-            // FIXME: Detect switch input method.
-            if self.is_valid_remapping(keysym, keycode) {
-                Some(keycode)
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        // FIXME: Detect switch input method.
+        self.remap_keysym
+            .get(&keysym)
+            .copied()
+            .filter(|keycode| self.is_valid_remapping(keysym, *keycode))
     }
 
     pub fn recover_remapped_keycodes(&mut self) {
+        // If the keyboard mapping was replaced (by setxkbmap for example), the
+        // keycode may no longer be ours to restore.
         self.remap_keysym
             .iter()
+            .filter(|(keysym, keycode)| self.is_valid_remapping(**keysym, **keycode))
             .for_each(|(_, keycode)| change_keyboard_mapping(self.display, *keycode, NoSymbol));
         self.remap_keysym.clear();
         self.unused_index = 0;
@@ -322,7 +443,9 @@ impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
             self.recover_remapped_keycodes();
-            ffi::XFreeModifiermap(self.modifier_map);
+            if !self.modifier_map.is_null() {
+                ffi::XFreeModifiermap(self.modifier_map);
+            }
             ffi::XCloseDisplay(self.display);
         }
     }
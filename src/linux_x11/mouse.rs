@@ -10,19 +10,18 @@ fn to_button(button: MouseButton) -> c_uint {
     }
 }
 
-fn button_event(ctx: &Context, button: c_uint, down: bool) -> Result<(), Error> {
+fn button_event(ctx: &mut Context, button: c_uint, down: bool) -> Result<(), Error> {
     let press = if down { ffi::True } else { ffi::False };
-    unsafe {
+    ctx.trap_errors(|ctx| unsafe {
         if ffi::XTestFakeButtonEvent(ctx.display, button, press, ffi::CurrentTime) == 0 {
             return Err(Error::Platform(PlatformError::XTestFakeButtonEvent));
         }
-        ffi::XSync(ctx.display, ffi::False);
-    }
-    Ok(())
+        Ok(())
+    })
 }
 
-fn repeat_button_event(ctx: &Context, count: i32, button: c_uint) -> Result<(), Error> {
-    unsafe {
+fn repeat_button_event(ctx: &mut Context, count: i32, button: c_uint) -> Result<(), Error> {
+    ctx.trap_errors(|ctx| unsafe {
         for _ in 0..count {
             if ffi::XTestFakeButtonEvent(ctx.display, button, ffi::True, ffi::CurrentTime) == 0 {
                 return Err(Error::Platform(PlatformError::XTestFakeButtonEvent));
@@ -31,18 +30,17 @@ fn repeat_button_event(ctx: &Context, count: i32, button: c_uint) -> Result<(),
                 return Err(Error::Platform(PlatformError::XTestFakeButtonEvent));
             }
         }
-        ffi::XSync(ctx.display, ffi::False);
-    }
-    Ok(())
+        Ok(())
+    })
 }
 
 impl crate::MouseContext for Context {
     fn mouse_move_rel(&mut self, dx: i32, dy: i32) -> Result<(), Error> {
-        unsafe {
+        self.trap_errors(|ctx| unsafe {
             // XTestFakeRelativeMotionEvent seems to only move the mouse
             // vertically. Very odd.
             if ffi::XWarpPointer(
-                self.display,
+                ctx.display,
                 ffi::None,
                 ffi::None,
                 0,
@@ -55,17 +53,16 @@ impl crate::MouseContext for Context {
             {
                 return Err(Error::Platform(PlatformError::XWarpPointer));
             }
-            ffi::XFlush(self.display);
-        }
-        Ok(())
+            Ok(())
+        })
     }
 
     fn mouse_move_abs(&mut self, x: i32, y: i32) -> Result<(), Error> {
-        unsafe {
+        self.trap_errors(|ctx| unsafe {
             // XTestFakeMotionEvent apparently ignores the screen number.
-            let window = ffi::XRootWindow(self.display, self.screen_number);
+            let window = ffi::XRootWindow(ctx.display, ctx.screen_number);
             if ffi::XWarpPointer(
-                self.display,
+                ctx.display,
                 ffi::None,
                 window,
                 0,
@@ -78,9 +75,8 @@ impl crate::MouseContext for Context {
             {
                 return Err(Error::Platform(PlatformError::XWarpPointer));
             }
-            ffi::XFlush(self.display);
-        }
-        Ok(())
+            Ok(())
+        })
     }
 
     fn mouse_scroll(&mut self, dx: i32, dy: i32) -> Result<(), Error> {
@@ -0,0 +1,142 @@
+use super::{
+    ffi, open_display,
+    trap::trap_display_errors,
+    xinput::{self, XTestDevices},
+    Error,
+};
+use crate::{linux_common, ContextBuilder, InputEvent, TimedInputEvent};
+use std::{
+    ffi::c_void,
+    os::raw::c_int,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use x11::xlib;
+
+/// Reads the key events of real devices from an X server using the raw events
+/// of XInput 2.
+///
+/// Raw events are reported no matter which window has focus and carry the
+/// device that they came from. The events generated with XTest are ignored.
+/// Reading stops when this is dropped.
+pub struct RawReader {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RawReader {
+    /// Start reading the key events on the display chosen by the builder.
+    pub fn open(builder: &ContextBuilder, sender: Sender<TimedInputEvent>) -> Result<Self, Error> {
+        unsafe {
+            let display = open_display(builder)?;
+            let opcode = match xinput::query_version(display) {
+                Ok(opcode) => opcode,
+                Err(e) => {
+                    ffi::XCloseDisplay(display);
+                    return Err(e);
+                }
+            };
+
+            // Raw events can only be selected on the root window.
+            let xdisplay = display as *mut xlib::Display;
+            let root = xlib::XDefaultRootWindow(xdisplay);
+            let mut key_mask = xinput::event_mask(&[ffi::XI_RawKeyPress, ffi::XI_RawKeyRelease]);
+            // The XTEST devices change along with the device hierarchy.
+            let mut hierarchy_mask = xinput::event_mask(&[ffi::XI_HierarchyChanged]);
+            let mut masks = [
+                ffi::XIEventMask {
+                    deviceid: ffi::XIAllMasterDevices,
+                    mask_len: key_mask.len() as c_int,
+                    mask: key_mask.as_mut_ptr(),
+                },
+                ffi::XIEventMask {
+                    deviceid: ffi::XIAllDevices,
+                    mask_len: hierarchy_mask.len() as c_int,
+                    mask: hierarchy_mask.as_mut_ptr(),
+                },
+            ];
+            let selected = trap_display_errors(display, || {
+                ffi::XISelectEvents(display, root, masks.as_mut_ptr(), masks.len() as c_int);
+            });
+            if let Err(e) = selected {
+                ffi::XCloseDisplay(display);
+                return Err(Error::Platform(e));
+            }
+
+            let stop = Arc::new(AtomicBool::new(false));
+            let thread_stop = stop.clone();
+            let display = display as usize;
+            let thread = thread::spawn(move || {
+                // The server timestamp of the first event and the time it was
+                // received. The times of the other events are derived from
+                // their timestamps relative to this one.
+                let mut first = None;
+                // Errors are trapped for the lifetime of the thread so that an
+                // error on this connection can't exit the process.
+                let _ = trap_display_errors(display as *mut ffi::Display, || {
+                    let xdisplay = display as *mut xlib::Display;
+                    xinput::read_events(xdisplay, opcode, &thread_stop, |xtest, evtype, data| {
+                        read_raw(&sender, &mut first, xtest, evtype, data)
+                    });
+                });
+                ffi::XCloseDisplay(display as *mut ffi::Display);
+            });
+
+            Ok(Self {
+                stop,
+                thread: Some(thread),
+            })
+        }
+    }
+}
+
+impl Drop for RawReader {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// Returns false if the receiver has been dropped.
+unsafe fn read_raw(
+    sender: &Sender<TimedInputEvent>,
+    first: &mut Option<(u32, Instant)>,
+    xtest: &XTestDevices,
+    evtype: c_int,
+    data: *const c_void,
+) -> bool {
+    if evtype != ffi::XI_RawKeyPress && evtype != ffi::XI_RawKeyRelease {
+        return true;
+    }
+    let raw = &*(data as *const ffi::XIRawEvent);
+    if raw.flags & ffi::XIKeyRepeat != 0 || xtest.contains(raw.sourceid) || raw.detail < 8 {
+        return true;
+    }
+    let key = match linux_common::from_key_code(raw.detail as u16 - 8) {
+        Some(key) => key,
+        None => return true,
+    };
+    let event = if evtype == ffi::XI_RawKeyPress {
+        InputEvent::KeyDown(key)
+    } else {
+        InputEvent::KeyUp(key)
+    };
+
+    // The timestamp is in milliseconds and wraps around.
+    let timestamp = raw.time as u32;
+    let (first_timestamp, first_time) = *first.get_or_insert_with(|| (timestamp, Instant::now()));
+    let elapsed = timestamp.wrapping_sub(first_timestamp) as u64;
+    let event = TimedInputEvent {
+        event,
+        ch: None,
+        time: first_time + Duration::from_millis(elapsed),
+    };
+    sender.send(event).is_ok()
+}
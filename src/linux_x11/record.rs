@@ -0,0 +1,253 @@
+use super::{ffi, open_display, trap::trap_display_errors, Error, PlatformError};
+use crate::{linux_common, ContextBuilder, InputEvent, MouseButton, TimedInputEvent};
+use std::{
+    os::raw::{c_char, c_int, c_uint},
+    sync::mpsc::Sender,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+// Core event types from X.h
+const KEY_PRESS: u8 = 2;
+const KEY_RELEASE: u8 = 3;
+const BUTTON_PRESS: u8 = 4;
+const BUTTON_RELEASE: u8 = 5;
+const MOTION_NOTIFY: u8 = 6;
+
+const SHIFT_MASK: u16 = 1 << 0;
+const LOCK_MASK: u16 = 1 << 1;
+
+// The number of scroll units in one notch of the wheel.
+const SCROLL_NOTCH: i32 = 120;
+
+/// Reads input events from an X server using the RECORD extension.
+///
+/// The events are received on a separate thread using a separate connection.
+/// Recording stops when this is dropped.
+pub struct RecordReader {
+    control: *mut ffi::Display,
+    context: ffi::XRecordContext,
+    thread: Option<JoinHandle<()>>,
+}
+
+unsafe impl Send for RecordReader {}
+
+struct CallbackState {
+    // Used for looking up keysyms while the data connection is blocked.
+    lookup: *mut ffi::Display,
+    sender: Sender<TimedInputEvent>,
+    // Set if the receiver has been dropped.
+    disconnected: bool,
+    // The server timestamp of the first event and the time it was received.
+    // The data is delivered in bursts so the times of the other events are
+    // derived from their timestamps relative to this one.
+    first: Option<(u32, Instant)>,
+}
+
+impl RecordReader {
+    /// Start recording the input events on the display chosen by the builder.
+    pub fn open(builder: &ContextBuilder, sender: Sender<TimedInputEvent>) -> Result<Self, Error> {
+        unsafe {
+            let control = open_display(builder)?;
+            let close = |displays: &[*mut ffi::Display]| {
+                for display in displays {
+                    ffi::XCloseDisplay(*display);
+                }
+            };
+
+            let mut major = 0;
+            let mut minor = 0;
+            if ffi::XRecordQueryVersion(control, &mut major, &mut minor) == ffi::False {
+                close(&[control]);
+                return Err(Error::Platform(PlatformError::XRecordQueryVersion));
+            }
+
+            let data = match open_display(builder) {
+                Ok(display) => display,
+                Err(e) => {
+                    close(&[control]);
+                    return Err(e);
+                }
+            };
+            let lookup = match open_display(builder) {
+                Ok(display) => display,
+                Err(e) => {
+                    close(&[control, data]);
+                    return Err(e);
+                }
+            };
+
+            let range = ffi::XRecordAllocRange();
+            if range.is_null() {
+                close(&[control, data, lookup]);
+                return Err(Error::Platform(PlatformError::XRecordCreateContext));
+            }
+            (*range).device_events.first = KEY_PRESS;
+            (*range).device_events.last = MOTION_NOTIFY;
+            let mut clients = ffi::XRecordAllClients;
+            let mut ranges = range;
+            let context = ffi::XRecordCreateContext(control, 0, &mut clients, 1, &mut ranges, 1);
+            ffi::XFree(range as *const ffi::KeySym);
+            if context == 0 {
+                close(&[control, data, lookup]);
+                return Err(Error::Platform(PlatformError::XRecordCreateContext));
+            }
+            // The context must be known to the server before the data
+            // connection can enable it.
+            ffi::XSync(control, ffi::False);
+
+            let data = data as usize;
+            let lookup = lookup as usize;
+            let thread = thread::spawn(move || {
+                let data = data as *mut ffi::Display;
+                let mut state = CallbackState {
+                    lookup: lookup as *mut ffi::Display,
+                    sender,
+                    disconnected: false,
+                    first: None,
+                };
+                // Errors are trapped for the lifetime of the thread so that an
+                // error on either connection can't exit the process.
+                let lookup = state.lookup;
+                let _ = trap_display_errors(lookup, || {
+                    trap_display_errors(data, || {
+                        ffi::XRecordEnableContext(
+                            data,
+                            context,
+                            Some(record_callback),
+                            &mut state as *mut CallbackState as *mut c_char,
+                        );
+                    })
+                });
+                ffi::XCloseDisplay(lookup);
+                ffi::XCloseDisplay(data);
+            });
+
+            Ok(Self {
+                control,
+                context,
+                thread: Some(thread),
+            })
+        }
+    }
+}
+
+impl Drop for RecordReader {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = trap_display_errors(self.control, || {
+                ffi::XRecordDisableContext(self.control, self.context);
+            });
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        unsafe {
+            ffi::XRecordFreeContext(self.control, self.context);
+            ffi::XCloseDisplay(self.control);
+        }
+    }
+}
+
+// Determine the character that a key press would produce. This only considers
+// the group and the Shift and Lock modifiers.
+unsafe fn key_char(display: *mut ffi::Display, keycode: u8, state: u16) -> Option<char> {
+    let group = ((state >> 13) & 3) as c_uint;
+    let to_char = |level: c_uint| {
+        let keysym = ffi::XkbKeycodeToKeysym(display, keycode, group, level);
+        if keysym == ffi::NoSymbol {
+            return None;
+        }
+        match std::char::from_u32(ffi::xkb_keysym_to_utf32(keysym as ffi::xkb_keysym_t)) {
+            Some('\0') | None => None,
+            Some('\r') => Some('\n'),
+            Some(ch) if ch.is_control() && ch != '\t' => None,
+            Some(ch) => Some(ch),
+        }
+    };
+
+    let base = to_char(0);
+    let mut shift = state & SHIFT_MASK != 0;
+    if state & LOCK_MASK != 0 && matches!(base, Some(ch) if ch.is_lowercase()) {
+        shift = !shift;
+    }
+    if shift {
+        to_char(1).or(base)
+    } else {
+        base
+    }
+}
+
+fn button_events(button: u8, down: bool) -> Option<InputEvent> {
+    let mouse_button = match button {
+        1 => MouseButton::Left,
+        2 => MouseButton::Middle,
+        3 => MouseButton::Right,
+        // Each scroll notch is a press and a release.
+        4 if down => return Some(InputEvent::MouseScroll(0, -SCROLL_NOTCH)),
+        5 if down => return Some(InputEvent::MouseScroll(0, SCROLL_NOTCH)),
+        6 if down => return Some(InputEvent::MouseScroll(-SCROLL_NOTCH, 0)),
+        7 if down => return Some(InputEvent::MouseScroll(SCROLL_NOTCH, 0)),
+        _ => return None,
+    };
+    if down {
+        Some(InputEvent::MouseDown(mouse_button))
+    } else {
+        Some(InputEvent::MouseUp(mouse_button))
+    }
+}
+
+// The intercepted data is a 32-byte xEvent in the wire format. The server is
+// assumed to have the same byte order as we do.
+unsafe extern "C" fn record_callback(closure: *mut c_char, data: *mut ffi::XRecordInterceptData) {
+    let state = &mut *(closure as *mut CallbackState);
+    let intercept = &*data;
+
+    if intercept.category == ffi::XRecordFromServer
+        && intercept.data_len * 4 >= 32
+        && !state.disconnected
+    {
+        let bytes = std::slice::from_raw_parts(intercept.data, 32);
+        let detail = bytes[1];
+        let event_state = u16::from_ne_bytes([bytes[28], bytes[29]]);
+        let timestamp = u32::from_ne_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+
+        let event = match bytes[0] & 0x7f {
+            KEY_PRESS | KEY_RELEASE if detail >= 8 => {
+                match linux_common::from_key_code(detail as u16 - 8) {
+                    Some(key) if bytes[0] & 0x7f == KEY_PRESS => Some((
+                        InputEvent::KeyDown(key),
+                        key_char(state.lookup, detail, event_state),
+                    )),
+                    Some(key) => Some((InputEvent::KeyUp(key), None)),
+                    None => None,
+                }
+            }
+            BUTTON_PRESS => button_events(detail, true).map(|e| (e, None)),
+            BUTTON_RELEASE => button_events(detail, false).map(|e| (e, None)),
+            MOTION_NOTIFY => {
+                let x = i16::from_ne_bytes([bytes[20], bytes[21]]) as c_int;
+                let y = i16::from_ne_bytes([bytes[22], bytes[23]]) as c_int;
+                Some((InputEvent::MouseMoveAbs(x, y), None))
+            }
+            _ => None,
+        };
+
+        if let Some((event, ch)) = event {
+            let (first_timestamp, first_time) =
+                *state.first.get_or_insert_with(|| (timestamp, Instant::now()));
+            // The timestamp is in milliseconds and wraps around.
+            let elapsed = timestamp.wrapping_sub(first_timestamp) as u64;
+            let event = TimedInputEvent {
+                event,
+                ch,
+                time: first_time + Duration::from_millis(elapsed),
+            };
+            if state.sender.send(event).is_err() {
+                state.disconnected = true;
+            }
+        }
+    }
+
+    ffi::XRecordFreeData(data);
+}
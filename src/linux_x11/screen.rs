@@ -1,4 +1,36 @@
 use super::{ffi, Error, PlatformError};
+use crate::Rect;
+
+// Get the monitors with RandR. `None` is returned if libXrandr isn't installed
+// or is older than 1.5.
+unsafe fn randr_monitors(display: *mut ffi::Display, root: ffi::Window) -> Option<Vec<Rect>> {
+    let library = ffi::dlopen(ffi::LIBXRANDR.as_ptr() as _, ffi::RTLD_LAZY);
+    if library.is_null() {
+        return None;
+    }
+    let get_monitors = ffi::dlsym(library, b"XRRGetMonitors\0".as_ptr() as _);
+    let free_monitors = ffi::dlsym(library, b"XRRFreeMonitors\0".as_ptr() as _);
+    let mut monitors = None;
+
+    if !get_monitors.is_null() && !free_monitors.is_null() {
+        let get_monitors: ffi::XRRGetMonitors = std::mem::transmute(get_monitors);
+        let free_monitors: ffi::XRRFreeMonitors = std::mem::transmute(free_monitors);
+        let mut count = 0;
+        let info = get_monitors(display, root, ffi::True, &mut count);
+        if !info.is_null() {
+            monitors = Some(
+                std::slice::from_raw_parts(info, count.max(0) as usize)
+                    .iter()
+                    .map(|m| Rect::new(m.x, m.y, m.width.max(0) as u32, m.height.max(0) as u32))
+                    .collect(),
+            );
+            free_monitors(info);
+        }
+    }
+
+    ffi::dlclose(library);
+    monitors
+}
 
 impl crate::ScreenContext for super::Context {
     fn cursor_location(&self) -> Result<(i32, i32), Error> {
@@ -41,4 +73,18 @@ impl crate::ScreenContext for super::Context {
             Ok((width as i32, height as i32))
         }
     }
+
+    fn monitors(&self) -> Result<Vec<Rect>, Error> {
+        let monitors = unsafe {
+            let screen = ffi::XScreenOfDisplay(self.display, self.screen_number);
+            randr_monitors(self.display, ffi::XRootWindowOfScreen(screen))
+        };
+        match monitors {
+            Some(monitors) if !monitors.is_empty() => Ok(monitors),
+            _ => {
+                let (width, height) = self.screen_size()?;
+                Ok(vec![Rect::new(0, 0, width as u32, height as u32)])
+            }
+        }
+    }
 }
@@ -0,0 +1,292 @@
+use super::{keyboard::info_from_char, window::window_id, Context, Error, PlatformError};
+use crate::{linux_common, FallibleContext, Key, MouseButton, WindowId};
+use std::os::raw::{c_int, c_long, c_uint};
+use x11::xlib;
+
+// The state field of an event holds the keyboard group in these bits.
+const GROUP_SHIFT: c_uint = 13;
+
+fn to_button(button: MouseButton) -> c_uint {
+    match button {
+        MouseButton::Left => 1,
+        MouseButton::Right => 3,
+        MouseButton::Middle => 2,
+    }
+}
+
+fn button_mask(button: c_uint) -> c_uint {
+    match button {
+        1..=5 => xlib::Button1Mask << (button - 1),
+        _ => 0,
+    }
+}
+
+/// A context that sends events directly to a window (Linux-X11).
+///
+/// This is created by [`Context::for_window`]. Events are delivered with
+/// `XSendEvent` so the window doesn't need to have focus and the user can keep
+/// working in another window. Mouse coordinates are relative to the top-left
+/// corner of the window and the real pointer isn't moved.
+///
+/// The X server marks events delivered this way as synthetic and some
+/// toolkits ignore them entirely. For example, xterm ignores them unless
+/// `allowSendEvents` is enabled and many games and Java applications don't
+/// respond to them. Applications built with GTK and Qt generally accept
+/// synthetic key events.
+pub struct TargetedContext<'a> {
+    ctx: &'a mut Context,
+    window: xlib::Window,
+    // The modifier and button mask for the state field of the events.
+    state: c_uint,
+    pointer: (i32, i32),
+    scroll: linux_common::ScrollAccum,
+}
+
+impl Context {
+    /// Create a context that sends events directly to a window.
+    ///
+    /// See [`TargetedContext`] for the limitations of this.
+    pub fn for_window(&mut self, window: WindowId) -> TargetedContext<'_> {
+        TargetedContext {
+            ctx: self,
+            window: window.0 as xlib::Window,
+            state: 0,
+            pointer: (0, 0),
+            scroll: Default::default(),
+        }
+    }
+}
+
+impl<'a> TargetedContext<'a> {
+    /// The window that events are sent to.
+    pub fn window(&self) -> WindowId {
+        window_id(self.window)
+    }
+
+    // Get the mask of the modifier that a keycode is mapped to.
+    fn modifier_mask(&self, keycode: u8) -> c_uint {
+        unsafe {
+            let map = &*self.ctx.modifier_map;
+            let key_per_mod = map.max_keypermod as usize;
+            for mod_index in 0..8 {
+                for key_index in 0..key_per_mod {
+                    if *map.modifiermap.add(mod_index * key_per_mod + key_index) == keycode {
+                        return 1 << mod_index;
+                    }
+                }
+            }
+        }
+        0
+    }
+
+    fn root_position(&self) -> (xlib::Window, c_int, c_int) {
+        let display = self.ctx.display as *mut xlib::Display;
+        unsafe {
+            let root = xlib::XRootWindow(display, self.ctx.screen_number);
+            let mut x = 0;
+            let mut y = 0;
+            let mut child = 0;
+            xlib::XTranslateCoordinates(
+                display,
+                self.window,
+                root,
+                self.pointer.0,
+                self.pointer.1,
+                &mut x,
+                &mut y,
+                &mut child,
+            );
+            (root, x, y)
+        }
+    }
+
+    fn send(&mut self, mut event: xlib::XEvent, mask: c_long) -> Result<(), Error> {
+        let window = self.window;
+        self.ctx.trap_errors(|ctx| unsafe {
+            let display = ctx.display as *mut xlib::Display;
+            if xlib::XSendEvent(display, window, xlib::True, mask, &mut event) == 0 {
+                return Err(Error::Platform(PlatformError::XSendEvent));
+            }
+            xlib::XSync(display, xlib::False);
+            Ok(())
+        })
+    }
+
+    fn key_event(&mut self, keycode: u8, state: c_uint, down: bool) -> Result<(), Error> {
+        let (root, x_root, y_root) = self.root_position();
+        let mut event: xlib::XEvent = unsafe { std::mem::zeroed() };
+        let key = unsafe { &mut event.key };
+        key.type_ = if down {
+            xlib::KeyPress
+        } else {
+            xlib::KeyRelease
+        };
+        key.display = self.ctx.display as *mut xlib::Display;
+        key.window = self.window;
+        key.root = root;
+        key.time = xlib::CurrentTime;
+        key.x = self.pointer.0;
+        key.y = self.pointer.1;
+        key.x_root = x_root;
+        key.y_root = y_root;
+        key.state = state;
+        key.keycode = keycode as c_uint;
+        key.same_screen = xlib::True;
+        let mask = if down {
+            xlib::KeyPressMask
+        } else {
+            xlib::KeyReleaseMask
+        };
+        self.send(event, mask)
+    }
+
+    fn button_event(&mut self, button: c_uint, down: bool) -> Result<(), Error> {
+        let (root, x_root, y_root) = self.root_position();
+        let mut event: xlib::XEvent = unsafe { std::mem::zeroed() };
+        let button_event = unsafe { &mut event.button };
+        button_event.type_ = if down {
+            xlib::ButtonPress
+        } else {
+            xlib::ButtonRelease
+        };
+        button_event.display = self.ctx.display as *mut xlib::Display;
+        button_event.window = self.window;
+        button_event.root = root;
+        button_event.time = xlib::CurrentTime;
+        button_event.x = self.pointer.0;
+        button_event.y = self.pointer.1;
+        button_event.x_root = x_root;
+        button_event.y_root = y_root;
+        button_event.state = self.state;
+        button_event.button = button;
+        button_event.same_screen = xlib::True;
+        let mask = if down {
+            xlib::ButtonPressMask
+        } else {
+            xlib::ButtonReleaseMask
+        };
+        self.send(event, mask)?;
+        // The state of an event is the state before the event.
+        if down {
+            self.state |= button_mask(button);
+        } else {
+            self.state &= !button_mask(button);
+        }
+        Ok(())
+    }
+
+    fn motion_event(&mut self) -> Result<(), Error> {
+        let (root, x_root, y_root) = self.root_position();
+        let mut event: xlib::XEvent = unsafe { std::mem::zeroed() };
+        let motion = unsafe { &mut event.motion };
+        motion.type_ = xlib::MotionNotify;
+        motion.display = self.ctx.display as *mut xlib::Display;
+        motion.window = self.window;
+        motion.root = root;
+        motion.time = xlib::CurrentTime;
+        motion.x = self.pointer.0;
+        motion.y = self.pointer.1;
+        motion.x_root = x_root;
+        motion.y_root = y_root;
+        motion.state = self.state;
+        motion.same_screen = xlib::True;
+        let mut mask = xlib::PointerMotionMask;
+        if self.state & (xlib::Button1Mask | xlib::Button2Mask | xlib::Button3Mask) != 0 {
+            mask |= xlib::ButtonMotionMask;
+        }
+        self.send(event, mask)
+    }
+
+    fn char_event(&mut self, ch: char, down: bool, up: bool) -> Result<(), Error> {
+        // Remapping a keycode can fail so errors need to be trapped.
+        let info = self.ctx.trap_errors(|ctx| {
+            ctx.handle_mapping_events()?;
+            let group = ctx.active_group();
+            info_from_char(ctx, group, ch).ok_or(Error::UnsupportedUnicode(ch))
+        })?;
+        // The modifiers are only applied to the event rather than being
+        // pressed.
+        let state = self.state | info.modifiers as c_uint | (info.group as c_uint) << GROUP_SHIFT;
+        if down {
+            self.key_event(info.keycode, state, true)?;
+        }
+        if up {
+            self.key_event(info.keycode, state, false)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> FallibleContext for TargetedContext<'a> {
+    type PlatformError = PlatformError;
+}
+
+impl<'a> crate::KeyboardContext for TargetedContext<'a> {
+    fn key_down(&mut self, key: Key) -> Result<(), Error> {
+        let keycode = (linux_common::to_key_code(key) + 8) as u8;
+        self.key_event(keycode, self.state, true)?;
+        self.state |= self.modifier_mask(keycode);
+        Ok(())
+    }
+
+    fn key_up(&mut self, key: Key) -> Result<(), Error> {
+        let keycode = (linux_common::to_key_code(key) + 8) as u8;
+        self.key_event(keycode, self.state, false)?;
+        self.state &= !self.modifier_mask(keycode);
+        Ok(())
+    }
+}
+
+impl<'a> crate::UnicodeKeyboardContext for TargetedContext<'a> {
+    fn unicode_char_down(&mut self, ch: char) -> Result<(), Error> {
+        self.char_event(ch, true, false)
+    }
+
+    fn unicode_char_up(&mut self, ch: char) -> Result<(), Error> {
+        self.char_event(ch, false, true)
+    }
+
+    fn unicode_char(&mut self, ch: char) -> Result<(), Error> {
+        self.char_event(ch, true, true)
+    }
+
+    fn unicode_string(&mut self, s: &str) -> Result<(), Error> {
+        for ch in s.chars() {
+            self.char_event(ch, true, true)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> crate::MouseContext for TargetedContext<'a> {
+    fn mouse_move_rel(&mut self, dx: i32, dy: i32) -> Result<(), Error> {
+        self.pointer = (self.pointer.0 + dx, self.pointer.1 + dy);
+        self.motion_event()
+    }
+
+    fn mouse_move_abs(&mut self, x: i32, y: i32) -> Result<(), Error> {
+        self.pointer = (x, y);
+        self.motion_event()
+    }
+
+    fn mouse_scroll(&mut self, dx: i32, dy: i32) -> Result<(), Error> {
+        let (x, y) = self.scroll.accumulate(dx, dy);
+        let horizontal = if x < 0 { 6 } else { 7 };
+        let vertical = if y < 0 { 4 } else { 5 };
+        for (button, count) in [(horizontal, x.abs()), (vertical, y.abs())] {
+            for _ in 0..count {
+                self.button_event(button, true)?;
+                self.button_event(button, false)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn mouse_down(&mut self, button: MouseButton) -> Result<(), Error> {
+        self.button_event(to_button(button), true)
+    }
+
+    fn mouse_up(&mut self, button: MouseButton) -> Result<(), Error> {
+        self.button_event(to_button(button), false)
+    }
+}
@@ -0,0 +1,173 @@
+use super::{ffi, Context, Error, PlatformError};
+use std::{
+    ffi::c_void,
+    os::raw::c_int,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+// Xlib reports protocol errors asynchronously through an error handler. The
+// default handler prints the error and then exits the process which isn't
+// something that a library should ever do. While an error trap is active, our
+// own handler is installed and errors on the trapped displays are recorded.
+// Errors on any other display are passed on to the previous handler.
+//
+// The error handler is process-wide so the trap state is too. Traps may be
+// nested and may be active on multiple threads at once. The handler is
+// installed by the first trap and restored by the last.
+
+struct TrapState {
+    depth: usize,
+    previous: ffi::XErrorHandler,
+    displays: Vec<usize>,
+    errors: Vec<(usize, PlatformError)>,
+}
+
+static TRAP: Mutex<TrapState> = Mutex::new(TrapState {
+    depth: 0,
+    previous: None,
+    displays: Vec::new(),
+    errors: Vec::new(),
+});
+
+unsafe extern "C" fn error_handler(display: *mut ffi::Display, event: *mut ffi::XErrorEvent) -> c_int {
+    let mut state = lock();
+    if state.displays.contains(&(display as usize)) {
+        let event = &*event;
+        state.errors.push((
+            display as usize,
+            PlatformError::XProtocol {
+                error_code: event.error_code,
+                request_code: event.request_code,
+                minor_code: event.minor_code,
+            },
+        ));
+        0
+    } else if let Some(previous) = state.previous {
+        drop(state);
+        previous(display, event)
+    } else {
+        0
+    }
+}
+
+// Called by Xlib when the connection to the server is lost. The default exit
+// handler exits the process. If this returns, Xlib will carry on and all
+// further requests on the display will fail.
+unsafe extern "C" fn io_error_exit_handler(_: *mut ffi::Display, user_data: *mut c_void) {
+    (*(user_data as *const AtomicBool)).store(true, Ordering::Release);
+}
+
+/// Install the handler that is called when the connection to the server is
+/// lost.
+///
+/// The flag will be set when the connection is lost. The flag must outlive the
+/// display. The exit handler was added in libX11 1.7. With older versions,
+/// nothing is installed and losing the connection exits the process.
+pub(super) unsafe fn set_connection_lost_flag(display: *mut ffi::Display, flag: &AtomicBool) {
+    let symbol = ffi::dlsym(ffi::RTLD_DEFAULT, b"XSetIOErrorExitHandler\0".as_ptr() as _);
+    if symbol.is_null() {
+        return;
+    }
+    let set_handler: ffi::XSetIOErrorExitHandler = std::mem::transmute(symbol);
+    set_handler(
+        display,
+        Some(io_error_exit_handler),
+        flag as *const AtomicBool as *mut c_void,
+    );
+}
+
+fn lock() -> std::sync::MutexGuard<'static, TrapState> {
+    match TRAP.lock() {
+        Ok(state) => state,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn begin_trap(display: usize) {
+    let mut state = lock();
+    if state.depth == 0 {
+        state.previous = unsafe { ffi::XSetErrorHandler(Some(error_handler)) };
+    }
+    state.depth += 1;
+    state.displays.push(display);
+}
+
+fn end_trap(display: usize) -> Option<PlatformError> {
+    let mut state = lock();
+    let error = state
+        .errors
+        .iter()
+        .position(|(d, _)| *d == display)
+        .map(|index| state.errors.remove(index).1);
+    state.errors.retain(|(d, _)| *d != display);
+    if let Some(index) = state.displays.iter().position(|d| *d == display) {
+        state.displays.swap_remove(index);
+    }
+    state.depth -= 1;
+    if state.depth == 0 {
+        let previous = state.previous.take();
+        unsafe {
+            ffi::XSetErrorHandler(previous);
+        }
+    }
+    error
+}
+
+impl Context {
+    /// Run a function with the errors on this display being trapped.
+    ///
+    /// The function should make requests on the display. Once it returns, the
+    /// display is synchronised so that any errors generated by those requests
+    /// are received. The first of these errors is returned.
+    ///
+    /// During a [`batch`](Context::batch), the whole batch is trapped so the
+    /// display is only synchronised at the end of it.
+    pub(super) fn trap_errors<T, F>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        if self.connection_lost.load(Ordering::Acquire) {
+            return Err(Error::Platform(PlatformError::ConnectionLost));
+        }
+        if self.batching {
+            return f(self);
+        }
+
+        let display = self.display as usize;
+        begin_trap(display);
+        let result = f(self);
+        unsafe {
+            ffi::XSync(self.display, ffi::False);
+        }
+        let error = end_trap(display);
+
+        if self.connection_lost.load(Ordering::Acquire) {
+            Err(Error::Platform(PlatformError::ConnectionLost))
+        } else if let Some(error) = error {
+            Err(Error::Platform(error))
+        } else {
+            result
+        }
+    }
+}
+
+/// Run a function with the errors on a display that doesn't belong to a
+/// context being trapped.
+///
+/// This is the same as [`Context::trap_errors`] but for the extra connections
+/// used by listeners.
+pub(super) unsafe fn trap_display_errors<T, F>(display: *mut ffi::Display, f: F) -> Result<T, PlatformError>
+where
+    F: FnOnce() -> T,
+{
+    begin_trap(display as usize);
+    let result = f();
+    ffi::XSync(display, ffi::False);
+    match end_trap(display as usize) {
+        Some(error) => Err(error),
+        None => Ok(result),
+    }
+}
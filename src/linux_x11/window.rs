@@ -0,0 +1,381 @@
+use super::{trap::trap_display_errors, Context, Error};
+use crate::{Rect, WindowId, WindowInfo};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_int, c_long, c_uchar, c_uint, c_ulong},
+};
+use x11::xlib;
+
+// The source indication for _NET_ACTIVE_WINDOW. Pagers and other tools that
+// act on behalf of the user use 2.
+const SOURCE_PAGER: c_long = 2;
+const MAX_PROPERTY_LENGTH: c_long = 1 << 24;
+
+// Window is a c_ulong which is only 32 bits on some targets.
+#[allow(clippy::unnecessary_cast)]
+pub(super) fn window_id(window: xlib::Window) -> WindowId {
+    WindowId(window as u64)
+}
+
+// Walk up the tree until the child of the root window is found.
+unsafe fn top_level(display: *mut xlib::Display, mut window: xlib::Window) -> xlib::Window {
+    loop {
+        let mut root = 0;
+        let mut parent = 0;
+        let mut children = std::ptr::null_mut();
+        let mut count: c_uint = 0;
+        if xlib::XQueryTree(
+            display,
+            window,
+            &mut root,
+            &mut parent,
+            &mut children,
+            &mut count,
+        ) == 0
+        {
+            return window;
+        }
+        if !children.is_null() {
+            xlib::XFree(children as *mut _);
+        }
+        if parent == root || parent == 0 {
+            return window;
+        }
+        window = parent;
+    }
+}
+
+impl Context {
+    pub(super) fn xdisplay(&self) -> *mut xlib::Display {
+        self.display as *mut xlib::Display
+    }
+
+    pub(super) fn atom(&self, name: &str) -> xlib::Atom {
+        let name = CString::new(name).unwrap();
+        unsafe { xlib::XInternAtom(self.xdisplay(), name.as_ptr(), xlib::False) }
+    }
+
+    // Get the raw bytes of a property along with its format. None is returned
+    // if the property doesn't exist or has a different type.
+    unsafe fn property(
+        &self,
+        window: xlib::Window,
+        name: &str,
+        type_: xlib::Atom,
+    ) -> Option<(c_int, Vec<u8>)> {
+        let mut actual_type = 0;
+        let mut format = 0;
+        let mut count = 0;
+        let mut bytes_after = 0;
+        let mut data: *mut c_uchar = std::ptr::null_mut();
+        let status = xlib::XGetWindowProperty(
+            self.xdisplay(),
+            window,
+            self.atom(name),
+            0,
+            MAX_PROPERTY_LENGTH,
+            xlib::False,
+            type_,
+            &mut actual_type,
+            &mut format,
+            &mut count,
+            &mut bytes_after,
+            &mut data,
+        );
+        if status != xlib::Success as c_int || data.is_null() {
+            return None;
+        }
+        // Items in the 32-bit format are stored as longs.
+        let item_size = match format {
+            8 => 1,
+            16 => std::mem::size_of::<std::os::raw::c_short>(),
+            _ => std::mem::size_of::<c_long>(),
+        };
+        let bytes = std::slice::from_raw_parts(data, count as usize * item_size).to_vec();
+        xlib::XFree(data as *mut _);
+        if actual_type == type_ {
+            Some((format, bytes))
+        } else {
+            None
+        }
+    }
+
+    unsafe fn property_longs(
+        &self,
+        window: xlib::Window,
+        name: &str,
+        type_: xlib::Atom,
+    ) -> Option<Vec<c_ulong>> {
+        match self.property(window, name, type_) {
+            Some((32, bytes)) => Some(
+                bytes
+                    .chunks_exact(std::mem::size_of::<c_ulong>())
+                    .map(|b| {
+                        let mut long = [0; std::mem::size_of::<c_ulong>()];
+                        long.copy_from_slice(b);
+                        c_ulong::from_ne_bytes(long)
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    unsafe fn property_string(
+        &self,
+        window: xlib::Window,
+        name: &str,
+        type_: xlib::Atom,
+    ) -> Option<String> {
+        match self.property(window, name, type_) {
+            Some((8, bytes)) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+            _ => None,
+        }
+    }
+
+    // Whether there is an EWMH compliant window manager.
+    fn has_ewmh(&self) -> bool {
+        unsafe {
+            self.property_longs(
+                self.root_window(),
+                "_NET_SUPPORTING_WM_CHECK",
+                xlib::XA_WINDOW,
+            )
+            .is_some_and(|windows| !windows.is_empty())
+        }
+    }
+
+    fn client_list(&self) -> Vec<xlib::Window> {
+        unsafe {
+            if let Some(windows) = self
+                .property_longs(
+                    self.root_window(),
+                    "_NET_CLIENT_LIST_STACKING",
+                    xlib::XA_WINDOW,
+                )
+                .or_else(|| {
+                    self.property_longs(self.root_window(), "_NET_CLIENT_LIST", xlib::XA_WINDOW)
+                })
+            {
+                return windows;
+            }
+
+            let mut root = 0;
+            let mut parent = 0;
+            let mut children = std::ptr::null_mut();
+            let mut count: c_uint = 0;
+            if xlib::XQueryTree(
+                self.xdisplay(),
+                self.root_window(),
+                &mut root,
+                &mut parent,
+                &mut children,
+                &mut count,
+            ) == 0
+            {
+                return Vec::new();
+            }
+            if children.is_null() {
+                return Vec::new();
+            }
+            let windows = std::slice::from_raw_parts(children, count as usize)
+                .iter()
+                .copied()
+                .filter(|window| {
+                    let mut attributes: xlib::XWindowAttributes = std::mem::zeroed();
+                    xlib::XGetWindowAttributes(self.xdisplay(), *window, &mut attributes) != 0
+                        && attributes.map_state == xlib::IsViewable
+                        && attributes.override_redirect == xlib::False
+                })
+                .collect();
+            xlib::XFree(children as *mut _);
+            windows
+        }
+    }
+
+    unsafe fn window_info(&self, window: xlib::Window) -> Option<WindowInfo> {
+        let display = self.xdisplay();
+
+        let title = self
+            .property_string(window, "_NET_WM_NAME", self.atom("UTF8_STRING"))
+            .or_else(|| self.property_string(window, "WM_NAME", xlib::XA_STRING))
+            .unwrap_or_default();
+
+        let mut hint = xlib::XClassHint {
+            res_name: std::ptr::null_mut(),
+            res_class: std::ptr::null_mut(),
+        };
+        let mut class = String::new();
+        if xlib::XGetClassHint(display, window, &mut hint) != 0 {
+            if !hint.res_class.is_null() {
+                class = CStr::from_ptr(hint.res_class)
+                    .to_string_lossy()
+                    .into_owned();
+                xlib::XFree(hint.res_class as *mut _);
+            }
+            if !hint.res_name.is_null() {
+                xlib::XFree(hint.res_name as *mut _);
+            }
+        }
+
+        let pid = self
+            .property_longs(window, "_NET_WM_PID", xlib::XA_CARDINAL)
+            .and_then(|pids| pids.first().copied())
+            .map(|pid| pid as u32);
+
+        let mut root = 0;
+        let mut x = 0;
+        let mut y = 0;
+        let mut width = 0;
+        let mut height = 0;
+        let mut border = 0;
+        let mut depth = 0;
+        if xlib::XGetGeometry(
+            display,
+            window,
+            &mut root,
+            &mut x,
+            &mut y,
+            &mut width,
+            &mut height,
+            &mut border,
+            &mut depth,
+        ) == 0
+        {
+            return None;
+        }
+        // The window may have been reparented by the window manager so the
+        // position is relative to the frame.
+        let mut child = 0;
+        xlib::XTranslateCoordinates(display, window, root, 0, 0, &mut x, &mut y, &mut child);
+
+        Some(WindowInfo {
+            id: window_id(window),
+            title,
+            class,
+            pid,
+            rect: Rect::new(x, y, width, height),
+        })
+    }
+
+    // Send a client message to the root window for the window manager.
+    fn send_wm_message(
+        &mut self,
+        window: xlib::Window,
+        name: &str,
+        data: [c_long; 5],
+    ) -> Result<(), Error> {
+        let message_type = self.atom(name);
+        let root = self.root_window();
+        self.trap_errors(|ctx| unsafe {
+            let mut event: xlib::XEvent = std::mem::zeroed();
+            event.client_message.type_ = xlib::ClientMessage;
+            event.client_message.window = window;
+            event.client_message.message_type = message_type;
+            event.client_message.format = 32;
+            for (i, value) in data.iter().enumerate() {
+                event.client_message.data.set_long(i, *value);
+            }
+            xlib::XSendEvent(
+                ctx.xdisplay(),
+                root,
+                xlib::False,
+                xlib::SubstructureRedirectMask | xlib::SubstructureNotifyMask,
+                &mut event,
+            );
+            Ok(())
+        })
+    }
+}
+
+impl crate::WindowContext for Context {
+    fn windows(&self) -> Result<Vec<WindowInfo>, Error> {
+        let mut windows = Vec::new();
+        for window in self.client_list() {
+            // Windows can be destroyed at any time so errors are ignored.
+            if let Ok(Some(info)) =
+                unsafe { trap_display_errors(self.display, || self.window_info(window)) }
+            {
+                windows.push(info);
+            }
+        }
+        Ok(windows)
+    }
+
+    fn active_window(&self) -> Result<Option<WindowId>, Error> {
+        unsafe {
+            if self.has_ewmh() {
+                let active = self
+                    .property_longs(self.root_window(), "_NET_ACTIVE_WINDOW", xlib::XA_WINDOW)
+                    .and_then(|windows| windows.first().copied())
+                    .filter(|window| *window != 0);
+                return Ok(active.map(window_id));
+            }
+
+            let mut focus = 0;
+            let mut revert = 0;
+            xlib::XGetInputFocus(self.xdisplay(), &mut focus, &mut revert);
+            if focus == 0
+                || focus == xlib::PointerRoot as xlib::Window
+                || focus == self.root_window()
+            {
+                return Ok(None);
+            }
+            Ok(Some(window_id(top_level(self.xdisplay(), focus))))
+        }
+    }
+
+    fn activate_window(&mut self, id: WindowId) -> Result<(), Error> {
+        let window = id.0 as xlib::Window;
+        if self.has_ewmh() {
+            return self.send_wm_message(
+                window,
+                "_NET_ACTIVE_WINDOW",
+                [SOURCE_PAGER, xlib::CurrentTime as c_long, 0, 0, 0],
+            );
+        }
+        self.trap_errors(|ctx| unsafe {
+            xlib::XRaiseWindow(ctx.xdisplay(), window);
+            xlib::XSetInputFocus(
+                ctx.xdisplay(),
+                window,
+                xlib::RevertToParent,
+                xlib::CurrentTime,
+            );
+            Ok(())
+        })
+    }
+
+    fn raise_window(&mut self, id: WindowId) -> Result<(), Error> {
+        self.trap_errors(|ctx| unsafe {
+            xlib::XRaiseWindow(ctx.xdisplay(), id.0 as xlib::Window);
+            Ok(())
+        })
+    }
+
+    fn minimize_window(&mut self, id: WindowId) -> Result<(), Error> {
+        self.trap_errors(|ctx| unsafe {
+            xlib::XIconifyWindow(ctx.xdisplay(), id.0 as xlib::Window, ctx.screen_number);
+            Ok(())
+        })
+    }
+
+    fn move_window(&mut self, id: WindowId, x: i32, y: i32) -> Result<(), Error> {
+        self.trap_errors(|ctx| unsafe {
+            xlib::XMoveWindow(ctx.xdisplay(), id.0 as xlib::Window, x, y);
+            Ok(())
+        })
+    }
+
+    fn resize_window(&mut self, id: WindowId, width: u32, height: u32) -> Result<(), Error> {
+        self.trap_errors(|ctx| unsafe {
+            xlib::XResizeWindow(
+                ctx.xdisplay(),
+                id.0 as xlib::Window,
+                width.max(1),
+                height.max(1),
+            );
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,152 @@
+use super::{ffi, Error, PlatformError};
+use crate::linux_common;
+use std::{
+    ffi::{c_void, CStr},
+    io,
+    os::raw::{c_char, c_int, c_uchar},
+    sync::atomic::{AtomicBool, Ordering},
+};
+use x11::xlib;
+
+// How often the reader threads check whether they should stop.
+const POLL_TIMEOUT_MS: c_int = 100;
+
+// Large enough for every event that is selected.
+const MASK_LEN: usize = 4;
+
+/// Check that the server supports XInput 2.1 and return the major opcode of
+/// the extension.
+///
+/// Raw events only carry the device that they came from since 2.1.
+pub unsafe fn query_version(display: *mut ffi::Display) -> Result<c_int, Error> {
+    let name = b"XInputExtension\0";
+    let mut opcode = 0;
+    let mut event = 0;
+    let mut error = 0;
+    let present = xlib::XQueryExtension(
+        display as *mut xlib::Display,
+        name.as_ptr() as *const c_char,
+        &mut opcode,
+        &mut event,
+        &mut error,
+    );
+    if present == xlib::False {
+        return Err(Error::Platform(PlatformError::XIQueryVersion));
+    }
+
+    let mut major = 2;
+    let mut minor = 2;
+    if ffi::XIQueryVersion(display, &mut major, &mut minor) != xlib::Success as c_int
+        || (major, minor) < (2, 1)
+    {
+        return Err(Error::Platform(PlatformError::XIQueryVersion));
+    }
+    Ok(opcode)
+}
+
+/// An event mask that selects the given events.
+pub fn event_mask(events: &[c_int]) -> [c_uchar; MASK_LEN] {
+    let mut mask = [0; MASK_LEN];
+    for event in events {
+        mask[*event as usize / 8] |= 1 << (*event % 8);
+    }
+    mask
+}
+
+/// The slave devices that events generated with XTest come from.
+///
+/// The server creates an XTEST keyboard and pointer for every master device.
+/// Every event that a [`Context`](super::Context) generates comes from one of
+/// these so they can be told apart from the events of real devices by their
+/// source device.
+pub struct XTestDevices(Vec<c_int>);
+
+impl XTestDevices {
+    pub unsafe fn query(display: *mut ffi::Display) -> Self {
+        let mut count = 0;
+        let info = ffi::XIQueryDevice(display, ffi::XIAllDevices, &mut count);
+        if info.is_null() {
+            return Self(Vec::new());
+        }
+        let devices = std::slice::from_raw_parts(info, count as usize)
+            .iter()
+            .filter(|device| {
+                if device.use_ != ffi::XISlaveKeyboard && device.use_ != ffi::XISlavePointer {
+                    return false;
+                }
+                let name = CStr::from_ptr(device.name).to_string_lossy();
+                name.ends_with(" XTEST keyboard") || name.ends_with(" XTEST pointer")
+            })
+            .map(|device| device.deviceid)
+            .collect();
+        ffi::XIFreeDeviceInfo(info);
+        Self(devices)
+    }
+
+    pub fn contains(&self, deviceid: c_int) -> bool {
+        self.0.contains(&deviceid)
+    }
+}
+
+/// Read XInput events until `stop` is set, the connection is lost or `f`
+/// returns `false`.
+///
+/// `f` is called with the XTEST devices, the type of the event and the event
+/// data. The devices are queried again whenever the device hierarchy changes
+/// (`XI_HierarchyChanged` must be selected for this) so that a new master
+/// device doesn't go unnoticed.
+pub unsafe fn read_events<F>(
+    display: *mut xlib::Display,
+    opcode: c_int,
+    stop: &AtomicBool,
+    mut f: F,
+) where
+    F: FnMut(&XTestDevices, c_int, *const c_void) -> bool,
+{
+    let mut xtest = XTestDevices::query(display as *mut ffi::Display);
+
+    while !stop.load(Ordering::Relaxed) {
+        if xlib::XPending(display) == 0 {
+            let mut fds = linux_common::ffi::pollfd {
+                fd: xlib::XConnectionNumber(display),
+                events: linux_common::ffi::POLLIN,
+                revents: 0,
+            };
+            let ready = linux_common::ffi::poll(&mut fds, 1, POLL_TIMEOUT_MS);
+            if ready < 0 {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return;
+            }
+            if fds.revents & (linux_common::ffi::POLLERR | linux_common::ffi::POLLHUP) != 0 {
+                return;
+            }
+            // Reads any available data from the connection.
+            if ready == 0 || xlib::XPending(display) == 0 {
+                continue;
+            }
+        }
+
+        let mut event: xlib::XEvent = std::mem::zeroed();
+        xlib::XNextEvent(display, &mut event);
+
+        let cookie = &mut event.generic_event_cookie;
+        if cookie.type_ != xlib::GenericEvent
+            || cookie.extension != opcode
+            || xlib::XGetEventData(display, cookie) == xlib::False
+        {
+            continue;
+        }
+        let proceed = if cookie.evtype == ffi::XI_HierarchyChanged {
+            xtest = XTestDevices::query(display as *mut ffi::Display);
+            true
+        } else {
+            f(&xtest, cookie.evtype, cookie.data)
+        };
+        xlib::XFreeEventData(display, cookie);
+        if !proceed {
+            return;
+        }
+    }
+}
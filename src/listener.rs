@@ -0,0 +1,98 @@
+use crate::{
+    linux_common::EvdevReader,
+    linux_x11::{RawReader, RecordReader},
+    ContextBuilder, Error, TimedInputEvent,
+};
+use std::{
+    io,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
+    time::Duration,
+};
+
+// The readers are only held so that they are stopped when the listener is
+// dropped.
+#[allow(dead_code)]
+enum Source {
+    X11(RecordReader),
+    X11Keys(RawReader),
+    Evdev(EvdevReader),
+}
+
+/// Listens for events from real input devices (Linux).
+///
+/// Events can be received from the X server or directly from the devices in
+/// `/dev/input`. The X server doesn't distinguish between real events and
+/// those generated by a [`Context`](crate::Context) so those will be received
+/// too. Reading devices directly requires permission to read `/dev/input`
+/// which is usually granted by the `input` group.
+///
+/// ```no_run
+/// use tfc::{ContextBuilder, InputListener};
+///
+/// let listener = InputListener::x11(&ContextBuilder::new()).unwrap();
+/// while let Some(event) = listener.recv() {
+///     println!("{:?}", event.event);
+/// }
+/// ```
+pub struct InputListener {
+    receiver: Receiver<TimedInputEvent>,
+    _source: Source,
+}
+
+impl InputListener {
+    /// Listen for events on the display chosen by the builder using the
+    /// RECORD extension.
+    pub fn x11(builder: &ContextBuilder) -> Result<Self, Error> {
+        let (sender, receiver) = mpsc::channel();
+        Ok(Self {
+            receiver,
+            _source: Source::X11(RecordReader::open(builder, sender)?),
+        })
+    }
+
+    /// Listen for the key events of real devices on the display chosen by the
+    /// builder using XInput 2.
+    ///
+    /// Unlike [`x11`](Self::x11), the events generated by a
+    /// [`Context`](crate::Context) aren't received. Only key presses and
+    /// releases are received and they don't have characters.
+    pub(crate) fn x11_keys(builder: &ContextBuilder) -> Result<Self, Error> {
+        let (sender, receiver) = mpsc::channel();
+        Ok(Self {
+            receiver,
+            _source: Source::X11Keys(RawReader::open(builder, sender)?),
+        })
+    }
+
+    /// Listen for events from the devices in `/dev/input`.
+    ///
+    /// The virtual device that a context created from the builder would use is
+    /// ignored. This means that events generated by a uinput
+    /// [`Context`](crate::Context) won't be received.
+    pub fn evdev(builder: &ContextBuilder) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let ignore = [builder.device_name_or_default().to_owned()];
+        Ok(Self {
+            receiver,
+            _source: Source::Evdev(EvdevReader::open(&ignore, sender)?),
+        })
+    }
+
+    /// Wait for the next event.
+    ///
+    /// `None` is returned if the listener has stopped, for example, because
+    /// the connection to the X server was lost.
+    pub fn recv(&self) -> Option<TimedInputEvent> {
+        self.receiver.recv().ok()
+    }
+
+    /// Wait for the next event with a timeout.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<TimedInputEvent, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    /// Get the next event if one is available.
+    pub fn try_recv(&self) -> Result<TimedInputEvent, TryRecvError> {
+        self.receiver.try_recv()
+    }
+}
@@ -207,6 +207,18 @@ impl Context {
         })
     }
 
+    /// Get the operations that this context supports.
+    pub fn capabilities(&self) -> crate::Capabilities {
+        crate::Capabilities {
+            keyboard: true,
+            mouse: true,
+            unicode: true,
+            unicode_remapping: true,
+            screen: true,
+            ..Default::default()
+        }
+    }
+
     fn post_event(
         &self,
         event_type: u32,
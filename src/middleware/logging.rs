@@ -0,0 +1,154 @@
+use crate::{traits::*, GenericError};
+use log::Level;
+use std::fmt::{self, Debug};
+
+const TARGET: &str = "tfc";
+
+// Log a call once it has returned. Failed calls are logged as warnings.
+fn logged<T, P>(
+    level: Level,
+    call: fmt::Arguments<'_>,
+    result: Result<T, GenericError<P>>,
+) -> Result<T, GenericError<P>>
+where
+    T: Debug,
+    P: std::error::Error,
+{
+    match &result {
+        Ok(value) => log::log!(target: TARGET, level, "{} = {:?}", call, value),
+        Err(e) => log::warn!(target: TARGET, "{} failed: {}", call, e),
+    }
+    result
+}
+
+// The arguments of a call, formatted like a tuple.
+struct Args<'a>(&'a [&'a dyn Debug]);
+
+impl Debug for Args<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, arg) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A wrapper that logs each call through the [`log`] facade.
+///
+/// The calls that generate events are logged at the level given to
+/// [`level`](Self::level) (`Debug` by default). Queries such as
+/// [`cursor_location`](ScreenContext::cursor_location) are logged at `Trace`.
+/// Failed calls are logged at `Warn`. The target is `tfc`. Subscribers of the
+/// `tracing` crate can receive the records with `tracing-log`.
+///
+/// This requires the `log` feature.
+///
+/// ```no_run
+/// use tfc::{traits::*, Context, LoggingContext};
+///
+/// let mut ctx = LoggingContext::new(Context::new().unwrap());
+/// // Logs "unicode_string(\"Hello\") = ()"
+/// ctx.unicode_string("Hello").unwrap();
+/// ```
+pub struct LoggingContext<C> {
+    inner: C,
+    level: Level,
+}
+
+impl<C> LoggingContext<C> {
+    /// Wrap a context.
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            level: Level::Debug,
+        }
+    }
+
+    /// Set the level that events are logged at.
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Get the inner context.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Get the inner context mutably. Calls made on it aren't logged.
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner context.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: FallibleContext> FallibleContext for LoggingContext<C> {
+    type PlatformError = C::PlatformError;
+}
+
+// A callback for `forward!` that logs the call. Calls that generate events are
+// logged at the configured level and queries are logged at `Trace`.
+macro_rules! log_call {
+    (&$this:ident, capture($rect:expr)) => {{
+        let result = $this.inner.capture($rect);
+        // The pixels aren't worth logging.
+        match &result {
+            Ok(image) => log::trace!(
+                target: TARGET,
+                "capture({:?}) = {}x{} image",
+                $rect,
+                image.width(),
+                image.height()
+            ),
+            Err(e) => log::warn!(target: TARGET, "capture({:?}) failed: {}", $rect, e),
+        }
+        result
+    }};
+    // The matcher can't be printed.
+    (&$this:ident, find_window($title:expr)) => {
+        logged(
+            Level::Trace,
+            format_args!("find_window(..)"),
+            $this.inner.find_window($title),
+        )
+    };
+    (&$this:ident, wait_for_window($title:expr, $timeout:expr)) => {
+        logged(
+            Level::Trace,
+            format_args!("wait_for_window(.., {:?})", $timeout),
+            $this.inner.wait_for_window($title, $timeout),
+        )
+    };
+    (&mut $this:ident, $name:ident($($arg:expr),*)) => {
+        logged(
+            $this.level,
+            format_args!("{}{:?}", stringify!($name), Args(&[$(&$arg),*])),
+            $this.inner.$name($($arg),*),
+        )
+    };
+    (&$this:ident, $name:ident($($arg:expr),*)) => {
+        logged(
+            Level::Trace,
+            format_args!("{}{:?}", stringify!($name), Args(&[$(&$arg),*])),
+            $this.inner.$name($($arg),*),
+        )
+    };
+}
+
+forward!(impl[C: KeyboardContext] KeyboardContext for LoggingContext<C> => log_call);
+forward!(impl[C: UnicodeKeyboardContext] UnicodeKeyboardContext for LoggingContext<C> => log_call);
+forward!(impl[C: MouseContext] MouseContext for LoggingContext<C> => log_call);
+forward!(impl[C: ScreenContext] ScreenContext for LoggingContext<C> => log_call);
+forward!(impl[C: KeyStateContext] KeyStateContext for LoggingContext<C> => log_call);
+forward!(impl[C: LayoutContext] LayoutContext for LoggingContext<C> => log_call);
+forward!(impl[C: ScreenCaptureContext] ScreenCaptureContext for LoggingContext<C> => log_call);
+forward!(impl[C: WindowContext] WindowContext for LoggingContext<C> => log_call);
+forward!(impl[C: ClipboardContext] ClipboardContext for LoggingContext<C> => log_call);
@@ -0,0 +1,11 @@
+// Contexts that wrap other contexts to add some behaviour.
+
+#[cfg(feature = "log")]
+mod logging;
+mod tee;
+mod throttle;
+
+#[cfg(feature = "log")]
+pub use logging::*;
+pub use tee::*;
+pub use throttle::*;
@@ -0,0 +1,146 @@
+use crate::{traits::*, GenericError};
+use std::fmt::{self, Display, Formatter};
+
+/// The platform-specific error of a [`TeeContext`].
+///
+/// This identifies which of the two contexts the error came from.
+#[derive(Debug)]
+pub enum TeeError<A, B> {
+    /// An error from the first context.
+    First(A),
+    /// An error from the second context.
+    Second(B),
+}
+
+impl<A: Display, B: Display> Display for TeeError<A, B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TeeError::First(e) => write!(f, "First context: {}", e),
+            TeeError::Second(e) => write!(f, "Second context: {}", e),
+        }
+    }
+}
+
+impl<A: std::error::Error, B: std::error::Error> std::error::Error for TeeError<A, B> {}
+
+type TeeResult<T, A, B> = Result<
+    T,
+    GenericError<
+        TeeError<<A as FallibleContext>::PlatformError, <B as FallibleContext>::PlatformError>,
+    >,
+>;
+
+/// A wrapper that sends the events to two contexts.
+///
+/// The keyboard and mouse events are generated on the first context and then
+/// on the second. If the first context fails, the event isn't sent to the
+/// second. Everything else, including queries such as
+/// [`cursor_location`](ScreenContext::cursor_location), only uses the first
+/// context.
+///
+/// A common use is to record the events that are generated on a real context.
+///
+/// ```no_run
+/// use tfc::{traits::*, Command, Context, Recorder, TeeContext};
+///
+/// let mut ctx = TeeContext::new(Context::new().unwrap(), Recorder::new());
+/// ctx.unicode_string("Hello").unwrap();
+/// let (_, recorder) = ctx.into_inner();
+/// assert_eq!(recorder.finish(), [Command::UnicodeString("Hello".to_owned())]);
+/// ```
+pub struct TeeContext<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> TeeContext<A, B> {
+    /// Combine two contexts.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get the first context.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Get the second context.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Split into the two contexts.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: FallibleContext, B: FallibleContext> TeeContext<A, B> {
+    // Call a function on both of the contexts.
+    fn both<F, G>(&mut self, f: F, g: G) -> TeeResult<(), A, B>
+    where
+        F: FnOnce(&mut A) -> Result<(), GenericError<A::PlatformError>>,
+        G: FnOnce(&mut B) -> Result<(), GenericError<B::PlatformError>>,
+    {
+        f(&mut self.first).map_err(|e| e.map_platform(TeeError::First))?;
+        g(&mut self.second).map_err(|e| e.map_platform(TeeError::Second))
+    }
+}
+
+fn first<T, A: std::error::Error, B: std::error::Error>(
+    result: Result<T, GenericError<A>>,
+) -> Result<T, GenericError<TeeError<A, B>>> {
+    result.map_err(|e| e.map_platform(TeeError::First))
+}
+
+impl<A: FallibleContext, B: FallibleContext> FallibleContext for TeeContext<A, B> {
+    type PlatformError = TeeError<A::PlatformError, B::PlatformError>;
+}
+
+// A callback for `forward!` that sends an event to both contexts. The state
+// for typing a string is only prepared on the first context because that is
+// where it is read from.
+macro_rules! to_both {
+    (&mut $this:ident, begin_string()) => {
+        first($this.first.begin_string())
+    };
+    (&mut $this:ident, end_string($caps_lock:expr)) => {
+        first($this.first.end_string($caps_lock))
+    };
+    (&mut $this:ident, $name:ident($($arg:expr),*)) => {
+        $this.both(|ctx| ctx.$name($($arg),*), |ctx| ctx.$name($($arg),*))
+    };
+}
+
+// A callback for `forward!` that only uses the first context.
+macro_rules! to_first {
+    (&mut $this:ident, $name:ident($($arg:expr),*)) => {
+        first($this.first.$name($($arg),*))
+    };
+    (&$this:ident, $name:ident($($arg:expr),*)) => {
+        first($this.first.$name($($arg),*))
+    };
+}
+
+forward!(
+    impl[A: KeyboardContext, B: KeyboardContext] KeyboardContext for TeeContext<A, B> => to_both
+);
+forward!(
+    impl[A: UnicodeKeyboardContext, B: UnicodeKeyboardContext] UnicodeKeyboardContext
+        for TeeContext<A, B> => to_both
+);
+forward!(impl[A: MouseContext, B: MouseContext] MouseContext for TeeContext<A, B> => to_both);
+forward!(impl[A: ScreenContext, B: FallibleContext] ScreenContext for TeeContext<A, B> => to_first);
+forward!(
+    impl[A: KeyStateContext, B: FallibleContext] KeyStateContext for TeeContext<A, B> => to_first
+);
+forward!(impl[A: LayoutContext, B: FallibleContext] LayoutContext for TeeContext<A, B> => to_first);
+forward!(
+    impl[A: ScreenCaptureContext, B: FallibleContext] ScreenCaptureContext
+        for TeeContext<A, B> => to_first
+);
+forward!(impl[A: WindowContext, B: FallibleContext] WindowContext for TeeContext<A, B> => to_first);
+forward!(
+    impl[A: ClipboardContext, B: FallibleContext] ClipboardContext
+        for TeeContext<A, B> => to_first
+);
@@ -0,0 +1,100 @@
+use crate::traits::*;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A wrapper that adds a minimum delay between the calls that generate events.
+///
+/// Before each keyboard or mouse call is forwarded, the current thread sleeps
+/// until at least the delay has passed since the previous one. A call that
+/// generates several events, such as
+/// [`unicode_string`](UnicodeKeyboardContext::unicode_string), is forwarded
+/// as a single call so its events aren't delayed from each other. The other
+/// calls are forwarded immediately.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tfc::{traits::*, Context, Key, ThrottleContext};
+///
+/// let ctx = Context::new().unwrap();
+/// let mut ctx = ThrottleContext::new(ctx, Duration::from_millis(50));
+/// // Takes at least 100 milliseconds.
+/// ctx.key_click(Key::A).unwrap();
+/// ctx.key_click(Key::B).unwrap();
+/// ctx.key_click(Key::C).unwrap();
+/// ```
+pub struct ThrottleContext<C> {
+    inner: C,
+    delay: Duration,
+    last_event: Option<Instant>,
+}
+
+impl<C> ThrottleContext<C> {
+    /// Wrap a context.
+    pub fn new(inner: C, delay: Duration) -> Self {
+        Self {
+            inner,
+            delay,
+            last_event: None,
+        }
+    }
+
+    /// Change the minimum delay between events.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// Get the inner context.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Get the inner context mutably. Calls made on it aren't delayed.
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner context.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    fn throttle<T, F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut C) -> T,
+    {
+        if let Some(last_event) = self.last_event {
+            let elapsed = last_event.elapsed();
+            if elapsed < self.delay {
+                thread::sleep(self.delay - elapsed);
+            }
+        }
+        let result = f(&mut self.inner);
+        self.last_event = Some(Instant::now());
+        result
+    }
+}
+
+impl<C: FallibleContext> FallibleContext for ThrottleContext<C> {
+    type PlatformError = C::PlatformError;
+}
+
+// A callback for `forward!` that delays the call.
+macro_rules! throttled {
+    (&mut $this:ident, $name:ident($($arg:expr),*)) => {
+        $this.throttle(|ctx| ctx.$name($($arg),*))
+    };
+}
+
+forward!(impl[C: KeyboardContext] KeyboardContext for ThrottleContext<C> => throttled);
+forward!(
+    impl[C: UnicodeKeyboardContext] UnicodeKeyboardContext for ThrottleContext<C> => throttled
+);
+forward!(impl[C: MouseContext] MouseContext for ThrottleContext<C> => throttled);
+forward!(impl[C: ScreenContext] ScreenContext for ThrottleContext<C> => to_inner);
+forward!(impl[C: KeyStateContext] KeyStateContext for ThrottleContext<C> => to_inner);
+forward!(impl[C: LayoutContext] LayoutContext for ThrottleContext<C> => to_inner);
+forward!(impl[C: ScreenCaptureContext] ScreenCaptureContext for ThrottleContext<C> => to_inner);
+forward!(impl[C: WindowContext] WindowContext for ThrottleContext<C> => to_inner);
+forward!(impl[C: ClipboardContext] ClipboardContext for ThrottleContext<C> => to_inner);
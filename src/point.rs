@@ -0,0 +1,114 @@
+use crate::{Rect, WindowInfo};
+
+/// The coordinate space that a [`Point`] is in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CoordinateSpace {
+    /// Pixels with the origin at the top-left of the screen.
+    Pixels,
+    /// Fractions of the size of the screen. (0.0, 0.0) is the top-left pixel
+    /// and (1.0, 1.0) is the bottom-right pixel.
+    Normalized,
+    /// Fractions of the size of a region of the screen, such as one of the
+    /// [`monitors`](crate::ScreenContext::monitors).
+    NormalizedIn(Rect),
+    /// Pixels with the origin at the top-left of a window. This is the
+    /// [`rect`](WindowInfo::rect) of the window.
+    Window(Rect),
+}
+
+/// A position on the screen in a [`CoordinateSpace`].
+///
+/// Use [`ScreenContext::to_pixels`](crate::ScreenContext::to_pixels) to get
+/// the pixel coordinates of a point. Normalized points are independent of the
+/// screen resolution so scripts that use them work on any screen. Pixels are in
+/// the same units as [`screen_size`](crate::ScreenContext::screen_size) on
+/// every platform so normalized points don't depend on the display scale
+/// either.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    pub space: CoordinateSpace,
+}
+
+// Map a fraction onto the pixels of a length.
+fn denormalize(fraction: f64, length: u32) -> i32 {
+    (fraction * length.saturating_sub(1) as f64).round() as i32
+}
+
+fn normalize(pixels: i32, length: u32) -> f64 {
+    if length <= 1 {
+        0.0
+    } else {
+        pixels as f64 / (length - 1) as f64
+    }
+}
+
+impl Point {
+    /// Create a point in pixels relative to the top-left of the screen.
+    pub fn pixels(x: i32, y: i32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            space: CoordinateSpace::Pixels,
+        }
+    }
+
+    /// Create a point in fractions of the size of the screen.
+    pub fn normalized(x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            space: CoordinateSpace::Normalized,
+        }
+    }
+
+    /// Create a point in fractions of the size of a region of the screen.
+    pub fn normalized_in(rect: Rect, x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            space: CoordinateSpace::NormalizedIn(rect),
+        }
+    }
+
+    /// Create a point in pixels relative to the top-left of a window.
+    pub fn in_window(window: &WindowInfo, x: i32, y: i32) -> Self {
+        Self {
+            x: x as f64,
+            y: y as f64,
+            space: CoordinateSpace::Window(window.rect),
+        }
+    }
+
+    /// Get the pixel coordinates of the point on a screen of the given size.
+    ///
+    /// The screen size is only used by
+    /// [`Normalized`](CoordinateSpace::Normalized) points.
+    pub fn to_pixels(&self, screen_size: (i32, i32)) -> (i32, i32) {
+        match self.space {
+            CoordinateSpace::Pixels => (self.x.round() as i32, self.y.round() as i32),
+            CoordinateSpace::Normalized => (
+                denormalize(self.x, screen_size.0.max(0) as u32),
+                denormalize(self.y, screen_size.1.max(0) as u32),
+            ),
+            CoordinateSpace::NormalizedIn(rect) => (
+                rect.x + denormalize(self.x, rect.width),
+                rect.y + denormalize(self.y, rect.height),
+            ),
+            CoordinateSpace::Window(rect) => (
+                rect.x + self.x.round() as i32,
+                rect.y + self.y.round() as i32,
+            ),
+        }
+    }
+
+    /// Convert pixel coordinates to a normalized point on a screen of the given
+    /// size.
+    pub fn from_pixels(x: i32, y: i32, screen_size: (i32, i32)) -> Self {
+        Self::normalized(
+            normalize(x, screen_size.0.max(0) as u32),
+            normalize(y, screen_size.1.max(0) as u32),
+        )
+    }
+}
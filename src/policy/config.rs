@@ -0,0 +1,119 @@
+use super::Policy;
+use crate::{CommandCode, Enum, Hotkey, Key, Rect};
+use serde::Deserialize;
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    path::Path,
+    str::FromStr,
+};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPolicy {
+    #[serde(default)]
+    denied_keys: Vec<String>,
+    #[serde(default)]
+    denied_hotkeys: Vec<String>,
+    confine: Option<RawRect>,
+    max_text_length: Option<usize>,
+    max_commands_per_second: Option<u32>,
+    allowed_commands: Option<Vec<String>>,
+}
+
+/// The error returned when a [`Policy`] can't be loaded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PolicyParseError(String);
+
+impl Display for PolicyParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid policy: {}", self.0)
+    }
+}
+
+impl std::error::Error for PolicyParseError {}
+
+fn parse_enum<E: Enum>(kind: &str, name: &str) -> Result<E, PolicyParseError> {
+    E::iter()
+        .find(|e| e.identifier_name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| PolicyParseError(format!("Unknown {} \"{}\"", kind, name)))
+}
+
+impl Policy {
+    /// Load a policy from a TOML file.
+    ///
+    /// See [`from_str`](Self::from_str) for the format.
+    pub fn load(path: &Path) -> Result<Self, PolicyParseError> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| PolicyParseError(format!("{}: {}", path.display(), e)))?;
+        source.parse()
+    }
+}
+
+impl FromStr for Policy {
+    type Err = PolicyParseError;
+
+    /// Parse a policy from TOML.
+    ///
+    /// Keys and commands are named by their variants and hotkeys are written
+    /// the same way as for [`Hotkey`]. Names are case-insensitive. All of the
+    /// fields are optional.
+    ///
+    /// ```
+    /// use tfc::{Key, Policy, Rect};
+    ///
+    /// let policy: Policy = r#"
+    ///     denied_keys = ["capslock"]
+    ///     denied_hotkeys = ["ctrl+alt+deleteorbackspace", "meta+l"]
+    ///     confine = { x = 0, y = 0, width = 1920, height = 1080 }
+    ///     max_text_length = 1000
+    ///     max_commands_per_second = 200
+    ///     allowed_commands = ["KeyClick", "MouseMoveRel", "MouseClick", "UnicodeString"]
+    /// "#.parse().unwrap();
+    ///
+    /// assert_eq!(policy.denied_keys, [Key::CapsLock]);
+    /// assert_eq!(policy.confine, Some(Rect::new(0, 0, 1920, 1080)));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw: RawPolicy = toml::from_str(s).map_err(|e| PolicyParseError(e.to_string()))?;
+
+        let denied_keys = raw
+            .denied_keys
+            .iter()
+            .map(|name| parse_enum::<Key>("key", name))
+            .collect::<Result<_, _>>()?;
+        let denied_hotkeys = raw
+            .denied_hotkeys
+            .iter()
+            .map(|hotkey| hotkey.parse::<Hotkey>())
+            .collect::<Result<_, _>>()
+            .map_err(|e| PolicyParseError(e.to_string()))?;
+        let allowed_commands = match raw.allowed_commands {
+            Some(names) => Some(
+                names
+                    .iter()
+                    .map(|name| parse_enum::<CommandCode>("command", name))
+                    .collect::<Result<_, _>>()?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            denied_keys,
+            denied_hotkeys,
+            confine: raw.confine.map(|r| Rect::new(r.x, r.y, r.width, r.height)),
+            max_text_length: raw.max_text_length,
+            max_commands_per_second: raw.max_commands_per_second,
+            allowed_commands,
+        })
+    }
+}
@@ -0,0 +1,383 @@
+#[cfg(feature = "config")]
+mod config;
+
+use crate::{
+    traits::{key_from_ascii, *},
+    Command, CommandCode, Enum, GenericError, Hotkey, Key, Modifiers, MouseButton, Rect, Selection,
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "config")]
+pub use config::PolicyParseError;
+
+/// The rules enforced by a [`PolicyContext`].
+///
+/// The default policy doesn't restrict anything. With the `config` feature, a
+/// policy can be loaded from a TOML file with `Policy::load`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Policy {
+    /// Keys that may not be pressed.
+    pub denied_keys: Vec<Key>,
+    /// Shortcuts that may not be pressed, such as `ctrl+alt+deleteorbackspace`
+    /// or `meta+l`.
+    ///
+    /// A shortcut is denied when its key is pressed while at least its
+    /// modifiers are held.
+    pub denied_hotkeys: Vec<Hotkey>,
+    /// The area of the screen that the mouse must stay within.
+    ///
+    /// Absolute movements outside of the area are denied. Relative movements
+    /// are clamped to the edge of the area. Pressing, releasing and scrolling
+    /// are denied while the cursor is outside of the area.
+    pub confine: Option<Rect>,
+    /// The maximum number of characters in a string that is typed or put into
+    /// the clipboard.
+    pub max_text_length: Option<usize>,
+    /// The maximum number of commands that may be checked within a second.
+    pub max_commands_per_second: Option<u32>,
+    /// The commands that may be executed. All commands are allowed if this is
+    /// `None`.
+    pub allowed_commands: Option<Vec<CommandCode>>,
+}
+
+impl Policy {
+    /// Whether commands with the given code may be executed.
+    pub fn allows_command(&self, code: CommandCode) -> bool {
+        match &self.allowed_commands {
+            Some(allowed) => allowed.contains(&code),
+            None => true,
+        }
+    }
+}
+
+// Check whether the held modifiers include all of the required modifiers.
+fn includes(held: Modifiers, required: Modifiers) -> bool {
+    (held.control || !required.control)
+        && (held.shift || !required.shift)
+        && (held.alt || !required.alt)
+        && (held.meta || !required.meta)
+}
+
+/// A wrapper that restricts what a context may do.
+///
+/// The wrapper implements the same traits as the inner context and forwards
+/// the calls that the [`Policy`] allows. The others return
+/// [`Denied`](GenericError::Denied). The rules that apply to whole commands
+/// (the allowed commands and the rate limit) are enforced by
+/// [`check_command`](Self::check_command). Executing commands with
+/// [`Executor::execute_policy`](crate::Executor::execute_policy) or
+/// [`Executor::execute_extended_policy`](crate::Executor::execute_extended_policy)
+/// checks each command before it is executed.
+///
+/// Keyboard shortcuts are detected by tracking the keys that are pressed
+/// through the wrapper. A modifier is held while either its left or right key
+/// is pressed. Characters typed with
+/// [`UnicodeKeyboardContext`] are checked as the keys that produce them on a
+/// US layout.
+///
+/// ```no_run
+/// use tfc::{traits::*, Command, Context, Executor, GenericError, Policy, PolicyContext, Rect};
+///
+/// let policy = Policy {
+///     denied_hotkeys: vec!["meta+l".parse().unwrap()],
+///     confine: Some(Rect::new(0, 0, 800, 600)),
+///     ..Default::default()
+/// };
+/// let mut ctx = PolicyContext::new(Context::new().unwrap(), policy);
+///
+/// ctx.key_down(tfc::Key::Meta).unwrap();
+/// assert!(matches!(ctx.key_click(tfc::Key::L), Err(GenericError::Denied(_))));
+///
+/// let commands = [Command::MouseMoveAbs(1000, 100)];
+/// let mut executor = Executor::new();
+/// assert!(executor.execute_policy(&mut ctx, &commands).is_err());
+/// ```
+pub struct PolicyContext<C> {
+    inner: C,
+    policy: Policy,
+    pressed: HashSet<Key>,
+    recent: VecDeque<Instant>,
+}
+
+impl<C> PolicyContext<C> {
+    /// Wrap a context.
+    pub fn new(inner: C, policy: Policy) -> Self {
+        Self {
+            inner,
+            policy,
+            pressed: HashSet::new(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Get the policy that is being enforced.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Get the inner context.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Unwrap the inner context.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: FallibleContext> PolicyContext<C> {
+    fn held_modifiers(&self) -> Modifiers {
+        let mut modifiers = Modifiers::default();
+        for key in self.pressed.iter() {
+            modifiers.update(*key, true);
+        }
+        modifiers
+    }
+
+    fn check_key(&self, key: Key, shift: bool) -> Result<(), GenericError<C::PlatformError>> {
+        if self.policy.denied_keys.contains(&key) {
+            return Err(GenericError::Denied(format!(
+                "Key \"{}\" is denied",
+                key.identifier_name().to_ascii_lowercase()
+            )));
+        }
+        let mut held = self.held_modifiers();
+        held.shift |= shift;
+        match self
+            .policy
+            .denied_hotkeys
+            .iter()
+            .find(|hotkey| hotkey.key == key && includes(held, hotkey.modifiers))
+        {
+            Some(hotkey) => Err(GenericError::Denied(format!(
+                "Shortcut \"{}\" is denied",
+                hotkey
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn check_char(&self, ch: char) -> Result<(), GenericError<C::PlatformError>> {
+        if !ch.is_ascii() {
+            return Ok(());
+        }
+        match key_from_ascii(ch as u8) {
+            Some((key, shift)) => self.check_key(key, shift),
+            None => Ok(()),
+        }
+    }
+
+    fn check_text_length(&self, length: usize) -> Result<(), GenericError<C::PlatformError>> {
+        match self.policy.max_text_length {
+            Some(max) if length > max => Err(GenericError::Denied(format!(
+                "Text is longer than {} characters",
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check whether a command is allowed before executing it.
+    ///
+    /// This checks the allowed commands, the rate limit and the length of
+    /// strings. Commands that are denied for another reason still count
+    /// towards the rate limit. The keys and mouse movements are checked when
+    /// the command is executed on the wrapper.
+    pub fn check_command(
+        &mut self,
+        command: &Command,
+    ) -> Result<(), GenericError<C::PlatformError>> {
+        if let Some(max) = self.policy.max_commands_per_second {
+            let now = Instant::now();
+            while let Some(time) = self.recent.front() {
+                if now.duration_since(*time) < Duration::from_secs(1) {
+                    break;
+                }
+                self.recent.pop_front();
+            }
+            if self.recent.len() >= max as usize {
+                return Err(GenericError::Denied(format!(
+                    "More than {} commands per second",
+                    max
+                )));
+            }
+            self.recent.push_back(now);
+        }
+
+        let code = command.code();
+        if !self.policy.allows_command(code) {
+            return Err(GenericError::Denied(format!(
+                "Command \"{}\" is not allowed",
+                code.display_name()
+            )));
+        }
+
+        match command {
+            Command::AsciiString(s) => self.check_text_length(s.len()),
+            Command::UnicodeString(s) | Command::SetClipboard(s) => {
+                self.check_text_length(s.chars().count())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<C: ScreenContext> PolicyContext<C> {
+    // The buttons and the wheel act wherever the cursor is, which may be
+    // outside of the area if the user moved it there.
+    fn check_cursor(&self) -> Result<(), GenericError<C::PlatformError>> {
+        if let Some(rect) = self.policy.confine {
+            let (x, y) = self.inner.cursor_location()?;
+            if !rect.contains(x, y) {
+                return Err(GenericError::Denied(format!(
+                    "The cursor at ({}, {}) is outside of the confinement area",
+                    x, y
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: FallibleContext> FallibleContext for PolicyContext<C> {
+    type PlatformError = C::PlatformError;
+}
+
+impl<C: KeyboardContext> KeyboardContext for PolicyContext<C> {
+    fn key_down(&mut self, key: Key) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_key(key, false)?;
+        self.inner.key_down(key)?;
+        self.pressed.insert(key);
+        Ok(())
+    }
+
+    fn key_up(&mut self, key: Key) -> Result<(), GenericError<Self::PlatformError>> {
+        self.inner.key_up(key)?;
+        self.pressed.remove(&key);
+        Ok(())
+    }
+
+    // The modifiers that are released by the inner context stay in the
+    // pressed keys. It can't be known whether they were released so assuming
+    // that they are still held is the safe choice.
+    fn begin_string(&mut self) -> Result<bool, GenericError<Self::PlatformError>> {
+        self.inner.begin_string()
+    }
+
+    fn end_string(&mut self, caps_lock: bool) -> Result<(), GenericError<Self::PlatformError>> {
+        self.inner.end_string(caps_lock)
+    }
+}
+
+impl<C: UnicodeKeyboardContext> UnicodeKeyboardContext for PolicyContext<C> {
+    fn unicode_char_down(&mut self, ch: char) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_char(ch)?;
+        self.inner.unicode_char_down(ch)
+    }
+
+    fn unicode_char_up(&mut self, ch: char) -> Result<(), GenericError<Self::PlatformError>> {
+        self.inner.unicode_char_up(ch)
+    }
+
+    fn unicode_char(&mut self, ch: char) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_char(ch)?;
+        self.inner.unicode_char(ch)
+    }
+
+    fn unicode_string(&mut self, s: &str) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_text_length(s.chars().count())?;
+        for ch in s.chars() {
+            self.check_char(ch)?;
+        }
+        self.inner.unicode_string(s)
+    }
+}
+
+impl<C: MouseContext + ScreenContext> MouseContext for PolicyContext<C> {
+    fn mouse_move_rel(
+        &mut self,
+        dx: i32,
+        dy: i32,
+    ) -> Result<(), GenericError<Self::PlatformError>> {
+        let rect = match self.policy.confine {
+            Some(rect) => rect,
+            None => return self.inner.mouse_move_rel(dx, dy),
+        };
+        let (x, y) = self.inner.cursor_location()?;
+        let right = rect.x + (rect.width as i32 - 1).max(0);
+        let bottom = rect.y + (rect.height as i32 - 1).max(0);
+        let target_x = (x + dx).max(rect.x).min(right);
+        let target_y = (y + dy).max(rect.y).min(bottom);
+        self.inner.mouse_move_rel(target_x - x, target_y - y)
+    }
+
+    fn mouse_move_abs(&mut self, x: i32, y: i32) -> Result<(), GenericError<Self::PlatformError>> {
+        if let Some(rect) = self.policy.confine {
+            if !rect.contains(x, y) {
+                return Err(GenericError::Denied(format!(
+                    "({}, {}) is outside of the confinement area",
+                    x, y
+                )));
+            }
+        }
+        self.inner.mouse_move_abs(x, y)
+    }
+
+    fn mouse_scroll(&mut self, dx: i32, dy: i32) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_cursor()?;
+        self.inner.mouse_scroll(dx, dy)
+    }
+
+    fn mouse_down(&mut self, button: MouseButton) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_cursor()?;
+        self.inner.mouse_down(button)
+    }
+
+    fn mouse_up(&mut self, button: MouseButton) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_cursor()?;
+        self.inner.mouse_up(button)
+    }
+
+    fn mouse_click(
+        &mut self,
+        button: MouseButton,
+    ) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_cursor()?;
+        self.inner.mouse_click(button)
+    }
+}
+
+forward!(impl[C: ScreenContext] ScreenContext for PolicyContext<C> => to_inner);
+forward!(impl[C: KeyStateContext] KeyStateContext for PolicyContext<C> => to_inner);
+forward!(impl[C: LayoutContext] LayoutContext for PolicyContext<C> => to_inner);
+forward!(impl[C: ScreenCaptureContext] ScreenCaptureContext for PolicyContext<C> => to_inner);
+forward!(impl[C: WindowContext] WindowContext for PolicyContext<C> => to_inner);
+
+impl<C: ClipboardContext> ClipboardContext for PolicyContext<C> {
+    fn clipboard_text(
+        &self,
+        selection: Selection,
+    ) -> Result<Option<String>, GenericError<Self::PlatformError>> {
+        self.inner.clipboard_text(selection)
+    }
+
+    fn set_clipboard_text(
+        &mut self,
+        selection: Selection,
+        text: &str,
+    ) -> Result<(), GenericError<Self::PlatformError>> {
+        self.check_text_length(text.chars().count())?;
+        self.inner.set_clipboard_text(selection, text)
+    }
+
+    fn clear_clipboard(
+        &mut self,
+        selection: Selection,
+    ) -> Result<(), GenericError<Self::PlatformError>> {
+        self.inner.clear_clipboard(selection)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{traits::*, Command, GenericError, InputEvent, Key, MouseButton, TimedInputEvent};
+use std::{
+    convert::Infallible,
+    time::{Duration, Instant},
+};
+
+/// Converts a stream of input events into a sequence of [`Command`]s.
+///
+/// The gaps between events are preserved with [`Command::Delay`] so that
+/// executing the commands reproduces the original timing.
+///
+/// The recorder is also a context. The calls that are made on it are recorded
+/// as commands at the time that they are made. This can be combined with a
+/// [`TeeContext`](crate::TeeContext) to record the events that are generated
+/// on another context.
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use tfc::{Command, InputEvent, Key, Recorder, TimedInputEvent};
+///
+/// let start = Instant::now();
+/// let mut recorder = Recorder::new();
+/// recorder.record(&TimedInputEvent {
+///     event: InputEvent::KeyDown(Key::A),
+///     ch: Some('a'),
+///     time: start,
+/// });
+/// recorder.record(&TimedInputEvent {
+///     event: InputEvent::KeyUp(Key::A),
+///     ch: None,
+///     time: start + Duration::from_millis(50),
+/// });
+///
+/// assert_eq!(recorder.finish(), vec![
+///     Command::KeyDown(Key::A),
+///     Command::Delay(50),
+///     Command::KeyUp(Key::A),
+/// ]);
+/// ```
+#[derive(Debug)]
+pub struct Recorder {
+    commands: Vec<Command>,
+    last_time: Option<Instant>,
+    min_delay: Duration,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            last_time: None,
+            min_delay: Duration::from_millis(1),
+        }
+    }
+
+    /// Set the shortest gap between events that is recorded as a delay.
+    ///
+    /// Shorter gaps are carried over to the next event so that the total
+    /// duration of the recording is preserved. The default is 1 millisecond.
+    pub fn min_delay(mut self, delay: Duration) -> Self {
+        self.min_delay = delay;
+        self
+    }
+
+    /// Append an event to the recording.
+    pub fn record(&mut self, event: &TimedInputEvent) {
+        let command = match event.event {
+            InputEvent::KeyDown(key) => Command::KeyDown(key),
+            InputEvent::KeyUp(key) => Command::KeyUp(key),
+            InputEvent::MouseDown(button) => Command::MouseDown(button),
+            InputEvent::MouseUp(button) => Command::MouseUp(button),
+            InputEvent::MouseMoveRel(dx, dy) => Command::MouseMoveRel(dx, dy),
+            InputEvent::MouseMoveAbs(x, y) => Command::MouseMoveAbs(x, y),
+            InputEvent::MouseScroll(dx, dy) => Command::MouseScroll(dx, dy),
+        };
+        self.record_command(command, event.time);
+    }
+
+    /// Append a command to the recording.
+    ///
+    /// The time is used to insert a delay before the command in the same way
+    /// as [`record`](Self::record).
+    pub fn record_command(&mut self, command: Command, time: Instant) {
+        if let Some(last_time) = self.last_time {
+            let gap = time.saturating_duration_since(last_time);
+            if gap < self.min_delay {
+                self.commands.push(command);
+                return;
+            }
+            let millis = gap.as_millis().min(u32::MAX as u128) as u32;
+            self.commands.push(Command::Delay(millis));
+            self.last_time = Some(last_time + Duration::from_millis(millis as u64));
+        } else {
+            self.last_time = Some(time);
+        }
+        self.commands.push(command);
+    }
+
+    /// The commands that have been recorded so far.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Finish recording and return the commands.
+    pub fn finish(self) -> Vec<Command> {
+        self.commands
+    }
+
+    fn push(&mut self, command: Command) -> Result<(), GenericError<Infallible>> {
+        self.record_command(command, Instant::now());
+        Ok(())
+    }
+}
+
+impl FallibleContext for Recorder {
+    type PlatformError = Infallible;
+}
+
+impl KeyboardContext for Recorder {
+    fn key_down(&mut self, key: Key) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::KeyDown(key))
+    }
+
+    fn key_up(&mut self, key: Key) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::KeyUp(key))
+    }
+
+    fn key_click(&mut self, key: Key) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::KeyClick(key))
+    }
+}
+
+impl UnicodeKeyboardContext for Recorder {
+    fn unicode_char_down(&mut self, ch: char) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::UnicodeCharDown(ch))
+    }
+
+    fn unicode_char_up(&mut self, ch: char) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::UnicodeCharUp(ch))
+    }
+
+    fn unicode_char(&mut self, ch: char) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::UnicodeChar(ch))
+    }
+
+    fn unicode_string(&mut self, s: &str) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::UnicodeString(s.to_owned()))
+    }
+}
+
+// The recorder doesn't have a screen. The trait is implemented so that the
+// recorder can be used as a DynContext.
+impl ScreenContext for Recorder {
+    fn cursor_location(&self) -> Result<(i32, i32), GenericError<Self::PlatformError>> {
+        Err(GenericError::Info("The recorder doesn't have a screen".into()))
+    }
+
+    fn screen_size(&self) -> Result<(i32, i32), GenericError<Self::PlatformError>> {
+        Err(GenericError::Info("The recorder doesn't have a screen".into()))
+    }
+}
+
+impl MouseContext for Recorder {
+    fn mouse_move_rel(
+        &mut self,
+        dx: i32,
+        dy: i32,
+    ) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::MouseMoveRel(dx, dy))
+    }
+
+    fn mouse_move_abs(&mut self, x: i32, y: i32) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::MouseMoveAbs(x, y))
+    }
+
+    fn mouse_scroll(&mut self, dx: i32, dy: i32) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::MouseScroll(dx, dy))
+    }
+
+    fn mouse_down(&mut self, button: MouseButton) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::MouseDown(button))
+    }
+
+    fn mouse_up(&mut self, button: MouseButton) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::MouseUp(button))
+    }
+
+    fn mouse_click(
+        &mut self,
+        button: MouseButton,
+    ) -> Result<(), GenericError<Self::PlatformError>> {
+        self.push(Command::MouseClick(button))
+    }
+}
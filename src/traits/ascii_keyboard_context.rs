@@ -213,9 +213,40 @@ impl KeyShift {
     }
 }
 
+/// Check whether an ASCII character is supported by [`AsciiKeyboardContext`].
+pub(crate) fn is_supported_ascii(ch: u8) -> bool {
+    KeyShift::from_ascii(ch) != KeyShift::NONE
+}
+
+/// Find the key that produces an ASCII character on a US layout and whether
+/// Shift needs to be held.
+pub(crate) fn key_from_ascii(ch: u8) -> Option<(Key, bool)> {
+    let key_shift = KeyShift::from_ascii(ch);
+    if key_shift == KeyShift::NONE {
+        None
+    } else {
+        Some((key_shift.key(), key_shift.shift()))
+    }
+}
+
+/// Find the ASCII character that a key produces on a US layout.
+///
+/// This is the inverse of the mapping used by [`AsciiKeyboardContext`].
+/// Backspace, Delete and Escape are not considered characters.
+pub(crate) fn ascii_from_key(key: Key, shift: bool) -> Option<u8> {
+    let key_shift = if shift {
+        KeyShift::new_shift(key)
+    } else {
+        KeyShift::new(key)
+    };
+    (b' '..=b'~')
+        .chain([b'\t', b'\n'].iter().copied())
+        .find(|ch| KeyShift::from_ascii(*ch) == key_shift)
+}
+
 fn apply<C>(ctx: &mut C, key_shift: KeyShift) -> Result<(), GenericError<C::PlatformError>>
 where
-    C: KeyboardContext + FallibleContext,
+    C: ?Sized + KeyboardContext + FallibleContext,
 {
     if key_shift.shift() {
         ctx.key_down(Key::Shift)?;
@@ -226,7 +257,7 @@ where
     }
 }
 
-impl<C: KeyboardContext + FallibleContext> AsciiKeyboardContext for C {
+impl<C: ?Sized + KeyboardContext + FallibleContext> AsciiKeyboardContext for C {
     fn ascii_char_down(&mut self, ch: u8) -> Result<(), GenericError<Self::PlatformError>> {
         let key_shift = KeyShift::from_ascii(ch);
         if key_shift == KeyShift::NONE {
@@ -261,15 +292,16 @@ impl<C: KeyboardContext + FallibleContext> AsciiKeyboardContext for C {
 
     fn ascii_string(&mut self, s: &[u8]) -> Result<(), GenericError<Self::PlatformError>> {
         for ch in s.iter() {
-            if KeyShift::from_ascii(*ch) == KeyShift::NONE {
+            if !is_supported_ascii(*ch) {
                 return Err(GenericError::UnsupportedAscii(*ch));
             }
         }
 
-        for ch in s.iter() {
-            apply(self, KeyShift::from_ascii(*ch))?;
-        }
-
-        Ok(())
+        let caps_lock = self.begin_string()?;
+        let result = s
+            .iter()
+            .try_for_each(|ch| apply(self, KeyShift::from_ascii(*ch)));
+        let restored = self.end_string(caps_lock);
+        result.and(restored)
     }
 }
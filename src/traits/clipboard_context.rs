@@ -0,0 +1,79 @@
+use super::{FallibleContext, KeyboardContext};
+use crate::{GenericError, Key, Selection};
+use std::time::Duration;
+
+// How long the application is given to request the clipboard contents after
+// Ctrl+V is pressed before the previous contents are restored.
+const PASTE_DELAY: Duration = Duration::from_millis(100);
+
+/// A context that supports reading and writing the clipboard.
+///
+/// # Platform Differences
+///
+/// This trait is only implemented for Linux-X11. X11 doesn't store the
+/// contents of selections. Instead, the contents are served to other
+/// applications from a helper thread for as long as the context lives or until
+/// another application takes the selection.
+pub trait ClipboardContext: FallibleContext {
+    /// Get the text in a selection.
+    ///
+    /// `None` is returned if the selection is empty or doesn't contain text.
+    fn clipboard_text(
+        &self,
+        selection: Selection,
+    ) -> Result<Option<String>, GenericError<Self::PlatformError>>;
+
+    /// Put text into a selection.
+    ///
+    /// Returns [`Info`](GenericError::Info) if the text is too long for the
+    /// selection. On Linux-X11, the limit is the maximum request size of the
+    /// server (usually 256 KiB).
+    fn set_clipboard_text(
+        &mut self,
+        selection: Selection,
+        text: &str,
+    ) -> Result<(), GenericError<Self::PlatformError>>;
+
+    /// Empty a selection that was set by this context.
+    ///
+    /// Nothing happens if another application has since taken the selection.
+    fn clear_clipboard(
+        &mut self,
+        selection: Selection,
+    ) -> Result<(), GenericError<Self::PlatformError>>;
+
+    /// Type a string by pasting it from the clipboard.
+    ///
+    /// The text is put into the clipboard and Ctrl+V is pressed. The previous
+    /// contents of the clipboard are then restored, or the clipboard is
+    /// emptied if it was empty. This is much faster than
+    /// [`unicode_string`](crate::UnicodeKeyboardContext::unicode_string) for
+    /// long strings but it only works in applications that paste with Ctrl+V.
+    /// Terminals usually paste with Ctrl+Shift+V.
+    ///
+    /// Nothing is pasted if the text is too long for the clipboard. See
+    /// [`set_clipboard_text`](Self::set_clipboard_text).
+    fn paste_string(&mut self, s: &str) -> Result<(), GenericError<Self::PlatformError>>
+    where
+        Self: KeyboardContext,
+    {
+        let previous = self.clipboard_text(Selection::Clipboard)?;
+        self.set_clipboard_text(Selection::Clipboard, s)?;
+        // Control is released even if pressing V fails.
+        let pasted = self.key_down(Key::Control).and_then(|_| {
+            let result = self.key_click(Key::V);
+            let released = self.key_up(Key::Control);
+            result.and(released)
+        });
+        if pasted.is_ok() {
+            // The application requests the contents after it receives the key
+            // press so there is no way to know when it has finished pasting.
+            std::thread::sleep(PASTE_DELAY);
+        }
+        let restored = match previous {
+            Some(previous) => self.set_clipboard_text(Selection::Clipboard, &previous),
+            None => self.clear_clipboard(Selection::Clipboard),
+        };
+        pasted.and(restored)
+    }
+}
@@ -0,0 +1,98 @@
+use super::{FallibleContext, KeyboardContext};
+use crate::{GenericError, Key, Modifiers};
+
+const MODIFIER_KEYS: [Key; 8] = [
+    Key::Control,
+    Key::RightControl,
+    Key::Shift,
+    Key::RightShift,
+    Key::Alt,
+    Key::RightAlt,
+    Key::Meta,
+    Key::RightMeta,
+];
+
+/// A context that supports querying the state of the keyboard.
+///
+/// The state includes keys held down by the user as well as those pressed by
+/// the context.
+///
+/// # Platform Differences
+///
+/// This trait is only implemented for Linux. On Linux-X11, the state is
+/// queried from the X server. On Linux-Wayland, the pressed keys are queried
+/// from the devices in `/dev/input` which requires permission to read them
+/// (usually by being in the `input` group). The lock state is read from the
+/// LEDs of the virtual device which are set by the compositor.
+pub trait KeyStateContext: FallibleContext {
+    /// Check whether a key is currently held down.
+    fn is_key_pressed(&self, key: Key) -> Result<bool, GenericError<Self::PlatformError>>;
+
+    /// Get the modifier keys that are currently held down.
+    ///
+    /// The left and right variants of a modifier are not distinguished.
+    fn modifier_state(&self) -> Result<Modifiers, GenericError<Self::PlatformError>> {
+        let mut modifiers = Modifiers::default();
+        for key in MODIFIER_KEYS.iter() {
+            if self.is_key_pressed(*key)? {
+                modifiers.update(*key, true);
+            }
+        }
+        Ok(modifiers)
+    }
+
+    /// Check whether Caps Lock is on.
+    fn caps_lock(&self) -> Result<bool, GenericError<Self::PlatformError>>;
+
+    /// Check whether Num Lock is on.
+    fn num_lock(&self) -> Result<bool, GenericError<Self::PlatformError>>;
+
+    /// Release the modifiers that are held down and turn off Caps Lock.
+    ///
+    /// Returns whether Caps Lock was on.
+    fn normalize_state(&mut self) -> Result<bool, GenericError<Self::PlatformError>>
+    where
+        Self: KeyboardContext,
+    {
+        for key in MODIFIER_KEYS.iter() {
+            if self.is_key_pressed(*key)? {
+                self.key_up(*key)?;
+            }
+        }
+        let caps_lock = self.caps_lock()?;
+        if caps_lock {
+            self.key_click(Key::CapsLock)?;
+        }
+        Ok(caps_lock)
+    }
+
+    /// Call a function with Caps Lock off and no modifiers held down.
+    ///
+    /// Typing a string while the user is holding Shift or while Caps Lock is
+    /// on will produce the wrong characters. This releases the modifiers and
+    /// turns off Caps Lock, calls the function, then turns Caps Lock back on.
+    /// Caps Lock is restored even if the function fails.
+    ///
+    /// The modifiers are not pressed again afterwards. Whether the user is
+    /// still holding them can't be known once they have been released so
+    /// pressing them again could leave them stuck down.
+    ///
+    /// ```no_run
+    /// use tfc::{traits::*, Context};
+    ///
+    /// let mut ctx = Context::new().unwrap();
+    /// ctx.with_normalized_state(|ctx| ctx.ascii_string(b"Hello")).unwrap();
+    /// ```
+    fn with_normalized_state<T, F>(&mut self, f: F) -> Result<T, GenericError<Self::PlatformError>>
+    where
+        Self: KeyboardContext + Sized,
+        F: FnOnce(&mut Self) -> Result<T, GenericError<Self::PlatformError>>,
+    {
+        let caps_lock = self.normalize_state()?;
+        let result = f(self);
+        if caps_lock {
+            self.key_click(Key::CapsLock)?;
+        }
+        result
+    }
+}
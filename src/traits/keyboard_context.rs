@@ -23,4 +23,31 @@ pub trait KeyboardContext: FallibleContext {
         self.key_down(key)?;
         self.key_up(key)
     }
+
+    /// Prepare the keyboard for typing a string.
+    ///
+    /// This is called by
+    /// [`ascii_string`](crate::AsciiKeyboardContext::ascii_string) before
+    /// typing. Contexts created with
+    /// [`normalize_key_state`](crate::ContextBuilder::normalize_key_state)
+    /// release the modifiers and turn off Caps Lock here. Returns whether Caps
+    /// Lock needs to be turned back on by
+    /// [`end_string`](KeyboardContext::end_string). By default, this does
+    /// nothing.
+    fn begin_string(&mut self) -> Result<bool, GenericError<Self::PlatformError>> {
+        Ok(false)
+    }
+
+    /// Restore the keyboard after typing a string.
+    ///
+    /// This is called with the value returned by
+    /// [`begin_string`](KeyboardContext::begin_string) once the string has
+    /// been typed, even if typing it failed.
+    fn end_string(&mut self, caps_lock: bool) -> Result<(), GenericError<Self::PlatformError>> {
+        if caps_lock {
+            self.key_click(Key::CapsLock)
+        } else {
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,54 @@
+use super::FallibleContext;
+use crate::GenericError;
+
+/// The keyboard layout and modifiers that would be used to type a character.
+///
+/// This is returned by [`char_layout`](LayoutContext::char_layout).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CharLayout {
+    /// The index of the layout that the character is on.
+    pub layout: u8,
+    /// The modifier mask that would be held while pressing the key.
+    ///
+    /// On Linux-X11, this is a core modifier mask. `1` is Shift and `128` is
+    /// usually AltGr (Mod5).
+    pub modifiers: u8,
+}
+
+/// A context that supports inspecting and switching keyboard layouts.
+///
+/// Layouts are identified by their index in the list of configured layouts.
+///
+/// # Platform Differences
+///
+/// This trait is only implemented for Linux-X11. Each layout corresponds to an
+/// XKB group so there can be at most four layouts.
+pub trait LayoutContext: FallibleContext {
+    /// Get the names of the configured keyboard layouts.
+    ///
+    /// The index of a name in the returned vector is the index of the layout.
+    /// The names are whatever the system calls them (e.g. `"English (US)"`).
+    fn layout_names(&self) -> Result<Vec<String>, GenericError<Self::PlatformError>>;
+
+    /// Get the index of the active keyboard layout.
+    fn active_layout(&self) -> Result<u8, GenericError<Self::PlatformError>>;
+
+    /// Switch to a different keyboard layout.
+    ///
+    /// This affects the whole system, not just the events generated by the
+    /// context. This can be used to pin a layout before typing.
+    fn set_active_layout(&mut self, layout: u8) -> Result<(), GenericError<Self::PlatformError>>;
+
+    /// Get the layout and modifiers that would be used to type a character.
+    ///
+    /// Characters are always typed using the active layout so only the active
+    /// layout is checked. Returns `None` if the character isn't on the active
+    /// layout, even if it is on another one. Such characters may still be
+    /// typed by [`UnicodeKeyboardContext`](crate::UnicodeKeyboardContext) by
+    /// remapping a key. Use [`set_active_layout`](Self::set_active_layout)
+    /// first to type a character from a different layout.
+    fn char_layout(
+        &self,
+        ch: char,
+    ) -> Result<Option<CharLayout>, GenericError<Self::PlatformError>>;
+}
@@ -1,13 +1,23 @@
 mod ascii_keyboard_context;
+mod clipboard_context;
 mod fallible_context;
+mod key_state_context;
 mod keyboard_context;
+mod layout_context;
 mod mouse_context;
+mod screen_capture_context;
 mod screen_context;
 mod unicode_keyboard_context;
+mod window_context;
 
 pub use ascii_keyboard_context::*;
+pub use clipboard_context::*;
 pub use fallible_context::*;
+pub use key_state_context::*;
 pub use keyboard_context::*;
+pub use layout_context::*;
 pub use mouse_context::*;
+pub use screen_capture_context::*;
 pub use screen_context::*;
 pub use unicode_keyboard_context::*;
+pub use window_context::*;
@@ -1,5 +1,5 @@
-use super::FallibleContext;
-use crate::{GenericError, MouseButton};
+use super::{FallibleContext, ScreenContext};
+use crate::{GenericError, MouseButton, Point};
 
 /// A context that supports mouse events.
 ///
@@ -30,6 +30,15 @@ pub trait MouseContext: FallibleContext {
     /// * `y` - The vertical position. A zero value is the top of the screen.
     fn mouse_move_abs(&mut self, x: i32, y: i32) -> Result<(), GenericError<Self::PlatformError>>;
 
+    /// Move the mouse to a point in any [`CoordinateSpace`](crate::CoordinateSpace).
+    fn mouse_move_to(&mut self, point: Point) -> Result<(), GenericError<Self::PlatformError>>
+    where
+        Self: ScreenContext,
+    {
+        let (x, y) = self.to_pixels(point)?;
+        self.mouse_move_abs(x, y)
+    }
+
     /// Scroll the mouse horizontally and vertically in pixels.
     ///
     /// # Arguments
@@ -0,0 +1,67 @@
+use super::{FallibleContext, ScreenContext};
+use crate::{find_image, utils::poll_until, GenericError, Image, Rect};
+use std::time::Duration;
+
+/// A context that supports capturing the contents of the screen.
+///
+/// Coordinates are in pixels with the origin at the top-left of the screen, the
+/// same as [`ScreenContext`]. The alpha channel of captured pixels is always
+/// 255.
+///
+/// # Platform Differences
+///
+/// This trait is only implemented for Linux-X11. The MIT-SHM extension is used
+/// if the server supports it.
+pub trait ScreenCaptureContext: FallibleContext {
+    /// Get the color of a single pixel on the screen.
+    fn pixel(&self, x: i32, y: i32) -> Result<[u8; 4], GenericError<Self::PlatformError>> {
+        Ok(self.capture(Rect::new(x, y, 1, 1))?.pixel(0, 0))
+    }
+
+    /// Capture a region of the screen.
+    ///
+    /// Returns [`Info`](GenericError::Info) if the region extends beyond the
+    /// edges of the screen.
+    fn capture(&self, rect: Rect) -> Result<Image, GenericError<Self::PlatformError>>;
+
+    /// Capture the whole screen.
+    fn capture_screen(&self) -> Result<Image, GenericError<Self::PlatformError>>
+    where
+        Self: ScreenContext,
+    {
+        let (width, height) = self.screen_size()?;
+        self.capture(Rect::new(0, 0, width as u32, height as u32))
+    }
+
+    /// Find the region of the screen that matches a template image.
+    ///
+    /// See [`find_image`] for a description of the `tolerance`. `None` is
+    /// returned if the template isn't on the screen.
+    fn find_on_screen(
+        &self,
+        template: &Image,
+        tolerance: f32,
+    ) -> Result<Option<Rect>, GenericError<Self::PlatformError>>
+    where
+        Self: ScreenContext,
+    {
+        Ok(find_image(&self.capture_screen()?, template, tolerance))
+    }
+
+    /// Wait until a template image appears on the screen.
+    ///
+    /// The screen is checked repeatedly with
+    /// [`find_on_screen`](Self::find_on_screen) until the template is found.
+    /// `None` is returned if the template doesn't appear before the timeout.
+    fn wait_for_image(
+        &self,
+        template: &Image,
+        tolerance: f32,
+        timeout: Duration,
+    ) -> Result<Option<Rect>, GenericError<Self::PlatformError>>
+    where
+        Self: ScreenContext,
+    {
+        poll_until(timeout, || self.find_on_screen(template, tolerance))
+    }
+}
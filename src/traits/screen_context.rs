@@ -1,12 +1,13 @@
 use super::FallibleContext;
-use crate::GenericError;
+use crate::{CoordinateSpace, GenericError, Point, Rect};
 
 /// A context that supports getting screen information.
 ///
 /// # Platform Differences
 ///
-/// This trait isn't implemented for the Linux-Wayland context. It is simply not
-/// possible to obtain this information under Wayland.
+/// The methods of the Linux-Wayland context return
+/// [`Info`](GenericError::Info). It is simply not possible to obtain this
+/// information under Wayland.
 pub trait ScreenContext: FallibleContext {
     /// Get the location of the cursor in pixels.
     ///
@@ -20,4 +21,39 @@ pub trait ScreenContext: FallibleContext {
     /// The first tuple element is the width and the second tuple element is the
     /// height.
     fn screen_size(&self) -> Result<(i32, i32), GenericError<Self::PlatformError>>;
+
+    /// Get the area of each monitor in pixels.
+    ///
+    /// The areas can be used with
+    /// [`NormalizedIn`](CoordinateSpace::NormalizedIn) points to position the
+    /// mouse on a particular monitor. By default, the whole screen is treated
+    /// as a single monitor.
+    ///
+    /// # Platform Differences
+    ///
+    /// On Linux-X11, the monitors are listed with RandR if libXrandr 1.5 or
+    /// newer is installed.
+    fn monitors(&self) -> Result<Vec<Rect>, GenericError<Self::PlatformError>> {
+        let (width, height) = self.screen_size()?;
+        Ok(vec![Rect::new(0, 0, width.max(0) as u32, height.max(0) as u32)])
+    }
+
+    /// Get the location of a point in pixels.
+    ///
+    /// The size of the screen is only queried for
+    /// [`Normalized`](CoordinateSpace::Normalized) points.
+    fn to_pixels(&self, point: Point) -> Result<(i32, i32), GenericError<Self::PlatformError>> {
+        let screen_size = match point.space {
+            CoordinateSpace::Normalized => self.screen_size()?,
+            _ => (0, 0),
+        };
+        Ok(point.to_pixels(screen_size))
+    }
+
+    /// Get the location of the cursor as a
+    /// [`Normalized`](CoordinateSpace::Normalized) point.
+    fn cursor_location_normalized(&self) -> Result<Point, GenericError<Self::PlatformError>> {
+        let (x, y) = self.cursor_location()?;
+        Ok(Point::from_pixels(x, y, self.screen_size()?))
+    }
 }
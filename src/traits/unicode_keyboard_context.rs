@@ -9,7 +9,8 @@ use crate::GenericError;
 /// [`AsciiKeyboardContext`](crate::AsciiKeyboardContext) may be used as an
 /// alternative. For convenience, the `"ascii-fallback"` feature may be enabled
 /// to provide an implementation of `UnicodeKeyboardContext` that uses
-/// [`AsciiKeyboardContext`](crate::AsciiKeyboardContext).
+/// [`AsciiKeyboardContext`](crate::AsciiKeyboardContext). Characters beyond
+/// ASCII can then be typed by setting a `UnicodeStrategy` on the context.
 ///
 /// On macOS and Windows,
 /// [`unicode_string`](UnicodeKeyboardContext::unicode_string) is not equivalent
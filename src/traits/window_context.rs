@@ -0,0 +1,78 @@
+use super::FallibleContext;
+use crate::{utils::poll_until, GenericError, WindowId, WindowInfo};
+use std::time::Duration;
+
+/// A context that supports finding and manipulating top-level windows.
+///
+/// # Platform Differences
+///
+/// This trait is only implemented for Linux-X11. The window manager is
+/// controlled through the
+/// [EWMH](https://specifications.freedesktop.org/wm-spec/latest/) properties
+/// and messages. Without an EWMH compliant window manager, the mapped children
+/// of the root window are listed instead and windows are focused directly.
+pub trait WindowContext: FallibleContext {
+    /// Get the top-level windows in stacking order from bottom to top.
+    fn windows(&self) -> Result<Vec<WindowInfo>, GenericError<Self::PlatformError>>;
+
+    /// Get the window that currently has focus.
+    fn active_window(&self) -> Result<Option<WindowId>, GenericError<Self::PlatformError>>;
+
+    /// Give a window focus and raise it above the other windows.
+    fn activate_window(&mut self, id: WindowId) -> Result<(), GenericError<Self::PlatformError>>;
+
+    /// Raise a window above the other windows without giving it focus.
+    fn raise_window(&mut self, id: WindowId) -> Result<(), GenericError<Self::PlatformError>>;
+
+    /// Minimize (iconify) a window.
+    fn minimize_window(&mut self, id: WindowId) -> Result<(), GenericError<Self::PlatformError>>;
+
+    /// Move the top-left corner of a window to a position on the screen.
+    fn move_window(
+        &mut self,
+        id: WindowId,
+        x: i32,
+        y: i32,
+    ) -> Result<(), GenericError<Self::PlatformError>>;
+
+    /// Change the size of a window.
+    fn resize_window(
+        &mut self,
+        id: WindowId,
+        width: u32,
+        height: u32,
+    ) -> Result<(), GenericError<Self::PlatformError>>;
+
+    /// Find the topmost window with a title that is accepted by a matcher.
+    ///
+    /// The matcher can be any function of the title, such as a comparison or
+    /// a regular expression.
+    ///
+    /// ```no_run
+    /// use tfc::{traits::*, Context};
+    ///
+    /// let ctx = Context::new().unwrap();
+    /// let editor = ctx.find_window(&|title| title.ends_with(" - Editor")).unwrap();
+    /// ```
+    fn find_window(
+        &self,
+        title: &dyn Fn(&str) -> bool,
+    ) -> Result<Option<WindowInfo>, GenericError<Self::PlatformError>> {
+        Ok(self
+            .windows()?
+            .into_iter()
+            .rev()
+            .find(|window| title(&window.title)))
+    }
+
+    /// Wait until a window with a title that is accepted by a matcher appears.
+    ///
+    /// `None` is returned if the window doesn't appear before the timeout.
+    fn wait_for_window(
+        &self,
+        title: &dyn Fn(&str) -> bool,
+        timeout: Duration,
+    ) -> Result<Option<WindowInfo>, GenericError<Self::PlatformError>> {
+        poll_until(timeout, || self.find_window(title))
+    }
+}
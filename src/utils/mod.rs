@@ -1,3 +1,5 @@
 mod non_zero;
+mod poll;
 
 pub use non_zero::*;
+pub use poll::*;
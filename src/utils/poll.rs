@@ -0,0 +1,25 @@
+use std::time::{Duration, Instant};
+
+// How often the condition is checked while waiting for something to appear.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Call a function repeatedly until it returns `Some` or the timeout expires.
+///
+/// `None` is returned if the timeout expires. The function is always called at
+/// least once.
+pub fn poll_until<T, E, F>(timeout: Duration, mut f: F) -> Result<Option<T>, E>
+where
+    F: FnMut() -> Result<Option<T>, E>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(value) = f()? {
+            return Ok(Some(value));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        std::thread::sleep(POLL_INTERVAL.min(deadline - now));
+    }
+}
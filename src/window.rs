@@ -0,0 +1,23 @@
+use crate::Rect;
+
+/// An identifier for a top-level window.
+///
+/// On Linux-X11, this is the XID of the window.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct WindowId(pub u64);
+
+/// Information about a top-level window.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WindowInfo {
+    pub id: WindowId,
+    /// The title shown in the title bar.
+    pub title: String,
+    /// The class of the application that owns the window. On Linux-X11, this
+    /// is the second part of `WM_CLASS` (e.g. `Firefox`).
+    pub class: String,
+    /// The ID of the process that owns the window, if it is known.
+    pub pid: Option<u32>,
+    /// The position and size of the window in screen coordinates, excluding
+    /// any decorations.
+    pub rect: Rect,
+}
@@ -17,6 +17,18 @@ impl Context {
         Ok(Self)
     }
 
+    /// Get the operations that this context supports.
+    pub fn capabilities(&self) -> crate::Capabilities {
+        crate::Capabilities {
+            keyboard: true,
+            mouse: true,
+            unicode: true,
+            unicode_remapping: true,
+            screen: true,
+            ..Default::default()
+        }
+    }
+
     fn send_input(&self, input: &ffi::INPUT) -> Result<(), Error> {
         self.send_inputs(std::slice::from_ref(input))
     }
@@ -0,0 +1,21 @@
+use tfc::{Capabilities, CommandCode};
+
+#[test]
+fn command_codes() {
+    let none = Capabilities::default();
+    assert_eq!(none.command_codes(), [CommandCode::Delay]);
+
+    let capabilities = Capabilities {
+        keyboard: true,
+        mouse: true,
+        clipboard: true,
+        ..Default::default()
+    };
+    assert!(capabilities.supports(CommandCode::AsciiString));
+    assert!(capabilities.supports(CommandCode::MouseClick));
+    assert!(capabilities.supports(CommandCode::SetClipboard));
+    // Normalized coordinates need the screen size.
+    assert!(!capabilities.supports(CommandCode::MouseMoveNormalized));
+    assert!(!capabilities.supports(CommandCode::UnicodeString));
+    assert!(!capabilities.supports(CommandCode::ClickImage));
+}
@@ -0,0 +1,452 @@
+// A headless X server for testing that generated events are actually
+// received.
+//
+// Each session runs its own Xvfb on a free display. The events that reach the
+// server are captured with the RECORD extension so tests can check which keys
+// and buttons were pressed, regardless of which window (if any) has focus.
+
+use std::{
+    ffi::CString,
+    io,
+    os::raw::{c_char, c_int, c_long, c_uint, c_ulong},
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use tfc::{Context, ContextBuilder, Error};
+use x11::{xlib, xrecord};
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+const EVENT_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_ATTEMPTS: u32 = 20;
+
+// Start well above the displays that a desktop is likely to be using.
+static NEXT_DISPLAY: AtomicU32 = AtomicU32::new(100);
+
+/// An input event captured from the server.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedEvent {
+    /// A key was pressed. The keysyms are those that were mapped to the
+    /// keycode at the time of the press.
+    KeyPress { keycode: u8, keysyms: Vec<c_ulong> },
+    KeyRelease { keycode: u8 },
+    ButtonPress { button: u8 },
+    ButtonRelease { button: u8 },
+    Motion { x: i16, y: i16 },
+}
+
+#[derive(Default)]
+struct Recording {
+    started: bool,
+    events: Vec<RecordedEvent>,
+}
+
+struct Shared {
+    recording: Mutex<Recording>,
+    changed: Condvar,
+    // Only used from the listener thread, while the callback is running.
+    lookup: usize,
+}
+
+/// An Xvfb server with a RECORD listener attached to it.
+pub struct HeadlessSession {
+    xvfb: Child,
+    display_name: String,
+    control: *mut xlib::Display,
+    record_context: xrecord::XRecordContext,
+    shared: Arc<Shared>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl HeadlessSession {
+    /// Start a server and begin listening for events.
+    ///
+    /// Returns `None` if Xvfb isn't installed so that tests can be skipped.
+    /// Panics if the server is installed but can't be used.
+    pub fn start() -> Option<Self> {
+        let (xvfb, display_name) = match spawn_xvfb() {
+            Ok(server) => server,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("Xvfb is not installed. Skipping test.");
+                return None;
+            }
+            Err(e) => panic!("Failed to start Xvfb: {}", e),
+        };
+
+        unsafe {
+            let control = open_display(&display_name);
+            let data = open_display(&display_name);
+            let lookup = open_display(&display_name);
+
+            let mut major = 0;
+            let mut minor = 0;
+            if xrecord::XRecordQueryVersion(control, &mut major, &mut minor) == 0 {
+                panic!("The RECORD extension is not available");
+            }
+
+            let range = xrecord::XRecordAllocRange();
+            (*range).device_events.first = xlib::KeyPress as u8;
+            (*range).device_events.last = xlib::MotionNotify as u8;
+            let mut clients = xrecord::XRecordAllClients;
+            let mut ranges = range;
+            let record_context =
+                xrecord::XRecordCreateContext(control, 0, &mut clients, 1, &mut ranges, 1);
+            xlib::XFree(range as *mut _);
+            if record_context == 0 {
+                panic!("XRecordCreateContext failed");
+            }
+            xlib::XSync(control, xlib::False);
+
+            let shared = Arc::new(Shared {
+                recording: Default::default(),
+                changed: Condvar::new(),
+                lookup: lookup as usize,
+            });
+
+            let listener_shared = shared.clone();
+            let data = data as usize;
+            let listener = thread::spawn(move || {
+                // Blocks until the context is disabled.
+                let data = data as *mut xlib::Display;
+                let closure = Arc::as_ptr(&listener_shared) as *mut c_char;
+                xrecord::XRecordEnableContext(data, record_context, Some(record_callback), closure);
+                xlib::XCloseDisplay(data);
+                xlib::XCloseDisplay(listener_shared.lookup as *mut xlib::Display);
+            });
+
+            let session = Self {
+                xvfb,
+                display_name,
+                control,
+                record_context,
+                shared,
+                listener: Some(listener),
+            };
+
+            // Events generated before the context is enabled would be missed.
+            if !session.wait_until(STARTUP_TIMEOUT, |recording| recording.started) {
+                panic!("The RECORD context was not enabled");
+            }
+
+            Some(session)
+        }
+    }
+
+    /// The name of the display that the server is running on.
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    /// Create a context that generates events on this server.
+    pub fn context(&self) -> Result<Context, Error> {
+        ContextBuilder::new().display(&self.display_name).build()
+    }
+
+    /// Change the keyboard layout of this server with `setxkbmap`.
+    ///
+    /// Returns `false` if setxkbmap isn't installed so that tests can be
+    /// skipped.
+    #[allow(dead_code)]
+    pub fn setxkbmap(&self, layouts: &str) -> bool {
+        let status = Command::new("setxkbmap")
+            .arg("-display")
+            .arg(&self.display_name)
+            .arg(layouts)
+            .status();
+        match status {
+            Ok(status) => {
+                assert!(status.success(), "setxkbmap {} failed", layouts);
+                true
+            }
+            Err(_) => {
+                eprintln!("setxkbmap is not installed. Skipping test.");
+                false
+            }
+        }
+    }
+
+    /// The events that have been captured so far.
+    pub fn events(&self) -> Vec<RecordedEvent> {
+        lock(&self.shared.recording).events.clone()
+    }
+
+    /// Wait until a key mapped to the given keysym is pressed.
+    pub fn wait_for_keysym(&self, keysym: c_ulong) -> bool {
+        self.wait_for_event(|event| match event {
+            RecordedEvent::KeyPress { keysyms, .. } => keysyms.contains(&keysym),
+            _ => false,
+        })
+    }
+
+    /// Wait until an event satisfying the predicate is captured.
+    pub fn wait_for_event<F>(&self, mut predicate: F) -> bool
+    where
+        F: FnMut(&RecordedEvent) -> bool,
+    {
+        self.wait_until(EVENT_TIMEOUT, |recording| recording.events.iter().any(&mut predicate))
+    }
+
+    /// Wait until a number of events satisfying the predicate are captured.
+    pub fn wait_for_events<F>(&self, count: usize, mut predicate: F) -> bool
+    where
+        F: FnMut(&RecordedEvent) -> bool,
+    {
+        self.wait_until(EVENT_TIMEOUT, |recording| {
+            recording.events.iter().filter(|e| predicate(e)).count() >= count
+        })
+    }
+
+    /// The current position of the pointer relative to the root window.
+    pub fn pointer_position(&self) -> (i32, i32) {
+        unsafe {
+            let mut root = 0;
+            let mut child = 0;
+            let mut root_x = 0;
+            let mut root_y = 0;
+            let mut win_x = 0;
+            let mut win_y = 0;
+            let mut mask: c_uint = 0;
+            xlib::XQueryPointer(
+                self.control,
+                xlib::XDefaultRootWindow(self.control),
+                &mut root,
+                &mut child,
+                &mut root_x,
+                &mut root_y,
+                &mut win_x,
+                &mut win_y,
+                &mut mask,
+            );
+            (root_x, root_y)
+        }
+    }
+
+    /// Show a window filled with a solid color. The color is given as
+    /// `0xRRGGBB`. The window lives until the session ends.
+    pub fn show_window(
+        &self,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        color: c_ulong,
+    ) -> xlib::Window {
+        unsafe {
+            let window = xlib::XCreateSimpleWindow(
+                self.control,
+                xlib::XDefaultRootWindow(self.control),
+                x,
+                y,
+                width,
+                height,
+                0,
+                0,
+                color,
+            );
+            xlib::XMapWindow(self.control, window);
+            xlib::XSync(self.control, xlib::False);
+            window
+        }
+    }
+
+    /// Set the title and class of a window.
+    pub fn name_window(&self, window: xlib::Window, title: &str, class: &str) {
+        let title = CString::new(title).unwrap();
+        let class = CString::new(class).unwrap();
+        unsafe {
+            xlib::XStoreName(self.control, window, title.as_ptr());
+            let mut hint = xlib::XClassHint {
+                res_name: class.as_ptr() as *mut c_char,
+                res_class: class.as_ptr() as *mut c_char,
+            };
+            xlib::XSetClassHint(self.control, window, &mut hint);
+            xlib::XSync(self.control, xlib::False);
+        }
+    }
+
+    /// Select events on a window so that they can be read with
+    /// [`window_events`](Self::window_events).
+    pub fn select_input(&self, window: xlib::Window, mask: c_long) {
+        unsafe {
+            xlib::XSelectInput(self.control, window, mask);
+            xlib::XSync(self.control, xlib::False);
+        }
+    }
+
+    /// Take the events matching the mask that have been delivered to a
+    /// window.
+    pub fn window_events(&self, window: xlib::Window, mask: c_long) -> Vec<xlib::XEvent> {
+        let mut events = Vec::new();
+        unsafe {
+            xlib::XSync(self.control, xlib::False);
+            let mut event = std::mem::zeroed();
+            while xlib::XCheckWindowEvent(self.control, window, mask, &mut event) != 0 {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    fn wait_until<F>(&self, timeout: Duration, mut condition: F) -> bool
+    where
+        F: FnMut(&Recording) -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+        let mut recording = lock(&self.shared.recording);
+        loop {
+            if condition(&recording) {
+                return true;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            recording = match self.shared.changed.wait_timeout(recording, deadline - now) {
+                Ok((recording, _)) => recording,
+                Err(poisoned) => poisoned.into_inner().0,
+            };
+        }
+    }
+}
+
+impl Drop for HeadlessSession {
+    fn drop(&mut self) {
+        unsafe {
+            xrecord::XRecordDisableContext(self.control, self.record_context);
+            xlib::XSync(self.control, xlib::False);
+        }
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+        unsafe {
+            xrecord::XRecordFreeContext(self.control, self.record_context);
+            xlib::XCloseDisplay(self.control);
+        }
+        let _ = self.xvfb.kill();
+        let _ = self.xvfb.wait();
+    }
+}
+
+fn lock(recording: &Mutex<Recording>) -> std::sync::MutexGuard<'_, Recording> {
+    match recording.lock() {
+        Ok(recording) => recording,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn spawn_xvfb() -> io::Result<(Child, String)> {
+    for _ in 0..MAX_ATTEMPTS {
+        let number = NEXT_DISPLAY.fetch_add(1, Ordering::Relaxed);
+        if Path::new(&format!("/tmp/.X{}-lock", number)).exists() {
+            continue;
+        }
+
+        let name = format!(":{}", number);
+        let mut child = Command::new("Xvfb")
+            .args([&name, "-screen", "0", "1024x768x24", "-nolisten", "tcp"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        if wait_for_server(&mut child, &name)? {
+            return Ok((child, name));
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    Err(io::Error::other("no free display was found"))
+}
+
+// Returns false if the server exited, most likely because another server
+// grabbed the display first.
+fn wait_for_server(child: &mut Child, name: &str) -> io::Result<bool> {
+    let c_name = CString::new(name).unwrap();
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    while Instant::now() < deadline {
+        if child.try_wait()?.is_some() {
+            return Ok(false);
+        }
+        unsafe {
+            let display = xlib::XOpenDisplay(c_name.as_ptr());
+            if !display.is_null() {
+                xlib::XCloseDisplay(display);
+                return Ok(true);
+            }
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    Ok(false)
+}
+
+unsafe fn open_display(name: &str) -> *mut xlib::Display {
+    let c_name = CString::new(name).unwrap();
+    let display = xlib::XOpenDisplay(c_name.as_ptr());
+    if display.is_null() {
+        panic!("Failed to open display {}", name);
+    }
+    display
+}
+
+unsafe fn keysyms_for_keycode(display: *mut xlib::Display, keycode: u8) -> Vec<c_ulong> {
+    let mut per_keycode: c_int = 0;
+    let keysyms = xlib::XGetKeyboardMapping(display, keycode, 1, &mut per_keycode);
+    if keysyms.is_null() {
+        return Vec::new();
+    }
+    let result = std::slice::from_raw_parts(keysyms, per_keycode as usize)
+        .iter()
+        .copied()
+        .filter(|keysym| *keysym != 0)
+        .collect();
+    xlib::XFree(keysyms as *mut _);
+    result
+}
+
+// The intercepted data is a 32-byte xEvent in the wire format. The root
+// coordinates are at offset 20.
+unsafe extern "C" fn record_callback(closure: *mut c_char, data: *mut xrecord::XRecordInterceptData) {
+    let shared = &*(closure as *const Shared);
+    let intercept = &*data;
+
+    let event = match intercept.category {
+        xrecord::XRecordStartOfData => {
+            lock(&shared.recording).started = true;
+            shared.changed.notify_all();
+            None
+        }
+        xrecord::XRecordFromServer if intercept.data_len * 4 >= 32 => {
+            let bytes = std::slice::from_raw_parts(intercept.data, 32);
+            let detail = bytes[1];
+            match (bytes[0] & 0x7f) as c_int {
+                xlib::KeyPress => Some(RecordedEvent::KeyPress {
+                    keycode: detail,
+                    keysyms: keysyms_for_keycode(shared.lookup as *mut xlib::Display, detail),
+                }),
+                xlib::KeyRelease => Some(RecordedEvent::KeyRelease { keycode: detail }),
+                xlib::ButtonPress => Some(RecordedEvent::ButtonPress { button: detail }),
+                xlib::ButtonRelease => Some(RecordedEvent::ButtonRelease { button: detail }),
+                xlib::MotionNotify => Some(RecordedEvent::Motion {
+                    x: i16::from_ne_bytes([bytes[20], bytes[21]]),
+                    y: i16::from_ne_bytes([bytes[22], bytes[23]]),
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    if let Some(event) = event {
+        lock(&shared.recording).events.push(event);
+        shared.changed.notify_all();
+    }
+
+    xrecord::XRecordFreeData(data);
+}
+
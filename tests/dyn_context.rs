@@ -0,0 +1,239 @@
+use std::{fmt, time::Duration};
+use tfc::{
+    traits::*, Command, Completion, DynContext, Executor, GenericError, Key, MouseButton, Recorder,
+};
+
+#[derive(Debug, PartialEq)]
+struct BrokenError;
+
+impl fmt::Display for BrokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "broken")
+    }
+}
+
+impl std::error::Error for BrokenError {}
+
+type Result = std::result::Result<(), GenericError<BrokenError>>;
+
+// A context from "another crate" that fails every call.
+struct BrokenContext;
+
+impl FallibleContext for BrokenContext {
+    type PlatformError = BrokenError;
+}
+
+impl KeyboardContext for BrokenContext {
+    fn key_down(&mut self, _: Key) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+
+    fn key_up(&mut self, _: Key) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+}
+
+impl UnicodeKeyboardContext for BrokenContext {
+    fn unicode_char_down(&mut self, _: char) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+
+    fn unicode_char_up(&mut self, _: char) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+
+    fn unicode_char(&mut self, ch: char) -> Result {
+        Err(GenericError::UnsupportedUnicode(ch))
+    }
+
+    fn unicode_string(&mut self, _: &str) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+}
+
+impl MouseContext for BrokenContext {
+    fn mouse_move_rel(&mut self, _: i32, _: i32) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+
+    fn mouse_move_abs(&mut self, _: i32, _: i32) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+
+    fn mouse_scroll(&mut self, _: i32, _: i32) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+
+    fn mouse_down(&mut self, _: MouseButton) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+
+    fn mouse_up(&mut self, _: MouseButton) -> Result {
+        Err(GenericError::Platform(BrokenError))
+    }
+}
+
+impl ScreenContext for BrokenContext {
+    fn cursor_location(&self) -> std::result::Result<(i32, i32), GenericError<BrokenError>> {
+        Err(GenericError::Platform(BrokenError))
+    }
+
+    fn screen_size(&self) -> std::result::Result<(i32, i32), GenericError<BrokenError>> {
+        Err(GenericError::Platform(BrokenError))
+    }
+}
+
+// A context with a 101x51 screen that only moves the mouse.
+#[derive(Default)]
+struct MouseOnly {
+    cursor: (i32, i32),
+}
+
+impl FallibleContext for MouseOnly {
+    type PlatformError = BrokenError;
+}
+
+impl KeyboardContext for MouseOnly {
+    fn key_down(&mut self, _: Key) -> Result {
+        Ok(())
+    }
+
+    fn key_up(&mut self, _: Key) -> Result {
+        Ok(())
+    }
+}
+
+impl UnicodeKeyboardContext for MouseOnly {
+    fn unicode_char_down(&mut self, _: char) -> Result {
+        Ok(())
+    }
+
+    fn unicode_char_up(&mut self, _: char) -> Result {
+        Ok(())
+    }
+
+    fn unicode_char(&mut self, _: char) -> Result {
+        Ok(())
+    }
+
+    fn unicode_string(&mut self, _: &str) -> Result {
+        Ok(())
+    }
+}
+
+impl MouseContext for MouseOnly {
+    fn mouse_move_rel(&mut self, dx: i32, dy: i32) -> Result {
+        self.cursor = (self.cursor.0 + dx, self.cursor.1 + dy);
+        Ok(())
+    }
+
+    fn mouse_move_abs(&mut self, x: i32, y: i32) -> Result {
+        self.cursor = (x, y);
+        Ok(())
+    }
+
+    fn mouse_scroll(&mut self, _: i32, _: i32) -> Result {
+        Ok(())
+    }
+
+    fn mouse_down(&mut self, _: MouseButton) -> Result {
+        Ok(())
+    }
+
+    fn mouse_up(&mut self, _: MouseButton) -> Result {
+        Ok(())
+    }
+}
+
+impl ScreenContext for MouseOnly {
+    fn cursor_location(&self) -> std::result::Result<(i32, i32), GenericError<BrokenError>> {
+        Ok(self.cursor)
+    }
+
+    fn screen_size(&self) -> std::result::Result<(i32, i32), GenericError<BrokenError>> {
+        Ok((101, 51))
+    }
+}
+
+#[test]
+fn execute_dyn() {
+    let mut recorder = Recorder::new().min_delay(Duration::from_secs(3600));
+    let ctx: &mut dyn DynContext = &mut recorder;
+
+    Command::KeyClick(Key::A).execute_dyn(ctx).unwrap();
+    Command::AsciiChar(b'B').execute_dyn(ctx).unwrap();
+    Command::UnicodeString("ü".to_owned())
+        .execute_dyn(ctx)
+        .unwrap();
+    assert!(Command::MouseMoveNormalized(0, 0).execute_dyn(ctx).is_err());
+    // The trait object can be used like any other context.
+    ctx.mouse_click(MouseButton::Left).unwrap();
+
+    assert_eq!(
+        recorder.finish(),
+        [
+            Command::KeyClick(Key::A),
+            Command::KeyDown(Key::Shift),
+            Command::KeyClick(Key::B),
+            Command::KeyUp(Key::Shift),
+            Command::UnicodeString("ü".to_owned()),
+            Command::MouseClick(MouseButton::Left),
+        ]
+    );
+}
+
+#[test]
+fn errors() {
+    let mut ctx: Box<dyn DynContext> = Box::new(BrokenContext);
+
+    match Command::KeyDown(Key::A).execute_dyn(&mut *ctx) {
+        Err(GenericError::Platform(e)) => {
+            assert_eq!(e.downcast_ref::<BrokenError>(), Some(&BrokenError));
+            assert_eq!(e.to_string(), "broken");
+        }
+        _ => panic!("expected a platform error"),
+    }
+    assert!(matches!(
+        ctx.unicode_char('x'),
+        Err(GenericError::UnsupportedUnicode('x'))
+    ));
+}
+
+#[test]
+fn executor() {
+    let mut recorder = Recorder::new().min_delay(Duration::from_secs(3600));
+    let commands = [Command::KeyDown(Key::Shift), Command::KeyUp(Key::Shift)];
+    let completion = Executor::new()
+        .execute_dyn(&mut recorder, &commands)
+        .unwrap();
+    assert_eq!(completion, Completion::Finished);
+    assert_eq!(recorder.finish(), commands);
+}
+
+#[test]
+fn executor_releases_keys_on_error() {
+    let mut recorder = Recorder::new().min_delay(Duration::from_secs(3600));
+    // The recorder fails to move to a normalized point without a screen.
+    let commands = [
+        Command::KeyDown(Key::Shift),
+        Command::MouseMoveNormalized(0, 0),
+    ];
+    assert!(Executor::new()
+        .execute_dyn(&mut recorder, &commands)
+        .is_err());
+    assert_eq!(
+        recorder.finish(),
+        [Command::KeyDown(Key::Shift), Command::KeyUp(Key::Shift)]
+    );
+}
+
+#[test]
+fn executor_mouse_move_normalized() {
+    let mut ctx: Box<dyn DynContext> = Box::new(MouseOnly::default());
+    let commands = [Command::MouseMoveNormalized(u16::MAX, u16::MAX / 2 + 1)];
+    // Only execute_dyn can query the screen of a DynContext.
+    assert!(Executor::new().execute(&mut *ctx, &commands).is_err());
+    let completion = Executor::new().execute_dyn(&mut *ctx, &commands).unwrap();
+    assert_eq!(completion, Completion::Finished);
+    assert_eq!(ctx.cursor_location().unwrap(), (100, 25));
+}
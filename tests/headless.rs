@@ -0,0 +1,518 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::{HeadlessSession, RecordedEvent};
+use regex::Regex;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+use tfc::{
+    traits::*, Command, CommandCode, Completion, ContextBuilder, DiagnosticStatus, Enum, Executor,
+    Hotkey, HotkeyListener, Image, Key, KillSwitch, Modifiers, MouseButton, Point, Rect, Selection,
+};
+use x11::{keysym, xlib};
+
+macro_rules! session {
+    () => {
+        match HeadlessSession::start() {
+            Some(session) => session,
+            None => return Ok(()),
+        }
+    };
+}
+
+#[test]
+fn key_click() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    ctx.key_click(Key::A)?;
+
+    assert!(session.wait_for_keysym(keysym::XK_a as _));
+    assert!(session.wait_for_event(|e| matches!(e, RecordedEvent::KeyRelease { .. })));
+    Ok(())
+}
+
+#[test]
+fn unicode_string() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    // Neither of these are on the default layout so the keycodes have to be
+    // remapped.
+    ctx.unicode_string("é✓")?;
+
+    assert!(session.wait_for_keysym(keysym::XK_eacute as _));
+    assert!(session.wait_for_keysym(0x0100_2713));
+    Ok(())
+}
+
+#[test]
+fn mouse_move_abs() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    ctx.mouse_move_abs(100, 200)?;
+    assert_eq!(session.pointer_position(), (100, 200));
+
+    ctx.mouse_move_rel(-50, 25)?;
+    ctx.mouse_move_abs(300, 50)?;
+    assert_eq!(session.pointer_position(), (300, 50));
+    Ok(())
+}
+
+#[test]
+fn batch() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    ctx.batch(|ctx| {
+        for x in 0..50 {
+            ctx.mouse_move_abs(x, 10)?;
+        }
+        ctx.key_click(Key::A)?;
+        ctx.unicode_string("b")
+    })?;
+    assert_eq!(session.pointer_position(), (49, 10));
+    assert!(session.wait_for_keysym(keysym::XK_a as _));
+    assert!(session.wait_for_keysym(keysym::XK_b as _));
+
+    // The errors from the batch are still reported.
+    let result = ctx.batch(|ctx| ctx.unicode_string("\u{0}"));
+    assert!(result.is_err());
+    ctx.key_click(Key::C)?;
+    assert!(session.wait_for_keysym(keysym::XK_c as _));
+    Ok(())
+}
+
+#[test]
+fn mouse_move_normalized() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+    let (width, height) = ctx.screen_size()?;
+
+    ctx.mouse_move_to(Point::normalized(1.0, 0.0))?;
+    assert_eq!(session.pointer_position(), (width - 1, 0));
+    ctx.mouse_move_to(Point::normalized(0.5, 0.5))?;
+    let center = session.pointer_position();
+    let point = ctx.cursor_location_normalized()?;
+    assert_eq!(ctx.to_pixels(point)?, center);
+
+    let command = Command::MouseMoveNormalized(0, u16::MAX);
+    assert!(command.execute(&mut ctx).is_err());
+    command.execute_extended(&mut ctx)?;
+    assert_eq!(session.pointer_position(), (0, height - 1));
+
+    // The virtual screen only has one monitor.
+    let monitors = ctx.monitors()?;
+    assert_eq!(monitors, [Rect::new(0, 0, width as u32, height as u32)]);
+    ctx.mouse_move_to(Point::normalized_in(monitors[0], 1.0, 1.0))?;
+    assert_eq!(session.pointer_position(), (width - 1, height - 1));
+    Ok(())
+}
+
+#[test]
+fn mouse_scroll() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    ctx.mouse_scroll(0, 240)?;
+    ctx.mouse_scroll(-120, 0)?;
+
+    assert!(session.wait_for_events(2, |e| *e == RecordedEvent::ButtonPress { button: 5 }));
+    assert!(session.wait_for_events(1, |e| *e == RecordedEvent::ButtonPress { button: 6 }));
+    let up = session
+        .events()
+        .iter()
+        .filter(|e| **e == RecordedEvent::ButtonPress { button: 4 })
+        .count();
+    assert_eq!(up, 0);
+    Ok(())
+}
+
+#[test]
+fn invalid_screen() {
+    let session = match HeadlessSession::start() {
+        Some(session) => session,
+        None => return,
+    };
+
+    let result = ContextBuilder::new()
+        .display(session.display_name())
+        .screen(1)
+        .build();
+    assert!(result.is_err());
+}
+
+#[test]
+fn hotkey() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+    let builder = ContextBuilder::new().display(session.display_name());
+    let hotkeys = ["ctrl+f1".parse::<Hotkey>()?, "f2".parse::<Hotkey>()?];
+    let listener = HotkeyListener::x11(&builder, &hotkeys)?;
+
+    // Events generated with XTest don't trigger hotkeys.
+    ctx.key_down(Key::Control)?;
+    ctx.key_click(Key::F1)?;
+    ctx.key_up(Key::Control)?;
+    ctx.key_click(Key::F2)?;
+    assert!(listener.recv_timeout(Duration::from_millis(500)).is_err());
+
+    // The hotkeys are still grabbed.
+    assert!(HotkeyListener::x11(&builder, &hotkeys[1..]).is_err());
+    Ok(())
+}
+
+#[test]
+fn key_state() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    ctx.key_down(Key::RightShift)?;
+    assert!(ctx.is_key_pressed(Key::RightShift)?);
+    assert!(!ctx.is_key_pressed(Key::Shift)?);
+    let shift = Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+    assert_eq!(ctx.modifier_state()?, shift);
+    ctx.key_up(Key::RightShift)?;
+    assert!(ctx.modifier_state()?.is_empty());
+
+    assert!(!ctx.caps_lock()?);
+    ctx.key_click(Key::CapsLock)?;
+    assert!(ctx.caps_lock()?);
+
+    // Caps Lock is turned off while typing and then turned back on.
+    ctx.with_normalized_state(|ctx| ctx.ascii_string(b"aB"))?;
+    assert!(session.wait_for_keysym(keysym::XK_a as _));
+    assert!(session.wait_for_keysym(keysym::XK_B as _));
+    assert!(ctx.caps_lock()?);
+    Ok(())
+}
+
+#[test]
+fn screen_capture() -> anyhow::Result<()> {
+    let session = session!();
+    let ctx = session.context()?;
+
+    session.show_window(10, 20, 30, 40, 0xFF0000);
+    session.show_window(40, 20, 30, 40, 0x00FF80);
+
+    assert_eq!(ctx.pixel(10, 20)?, [255, 0, 0, 255]);
+    assert_eq!(ctx.pixel(69, 59)?, [0, 255, 128, 255]);
+
+    let image = ctx.capture(Rect::new(30, 30, 20, 10))?;
+    assert_eq!((image.width(), image.height()), (20, 10));
+    assert_eq!(image.pixel(9, 0), [255, 0, 0, 255]);
+    assert_eq!(image.pixel(10, 9), [0, 255, 128, 255]);
+
+    let screen = ctx.capture_screen()?;
+    assert_eq!((screen.width(), screen.height()), (1024, 768));
+    assert_eq!(screen.pixel(15, 25), [255, 0, 0, 255]);
+
+    assert!(ctx.capture(Rect::new(1000, 0, 100, 100)).is_err());
+    Ok(())
+}
+
+#[test]
+fn click_image() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    session.show_window(200, 100, 40, 20, 0x2040C0);
+    session.show_window(240, 100, 40, 20, 0xE0E020);
+    let template = ctx.capture(Rect::new(220, 100, 40, 20))?;
+
+    let found = ctx.find_on_screen(&template, 0.01)?;
+    assert_eq!(found, Some(Rect::new(220, 100, 40, 20)));
+
+    Command::WaitForImage(template.clone(), 1000).execute_extended(&mut ctx)?;
+    Command::ClickImage(template, MouseButton::Left).execute_extended(&mut ctx)?;
+    assert_eq!(session.pointer_position(), (240, 110));
+    assert!(session.wait_for_event(|e| *e == RecordedEvent::ButtonPress { button: 1 }));
+
+    let mut missing = Image::new(8, 8);
+    for y in 0..8 {
+        for x in 0..8 {
+            missing.set_pixel(x, y, [255, 0, 255, 255]);
+        }
+    }
+    assert!(Command::ClickImage(missing, MouseButton::Left)
+        .execute_extended(&mut ctx)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn windows() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    let editor = session.show_window(10, 20, 100, 80, 0xFFFFFF);
+    session.name_window(editor, "notes.txt - Editor", "Editor");
+    let terminal = session.show_window(50, 60, 120, 90, 0x000000);
+    session.name_window(terminal, "Terminal", "Terminal");
+
+    let windows = ctx.windows()?;
+    assert_eq!(windows.len(), 2);
+    let editor = windows.iter().find(|w| w.class == "Editor").unwrap();
+    assert_eq!(editor.title, "notes.txt - Editor");
+    assert_eq!(editor.rect, Rect::new(10, 20, 100, 80));
+
+    let notes = Regex::new("notes")?;
+    let found = ctx.find_window(&|t| notes.is_match(t))?.unwrap();
+    assert_eq!(found.id, editor.id);
+    ctx.activate_window(found.id)?;
+    assert_eq!(ctx.active_window()?, Some(found.id));
+
+    ctx.move_window(found.id, 300, 200)?;
+    ctx.resize_window(found.id, 50, 60)?;
+    let found = ctx.find_window(&|t| notes.is_match(t))?.unwrap();
+    assert_eq!(found.rect, Rect::new(300, 200, 50, 60));
+
+    let terminal = windows.iter().find(|w| w.class == "Terminal").unwrap();
+    if cfg!(feature = "regex") {
+        Command::FocusWindow("^Terminal$".to_owned()).execute_extended(&mut ctx)?;
+        assert_eq!(ctx.active_window()?, Some(terminal.id));
+    }
+
+    let timeout = Duration::from_millis(200);
+    assert_eq!(ctx.wait_for_window(&|t| t == "Browser", timeout)?, None);
+    Ok(())
+}
+
+#[test]
+fn targeted_events() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+
+    let window = session.show_window(10, 20, 100, 80, 0xFFFFFF);
+    let mask = xlib::KeyPressMask
+        | xlib::KeyReleaseMask
+        | xlib::ButtonPressMask
+        | xlib::ButtonReleaseMask
+        | xlib::PointerMotionMask;
+    session.select_input(window, mask);
+
+    let id = ctx.windows()?[0].id;
+    let pointer = session.pointer_position();
+    let mut target = ctx.for_window(id);
+    target.key_down(Key::Shift)?;
+    target.key_click(Key::A)?;
+    target.key_up(Key::Shift)?;
+    target.unicode_char('b')?;
+    target.mouse_move_abs(5, 6)?;
+    target.mouse_click(MouseButton::Left)?;
+
+    let events = session.window_events(window, mask);
+    assert_eq!(events.len(), 9);
+    assert!(events
+        .iter()
+        .all(|event| unsafe { event.any.send_event } != 0));
+    unsafe {
+        // The A key is pressed while shift is held.
+        assert_eq!(events[1].get_type(), xlib::KeyPress);
+        assert_eq!(events[1].key.state & xlib::ShiftMask, xlib::ShiftMask);
+        assert_eq!(events[3].get_type(), xlib::KeyRelease);
+        assert_eq!(events[4].get_type(), xlib::KeyPress);
+        assert_eq!(events[4].key.state & xlib::ShiftMask, 0);
+
+        let motion = events[6].motion;
+        assert_eq!(motion.type_, xlib::MotionNotify);
+        assert_eq!((motion.x, motion.y), (5, 6));
+        assert_eq!((motion.x_root, motion.y_root), (15, 26));
+
+        assert_eq!(events[7].button.button, xlib::Button1);
+        assert_eq!(
+            events[8].button.state & xlib::Button1Mask,
+            xlib::Button1Mask
+        );
+    }
+
+    // The events bypass the server's input devices.
+    assert!(session.events().is_empty());
+    assert_eq!(session.pointer_position(), pointer);
+    Ok(())
+}
+
+#[test]
+fn clipboard() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+    let other = session.context()?;
+
+    assert_eq!(other.clipboard_text(Selection::Clipboard)?, None);
+    ctx.set_clipboard_text(Selection::Clipboard, "clipboard ✓")?;
+    ctx.set_clipboard_text(Selection::Primary, "primary")?;
+    assert_eq!(
+        other.clipboard_text(Selection::Clipboard)?.as_deref(),
+        Some("clipboard ✓")
+    );
+    assert_eq!(
+        other.clipboard_text(Selection::Primary)?.as_deref(),
+        Some("primary")
+    );
+
+    Command::SetClipboard("command".to_owned()).execute_extended(&mut ctx)?;
+    assert_eq!(
+        other.clipboard_text(Selection::Clipboard)?.as_deref(),
+        Some("command")
+    );
+
+    // The previous contents are restored after pasting.
+    ctx.paste_string("pasted")?;
+    assert!(session.wait_for_keysym(keysym::XK_v as _));
+    assert_eq!(
+        other.clipboard_text(Selection::Clipboard)?.as_deref(),
+        Some("command")
+    );
+
+    // The clipboard is emptied after pasting if it was empty before.
+    ctx.clear_clipboard(Selection::Clipboard)?;
+    assert_eq!(other.clipboard_text(Selection::Clipboard)?, None);
+    ctx.paste_string("pasted")?;
+    assert_eq!(other.clipboard_text(Selection::Clipboard)?, None);
+
+    // Text that is too long is rejected instead of being pasted.
+    let long = "a".repeat(64 << 20);
+    assert!(ctx.paste_string(&long).is_err());
+    assert_eq!(other.clipboard_text(Selection::Clipboard)?, None);
+
+    // The selections are released when the context is dropped.
+    drop(ctx);
+    assert_eq!(other.clipboard_text(Selection::Primary)?, None);
+    Ok(())
+}
+
+#[test]
+fn executor_abort() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+    let mut executor = Executor::new();
+    let abort = executor.abort_handle();
+
+    let aborter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        abort.abort();
+    });
+    let commands = [
+        Command::KeyDown(Key::Shift),
+        Command::MouseDown(MouseButton::Left),
+        Command::Delay(10000),
+        Command::KeyClick(Key::A),
+    ];
+    let start = Instant::now();
+    assert_eq!(executor.execute(&mut ctx, &commands)?, Completion::Aborted);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    aborter.join().unwrap();
+
+    // The held keys and buttons are released.
+    assert!(!ctx.is_key_pressed(Key::Shift)?);
+    assert!(session.wait_for_event(|e| matches!(e, RecordedEvent::ButtonRelease { .. })));
+
+    // The handle stays aborted until it is reset.
+    let click = [Command::KeyClick(Key::B)];
+    assert_eq!(executor.execute(&mut ctx, &click)?, Completion::Aborted);
+    executor.abort_handle().reset();
+    assert_eq!(executor.execute(&mut ctx, &click)?, Completion::Finished);
+    assert!(session.wait_for_keysym(keysym::XK_b as _));
+
+    // Generated events don't trigger the X11 kill switch.
+    let builder = ContextBuilder::new().display(session.display_name());
+    let _kill_switch = KillSwitch::x11(&builder, executor.abort_handle())?;
+    thread::sleep(Duration::from_millis(100));
+    let escapes = [
+        Command::KeyClick(Key::Escape),
+        Command::KeyClick(Key::Escape),
+        Command::KeyClick(Key::Escape),
+        Command::Delay(500),
+    ];
+    assert_eq!(executor.execute(&mut ctx, &escapes)?, Completion::Finished);
+    Ok(())
+}
+
+#[test]
+fn capabilities() -> anyhow::Result<()> {
+    let session = session!();
+    let ctx = session.context()?;
+    let capabilities = ctx.capabilities();
+    assert!(capabilities.keyboard && capabilities.mouse && capabilities.screen);
+    let focus_window = cfg!(feature = "regex");
+    assert_eq!(capabilities.supports(CommandCode::FocusWindow), focus_window);
+    assert_eq!(
+        capabilities.command_codes().len(),
+        CommandCode::COUNT as usize - !focus_window as usize
+    );
+    Ok(())
+}
+
+#[test]
+fn diagnose() -> anyhow::Result<()> {
+    let session = session!();
+    let builder = ContextBuilder::new().display(session.display_name());
+    let diagnostics = tfc::diagnose(&builder);
+    for check in ["display", "xtest", "xkb"] {
+        let diagnostic = diagnostics.iter().find(|d| d.check == check).unwrap();
+        assert_eq!(diagnostic.status, DiagnosticStatus::Ok, "{:?}", diagnostic);
+    }
+    Ok(())
+}
+
+#[test]
+fn char_layout_active_group() -> anyhow::Result<()> {
+    let session = session!();
+    if !session.setxkbmap("us,ru") {
+        return Ok(());
+    }
+    let mut ctx = session.context()?;
+    assert_eq!(ctx.layout_names()?.len(), 2);
+
+    assert_eq!(ctx.char_layout('a')?.map(|l| l.layout), Some(0));
+    assert_eq!(ctx.char_layout('ж')?, None);
+
+    ctx.set_active_layout(1)?;
+    assert_eq!(ctx.char_layout('ж')?.map(|l| l.layout), Some(1));
+    assert_eq!(ctx.char_layout('a')?, None);
+    Ok(())
+}
+
+#[test]
+fn refresh_keymap_after_setxkbmap() -> anyhow::Result<()> {
+    let session = session!();
+    let mut ctx = session.context()?;
+    assert_eq!(ctx.char_layout('ж')?, None);
+    if !session.setxkbmap("ru") {
+        return Ok(());
+    }
+
+    // Typing processes the mapping events so the new layout is picked up
+    // without remapping a keycode.
+    ctx.unicode_char('ж')?;
+    assert!(session.wait_for_keysym(keysym::XK_Cyrillic_zhe as _));
+    assert_eq!(ctx.char_layout('ж')?.map(|l| l.layout), Some(0));
+    Ok(())
+}
+
+#[test]
+fn normalize_key_state() -> anyhow::Result<()> {
+    let session = session!();
+    let builder = ContextBuilder::new()
+        .display(session.display_name())
+        .normalize_key_state(true);
+    let mut ctx = builder.build()?;
+
+    ctx.key_click(Key::CapsLock)?;
+    ctx.key_down(Key::Shift)?;
+    ctx.ascii_string(b"Hello")?;
+    assert!(session.wait_for_keysym(keysym::XK_H as _));
+    assert!(session.wait_for_keysym(keysym::XK_e as _));
+
+    // Caps Lock is turned back on but Shift isn't pressed again.
+    assert!(ctx.caps_lock()?);
+    assert!(!ctx.is_key_pressed(Key::Shift)?);
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::time::Instant;
+use tfc::{Expansion, Hotstring, HotstringEngine, InputEvent, Key, MouseButton, TimedInputEvent};
+
+fn event(event: InputEvent, ch: Option<char>) -> TimedInputEvent {
+    TimedInputEvent {
+        event,
+        ch,
+        time: Instant::now(),
+    }
+}
+
+// Feed the characters of a string and return the last expansion.
+fn type_str(engine: &mut HotstringEngine, s: &str) -> Option<Expansion> {
+    let mut expansion = None;
+    for ch in s.chars() {
+        // The key doesn't matter as long as it produces a character.
+        expansion = engine.feed(&event(InputEvent::KeyDown(Key::A), Some(ch)));
+        engine.feed(&event(InputEvent::KeyUp(Key::A), None));
+    }
+    expansion
+}
+
+#[test]
+fn immediate() {
+    let mut hotstring = Hotstring::new(";sig", "Kind regards");
+    hotstring.immediate = true;
+    let mut engine = HotstringEngine::new(vec![hotstring]);
+
+    let expansion = type_str(&mut engine, "hello ;sig").unwrap();
+    assert_eq!(expansion.index, 0);
+    assert_eq!(expansion.erase, 4);
+    assert_eq!(expansion.text, "Kind regards");
+}
+
+#[test]
+fn ending_char() {
+    let mut engine = HotstringEngine::new(vec![Hotstring::new("btw", "by the way")]);
+
+    assert_eq!(type_str(&mut engine, "btw"), None);
+    let expansion = type_str(&mut engine, ",").unwrap();
+    assert_eq!(expansion.erase, 4);
+    assert_eq!(expansion.text, "by the way,");
+}
+
+#[test]
+fn word_boundary() {
+    let mut engine = HotstringEngine::new(vec![Hotstring::new("btw", "by the way")]);
+    assert_eq!(type_str(&mut engine, "abtw "), None);
+
+    let mut hotstring = Hotstring::new("btw", "by the way");
+    hotstring.inside_word = true;
+    let mut engine = HotstringEngine::new(vec![hotstring]);
+    assert!(type_str(&mut engine, "abtw ").is_some());
+}
+
+#[test]
+fn case() {
+    let mut engine = HotstringEngine::new(vec![Hotstring::new("btw", "by the way")]);
+    assert_eq!(type_str(&mut engine, "BTW ").unwrap().text, "BY THE WAY ");
+    assert_eq!(type_str(&mut engine, "Btw ").unwrap().text, "By the way ");
+    assert_eq!(type_str(&mut engine, "bTw ").unwrap().text, "by the way ");
+
+    let mut hotstring = Hotstring::new("btw", "by the way");
+    hotstring.case_sensitive = true;
+    let mut engine = HotstringEngine::new(vec![hotstring]);
+    assert_eq!(type_str(&mut engine, "Btw "), None);
+    assert_eq!(type_str(&mut engine, "btw ").unwrap().text, "by the way ");
+}
+
+#[test]
+fn backspace_and_reset() {
+    let mut engine = HotstringEngine::new(vec![Hotstring::new("btw", "by the way")]);
+
+    type_str(&mut engine, "btx");
+    engine.feed(&event(InputEvent::KeyDown(Key::DeleteOrBackspace), Some('\u{8}')));
+    assert!(type_str(&mut engine, "w ").is_some());
+
+    type_str(&mut engine, "bt");
+    engine.feed(&event(InputEvent::MouseDown(MouseButton::Left), None));
+    assert_eq!(type_str(&mut engine, "w "), None);
+
+    type_str(&mut engine, "bt");
+    engine.feed(&event(InputEvent::KeyDown(Key::LeftArrow), None));
+    assert_eq!(type_str(&mut engine, "w "), None);
+}
+
+#[test]
+fn shortcut() {
+    let mut engine = HotstringEngine::new(vec![Hotstring::new("btw", "by the way")]);
+
+    type_str(&mut engine, "bt");
+    engine.feed(&event(InputEvent::KeyDown(Key::Control), None));
+    engine.feed(&event(InputEvent::KeyDown(Key::W), Some('w')));
+    engine.feed(&event(InputEvent::KeyUp(Key::Control), None));
+    assert_eq!(type_str(&mut engine, " "), None);
+}
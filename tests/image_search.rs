@@ -0,0 +1,87 @@
+use tfc::{find_image, Command, Image, MouseButton, Rect};
+
+// A deterministic image with plenty of detail.
+fn noise(width: u32, height: u32, seed: u32) -> Image {
+    let mut state = seed;
+    let mut data = Vec::new();
+    for _ in 0..width * height {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        let [r, g, b, _] = state.to_be_bytes();
+        data.extend_from_slice(&[r, g, b, 255]);
+    }
+    Image::from_rgba(width, height, data).unwrap()
+}
+
+fn crop(image: &Image, rect: Rect) -> Image {
+    let mut cropped = Image::new(rect.width, rect.height);
+    for y in 0..rect.height {
+        for x in 0..rect.width {
+            let pixel = image.pixel(rect.x as u32 + x, rect.y as u32 + y);
+            cropped.set_pixel(x, y, pixel);
+        }
+    }
+    cropped
+}
+
+#[test]
+fn exact_match() {
+    let image = noise(300, 200, 1);
+    // Large enough to be searched at several levels.
+    let rect = Rect::new(123, 57, 64, 40);
+    assert_eq!(find_image(&image, &crop(&image, rect), 0.01), Some(rect));
+
+    let rect = Rect::new(0, 195, 5, 5);
+    assert_eq!(find_image(&image, &crop(&image, rect), 0.01), Some(rect));
+}
+
+#[test]
+fn brightness_change() {
+    let image = noise(200, 150, 2);
+    let rect = Rect::new(80, 30, 40, 32);
+    let mut template = crop(&image, rect);
+    for y in 0..template.height() {
+        for x in 0..template.width() {
+            let [r, g, b, a] = template.pixel(x, y);
+            template.set_pixel(x, y, [r / 2 + 20, g / 2 + 20, b / 2 + 20, a]);
+        }
+    }
+    assert_eq!(find_image(&image, &template, 0.05), Some(rect));
+}
+
+#[test]
+fn not_found() {
+    let image = noise(200, 150, 3);
+    let template = noise(32, 32, 4);
+    assert_eq!(find_image(&image, &template, 0.05), None);
+    assert_eq!(find_image(&template, &image, 0.05), None);
+    assert_eq!(find_image(&image, &Image::new(0, 0), 0.05), None);
+}
+
+#[test]
+fn solid_color() {
+    let mut image = Image::new(50, 50);
+    for y in 10..30 {
+        for x in 20..40 {
+            image.set_pixel(x, y, [200, 200, 200, 255]);
+        }
+    }
+    let template = crop(&image, Rect::new(25, 15, 10, 10));
+    let found = find_image(&image, &template, 0.01).unwrap();
+    assert!(Rect::new(20, 10, 11, 11).contains(found.x, found.y));
+}
+
+#[test]
+fn command_bytes() {
+    let commands = [
+        Command::WaitForImage(noise(3, 2, 5), 2500),
+        Command::ClickImage(noise(4, 1, 6), MouseButton::Right),
+    ];
+    for command in commands.iter() {
+        let mut bytes = vec![0; command.bytes_len()];
+        assert_eq!(command.to_bytes(&mut bytes), Ok(bytes.len()));
+        let (parsed, len) = Command::from_bytes(&bytes).unwrap();
+        assert_eq!(len, bytes.len());
+        assert_eq!(&parsed, command);
+        assert!(Command::from_bytes(&bytes[..len - 1]).is_err());
+    }
+}
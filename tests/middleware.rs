@@ -0,0 +1,100 @@
+use std::{
+    convert::Infallible,
+    time::{Duration, Instant},
+};
+use tfc::{
+    traits::*, Command, GenericError, Key, MouseButton, Policy, PolicyContext, Recorder, Rect,
+    TeeContext, ThrottleContext,
+};
+
+// A recorder that doesn't insert delays.
+fn recorder() -> Recorder {
+    Recorder::new().min_delay(Duration::from_secs(3600))
+}
+
+#[test]
+fn recorder_context() {
+    let mut ctx = recorder();
+    ctx.key_click(Key::A).unwrap();
+    ctx.ascii_char(b'B').unwrap();
+    ctx.mouse_move_abs(10, 20).unwrap();
+    ctx.unicode_string("ü").unwrap();
+    assert_eq!(
+        ctx.finish(),
+        [
+            Command::KeyClick(Key::A),
+            Command::KeyDown(Key::Shift),
+            Command::KeyClick(Key::B),
+            Command::KeyUp(Key::Shift),
+            Command::MouseMoveAbs(10, 20),
+            Command::UnicodeString("ü".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn tee() {
+    let mut ctx = TeeContext::new(recorder(), recorder());
+    ctx.mouse_click(MouseButton::Left).unwrap();
+    ctx.unicode_char('x').unwrap();
+
+    let (first, second) = ctx.into_inner();
+    let expected = [
+        Command::MouseClick(MouseButton::Left),
+        Command::UnicodeChar('x'),
+    ];
+    assert_eq!(first.finish(), expected);
+    assert_eq!(second.finish(), expected);
+}
+
+#[test]
+fn throttle() {
+    let mut ctx = ThrottleContext::new(recorder(), Duration::from_millis(20));
+    let start = Instant::now();
+    // Each call is delayed but the string is forwarded as a whole.
+    ctx.unicode_string("abc").unwrap();
+    ctx.key_click(Key::A).unwrap();
+    ctx.mouse_move_rel(1, 1).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(40));
+
+    assert_eq!(
+        ctx.into_inner().finish(),
+        [
+            Command::UnicodeString("abc".to_owned()),
+            Command::KeyClick(Key::A),
+            Command::MouseMoveRel(1, 1),
+        ]
+    );
+}
+
+// A screen with two monitors side by side.
+struct TwoMonitors;
+
+impl FallibleContext for TwoMonitors {
+    type PlatformError = Infallible;
+}
+
+impl ScreenContext for TwoMonitors {
+    fn cursor_location(&self) -> Result<(i32, i32), GenericError<Infallible>> {
+        Ok((0, 0))
+    }
+
+    fn screen_size(&self) -> Result<(i32, i32), GenericError<Infallible>> {
+        Ok((200, 100))
+    }
+
+    fn monitors(&self) -> Result<Vec<Rect>, GenericError<Infallible>> {
+        Ok(vec![Rect::new(0, 0, 100, 100), Rect::new(100, 0, 100, 100)])
+    }
+}
+
+#[test]
+fn default_methods_are_forwarded() {
+    let expected = TwoMonitors.monitors().unwrap();
+    let ctx = ThrottleContext::new(TwoMonitors, Duration::from_millis(20));
+    assert_eq!(ctx.monitors().unwrap(), expected);
+    let ctx = TeeContext::new(TwoMonitors, recorder());
+    assert_eq!(ctx.monitors().unwrap(), expected);
+    let ctx = PolicyContext::new(TwoMonitors, Policy::default());
+    assert_eq!(ctx.monitors().unwrap(), expected);
+}
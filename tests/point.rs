@@ -0,0 +1,42 @@
+use tfc::{Command, CoordinateSpace, Point, Rect, WindowId, WindowInfo};
+
+#[test]
+fn normalized() {
+    let screen = (1920, 1080);
+    assert_eq!(Point::normalized(0.0, 0.0).to_pixels(screen), (0, 0));
+    assert_eq!(Point::normalized(1.0, 1.0).to_pixels(screen), (1919, 1079));
+    assert_eq!(Point::normalized(0.5, 0.25).to_pixels(screen), (960, 270));
+
+    let point = Point::from_pixels(1919, 540, screen);
+    assert_eq!(point.space, CoordinateSpace::Normalized);
+    assert_eq!(point.to_pixels(screen), (1919, 540));
+    // The same point on a different resolution.
+    assert_eq!(point.to_pixels((1280, 720)), (1279, 360));
+}
+
+#[test]
+fn regions() {
+    let monitor = Rect::new(1920, 0, 1280, 1024);
+    let point = Point::normalized_in(monitor, 0.5, 1.0);
+    assert_eq!(point.to_pixels((0, 0)), (2560, 1023));
+
+    let window = WindowInfo {
+        id: WindowId(1),
+        title: String::new(),
+        class: String::new(),
+        pid: None,
+        rect: Rect::new(100, 50, 300, 200),
+    };
+    let point = Point::in_window(&window, 10, 20);
+    assert_eq!(point.to_pixels((0, 0)), (110, 70));
+    assert_eq!(Point::pixels(-5, 7).to_pixels((0, 0)), (-5, 7));
+}
+
+#[test]
+fn command_bytes() {
+    let command = Command::MouseMoveNormalized(65535, 1234);
+    let mut bytes = vec![0; command.bytes_len()];
+    assert_eq!(command.to_bytes(&mut bytes), Ok(5));
+    assert_eq!(Command::from_bytes(&bytes).unwrap(), (command, 5));
+    assert!(Command::from_bytes(&bytes[..4]).is_err());
+}
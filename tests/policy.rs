@@ -0,0 +1,248 @@
+use std::fmt;
+use tfc::{
+    traits::*, Command, CommandCode, Executor, GenericError, Key, MouseButton, Policy,
+    PolicyContext, Rect,
+};
+
+#[derive(Debug)]
+struct NeverError;
+
+impl fmt::Display for NeverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "never")
+    }
+}
+
+impl std::error::Error for NeverError {}
+
+type Result<T = ()> = std::result::Result<T, GenericError<NeverError>>;
+
+// A context that records the events instead of generating them.
+#[derive(Default)]
+struct FakeContext {
+    events: Vec<String>,
+    cursor: (i32, i32),
+}
+
+impl FallibleContext for FakeContext {
+    type PlatformError = NeverError;
+}
+
+impl KeyboardContext for FakeContext {
+    fn key_down(&mut self, key: Key) -> Result {
+        self.events.push(format!("down {:?}", key));
+        Ok(())
+    }
+
+    fn key_up(&mut self, key: Key) -> Result {
+        self.events.push(format!("up {:?}", key));
+        Ok(())
+    }
+}
+
+impl UnicodeKeyboardContext for FakeContext {
+    fn unicode_char_down(&mut self, ch: char) -> Result {
+        self.events.push(format!("down {}", ch));
+        Ok(())
+    }
+
+    fn unicode_char_up(&mut self, ch: char) -> Result {
+        self.events.push(format!("up {}", ch));
+        Ok(())
+    }
+
+    fn unicode_char(&mut self, ch: char) -> Result {
+        self.events.push(format!("char {}", ch));
+        Ok(())
+    }
+
+    fn unicode_string(&mut self, s: &str) -> Result {
+        self.events.push(format!("string {}", s));
+        Ok(())
+    }
+}
+
+impl MouseContext for FakeContext {
+    fn mouse_move_rel(&mut self, dx: i32, dy: i32) -> Result {
+        self.cursor = (self.cursor.0 + dx, self.cursor.1 + dy);
+        Ok(())
+    }
+
+    fn mouse_move_abs(&mut self, x: i32, y: i32) -> Result {
+        self.cursor = (x, y);
+        Ok(())
+    }
+
+    fn mouse_scroll(&mut self, _: i32, _: i32) -> Result {
+        Ok(())
+    }
+
+    fn mouse_down(&mut self, _: tfc::MouseButton) -> Result {
+        Ok(())
+    }
+
+    fn mouse_up(&mut self, _: tfc::MouseButton) -> Result {
+        Ok(())
+    }
+}
+
+impl ScreenContext for FakeContext {
+    fn cursor_location(&self) -> Result<(i32, i32)> {
+        Ok(self.cursor)
+    }
+
+    fn screen_size(&self) -> Result<(i32, i32)> {
+        Ok((1920, 1080))
+    }
+}
+
+fn is_denied<T>(result: Result<T>) -> bool {
+    matches!(result, Err(GenericError::Denied(_)))
+}
+
+#[test]
+fn keys() {
+    let policy = Policy {
+        denied_keys: vec![Key::CapsLock],
+        denied_hotkeys: vec![
+            "ctrl+alt+deleteorbackspace".parse().unwrap(),
+            "meta+l".parse().unwrap(),
+        ],
+        ..Default::default()
+    };
+    let mut ctx = PolicyContext::new(FakeContext::default(), policy);
+
+    assert!(is_denied(ctx.key_click(Key::CapsLock)));
+    ctx.key_click(Key::L).unwrap();
+    ctx.key_click(Key::DeleteOrBackspace).unwrap();
+
+    ctx.key_down(Key::Meta).unwrap();
+    assert!(is_denied(ctx.key_click(Key::L)));
+    // Characters are checked as the keys that produce them.
+    assert!(is_denied(ctx.unicode_char('l')));
+    assert!(is_denied(ctx.unicode_string("hello")));
+    ctx.unicode_char('é').unwrap();
+    ctx.key_up(Key::Meta).unwrap();
+    ctx.key_click(Key::L).unwrap();
+
+    // Extra modifiers don't make a shortcut allowed.
+    ctx.key_down(Key::Control).unwrap();
+    ctx.key_down(Key::Shift).unwrap();
+    ctx.key_down(Key::RightAlt).unwrap();
+    assert!(is_denied(ctx.key_click(Key::DeleteOrBackspace)));
+
+    let events = &ctx.inner().events;
+    let count = |event: &str| events.iter().filter(|e| *e == event).count();
+    assert_eq!(count("down CapsLock"), 0);
+    assert_eq!(count("down DeleteOrBackspace"), 1);
+    assert_eq!(count("down L"), 2);
+    assert!(events.contains(&"char é".to_owned()));
+}
+
+#[test]
+fn left_and_right_modifiers() {
+    let policy = Policy {
+        denied_hotkeys: vec!["ctrl+alt+deleteorbackspace".parse().unwrap()],
+        ..Default::default()
+    };
+    let mut ctx = PolicyContext::new(FakeContext::default(), policy);
+
+    // Releasing the right key doesn't release the left one.
+    ctx.key_down(Key::Control).unwrap();
+    ctx.key_down(Key::RightControl).unwrap();
+    ctx.key_up(Key::RightControl).unwrap();
+    ctx.key_down(Key::Alt).unwrap();
+    assert!(is_denied(ctx.key_click(Key::DeleteOrBackspace)));
+}
+
+#[test]
+fn confine() {
+    let policy = Policy {
+        confine: Some(Rect::new(100, 100, 200, 100)),
+        ..Default::default()
+    };
+    let mut ctx = PolicyContext::new(FakeContext::default(), policy);
+
+    // The cursor starts outside of the area.
+    assert!(is_denied(ctx.mouse_click(MouseButton::Left)));
+    assert!(is_denied(ctx.mouse_down(MouseButton::Left)));
+    assert!(is_denied(ctx.mouse_up(MouseButton::Left)));
+    assert!(is_denied(ctx.mouse_scroll(0, 120)));
+
+    assert!(is_denied(ctx.mouse_move_abs(0, 0)));
+    ctx.mouse_move_abs(150, 150).unwrap();
+    ctx.mouse_click(MouseButton::Left).unwrap();
+    ctx.mouse_scroll(0, 120).unwrap();
+    ctx.mouse_move_rel(1000, -1000).unwrap();
+    assert_eq!(ctx.cursor_location().unwrap(), (299, 100));
+    ctx.mouse_move_rel(-10, 10).unwrap();
+    assert_eq!(ctx.cursor_location().unwrap(), (289, 110));
+}
+
+#[test]
+fn commands() {
+    let policy = Policy {
+        max_text_length: Some(5),
+        max_commands_per_second: Some(3),
+        allowed_commands: Some(vec![CommandCode::KeyClick, CommandCode::UnicodeString]),
+        ..Default::default()
+    };
+    let mut ctx = PolicyContext::new(FakeContext::default(), policy);
+
+    ctx.check_command(&Command::KeyClick(Key::A)).unwrap();
+    assert!(is_denied(ctx.check_command(&Command::MouseMoveRel(1, 1))));
+    assert!(is_denied(
+        ctx.check_command(&Command::UnicodeString("too long".to_owned()))
+    ));
+    // The rate limit has been reached.
+    assert!(is_denied(ctx.check_command(&Command::KeyClick(Key::A))));
+
+    assert!(is_denied(ctx.unicode_string("too long")));
+    ctx.unicode_string("short").unwrap();
+}
+
+#[test]
+fn executor() {
+    let policy = Policy {
+        max_commands_per_second: Some(4),
+        allowed_commands: Some(vec![CommandCode::KeyDown, CommandCode::KeyClick]),
+        ..Default::default()
+    };
+    let mut ctx = PolicyContext::new(FakeContext::default(), policy);
+    let mut executor = Executor::new();
+
+    // The key that is held when a command is denied is released.
+    let commands = [Command::KeyDown(Key::Shift), Command::MouseMoveRel(1, 1)];
+    assert!(is_denied(executor.execute_policy(&mut ctx, &commands)));
+    assert_eq!(ctx.inner().events, ["down Shift", "up Shift"]);
+    assert_eq!(ctx.inner().cursor, (0, 0));
+
+    // Delays are checked too.
+    let commands = [Command::Delay(0)];
+    assert!(is_denied(executor.execute_policy(&mut ctx, &commands)));
+
+    // The rate limit has been reached.
+    let commands = [Command::KeyClick(Key::A), Command::KeyClick(Key::B)];
+    assert!(is_denied(executor.execute_policy(&mut ctx, &commands)));
+    assert_eq!(ctx.inner().events[2..], ["down A", "up A"]);
+}
+
+#[cfg(feature = "config")]
+#[test]
+fn config() {
+    let policy: Policy = r#"
+        denied_hotkeys = ["meta+l"]
+        allowed_commands = ["keyclick", "MouseMoveRel"]
+    "#
+    .parse()
+    .unwrap();
+    assert_eq!(policy.denied_hotkeys, ["meta+l".parse().unwrap()]);
+    assert_eq!(
+        policy.allowed_commands,
+        Some(vec![CommandCode::KeyClick, CommandCode::MouseMoveRel])
+    );
+    assert_eq!(policy.max_text_length, None);
+
+    assert!("denied_keys = [\"nope\"]".parse::<Policy>().is_err());
+    assert!("unknown = 1".parse::<Policy>().is_err());
+}
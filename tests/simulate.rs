@@ -1,10 +1,8 @@
-use tfc::{traits::*, Context, Key};
+use tfc::{traits::*, ContextBuilder, Key};
 
 #[test]
 fn test_char() -> anyhow::Result<()> {
-    std::env::set_var("DISPLAY", ":0");
-
-    let mut kbd = Context::new()?;
+    let mut kbd = ContextBuilder::new().display(":0").build()?;
     kbd.key_down(Key::Shift)?;
     kbd.unicode_char_down('q')?;
     kbd.unicode_char_up('q')?;
@@ -15,9 +13,7 @@ fn test_char() -> anyhow::Result<()> {
 
 #[test]
 fn test_dead_char() -> anyhow::Result<()> {
-    std::env::set_var("DISPLAY", ":0");
-
-    let mut kbd = Context::new()?;
+    let mut kbd = ContextBuilder::new().display(":0").build()?;
     kbd.unicode_char_down('â')?;
     kbd.unicode_char_up('â')?;
 
@@ -0,0 +1,62 @@
+#![cfg(all(target_os = "linux", feature = "uinput"))]
+
+use std::{fs, thread, time::Duration};
+use tfc::{traits::*, ContextBuilder, Key};
+
+macro_rules! context {
+    ($builder:expr) => {
+        match $builder.build_uinput() {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                eprintln!("/dev/uinput can't be opened ({}). Skipping test.", e);
+                return Ok(());
+            }
+        }
+    };
+}
+
+// The block in /proc/bus/input/devices that describes the device with the
+// given name.
+fn device_info(name: &str) -> Option<String> {
+    let devices = fs::read_to_string("/proc/bus/input/devices").ok()?;
+    let name_line = format!("N: Name=\"{}\"", name);
+    devices
+        .split("\n\n")
+        .find(|block| block.lines().any(|line| line == name_line))
+        .map(str::to_owned)
+}
+
+#[test]
+fn device_identity() -> anyhow::Result<()> {
+    let name = format!("TFC test {}", std::process::id());
+    let builder = ContextBuilder::new()
+        .device_name(&name)
+        .vendor(0x1234)
+        .product(0x5678);
+    let _ctx = context!(builder);
+    thread::sleep(Duration::from_millis(50));
+
+    let info = device_info(&name).expect("The device should be listed");
+    assert!(info.contains("Vendor=1234"));
+    assert!(info.contains("Product=5678"));
+    Ok(())
+}
+
+#[test]
+fn key_events() -> anyhow::Result<()> {
+    let mut ctx = context!(ContextBuilder::new());
+    thread::sleep(Duration::from_millis(10));
+
+    // Shift is used because pressing it on its own doesn't type anything.
+    ctx.key_click(Key::Shift)?;
+    ctx.batch(|ctx| {
+        ctx.key_down(Key::Shift)?;
+        ctx.key_up(Key::Shift)
+    })?;
+
+    // There may not be a compositor to set the LEDs but reading them back
+    // shouldn't fail.
+    ctx.caps_lock()?;
+    ctx.num_lock()?;
+    Ok(())
+}